@@ -18,6 +18,10 @@ fn has_bytes() -> Result<(), Box<dyn Error>> {
     println!("Out {:}", out_dir);
     let mut prost_build = prost_build::Config::new();
     prost_build.protoc_arg("--experimental_allow_proto3_optional");
+    prost_build.type_attribute(
+        ".",
+        "#[cfg_attr(feature = \"serde\", derive(serde::Serialize, serde::Deserialize))]",
+    );
     prost_build.compile_protos(&["./proto/job.proto"], &["./proto/"])?;
 
     let src = Path::new(&out_dir).join("za.co.agriio.job.rs");
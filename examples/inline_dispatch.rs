@@ -0,0 +1,74 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+use tokio_cron_scheduler::{Job, JobScheduler, JobSchedulerError};
+
+const JOBS: usize = 10_000;
+
+///
+/// How many of `JOBS` trivial jobs that are all due at the same time ran, and how long it took
+/// from the first run to the last
+async fn dispatch(inline: bool) -> Result<(usize, Duration), JobSchedulerError> {
+    let mut sched = JobScheduler::new().await?;
+    let runs = Arc::new(AtomicUsize::new(0));
+    for _ in 0..JOBS {
+        let runs = runs.clone();
+        let job = Job::new_one_shot(Duration::from_millis(100), move |_uuid, _l| {
+            runs.fetch_add(1, Ordering::Relaxed);
+        })?;
+        let job_id = sched.add(job).await?;
+        sched.set_inline(&job_id, inline).await?;
+    }
+    sched.start().await?;
+    while runs.load(Ordering::Relaxed) == 0 {
+        tokio::time::sleep(Duration::from_micros(100)).await;
+    }
+    let first = Instant::now();
+    // Fires the runner can't keep up with are missed, so don't wait for all of them forever
+    let mut last = first;
+    let mut ran = 0;
+    while ran < JOBS && last.elapsed() < Duration::from_secs(1) {
+        tokio::time::sleep(Duration::from_micros(100)).await;
+        let now = runs.load(Ordering::Relaxed);
+        if now > ran {
+            ran = now;
+            last = Instant::now();
+        }
+    }
+    sched.shutdown().await?;
+    Ok((ran, last - first))
+}
+
+fn main() {
+    let runtimes = [
+        (
+            "current thread",
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build(),
+        ),
+        (
+            "multi thread",
+            tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build(),
+        ),
+    ];
+    for (name, runtime) in runtimes {
+        let runtime: Runtime = runtime.expect("Could not build the runtime");
+        for inline in [false, true] {
+            let (ran, took) = runtime
+                .block_on(dispatch(inline))
+                .expect("Could not run the jobs");
+            println!(
+                "{}, {}: {} of {} runs in {:?}",
+                name,
+                if inline { "inline" } else { "spawned" },
+                ran,
+                JOBS,
+                took
+            );
+        }
+    }
+}
@@ -27,7 +27,7 @@ pub async fn run_example(sched: &mut JobScheduler) -> Result<Vec<Uuid>, JobSched
     // the job store, but with stopped marking
     five_s_job
         .on_removed_notification_add(
-            &sched,
+            sched,
             Box::new(|job_id, notification_id, type_of_notification| {
                 Box::pin(async move {
                     info!(
@@ -55,7 +55,7 @@ pub async fn run_example(sched: &mut JobScheduler) -> Result<Vec<Uuid>, JobSched
     let four_s_job_async_clone = four_s_job_async.clone();
     let js = sched.clone();
     info!("4s job id {:?}", four_s_job_async.guid());
-    four_s_job_async.on_start_notification_add(&sched, Box::new(move |job_id, notification_id, type_of_notification| {
+    four_s_job_async.on_start_notification_add(sched, Box::new(move |job_id, notification_id, type_of_notification| {
         let four_s_job_async_clone = four_s_job_async_clone.clone();
         let js = js.clone();
         Box::pin(async move {
@@ -67,7 +67,7 @@ pub async fn run_example(sched: &mut JobScheduler) -> Result<Vec<Uuid>, JobSched
 
     four_s_job_async
         .on_done_notification_add(
-            &sched,
+            sched,
             Box::new(|job_id, notification_id, type_of_notification| {
                 Box::pin(async move {
                     info!(
@@ -195,7 +195,7 @@ pub async fn run_example(sched: &mut JobScheduler) -> Result<Vec<Uuid>, JobSched
         utc_job_guid,
         jhb_job_guid,
     ];
-    return Ok(ret);
+    Ok(ret)
 }
 
 pub async fn stop_example(
@@ -0,0 +1,85 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio_cron_scheduler::{Job, JobBuilder, JobScheduler, JobSchedulerError, SharedJobToRunAsync};
+
+/// Keeps track of the bytes allocated and not yet freed
+struct CountingAlloc;
+
+static LIVE: AtomicIsize = AtomicIsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE.fetch_add(layout.size() as isize, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(layout.size() as isize, Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+const JOBS: usize = 100_000;
+
+#[derive(Clone, Copy, Debug)]
+enum Handlers {
+    /// Every job has its own closure
+    Own,
+    /// The jobs share one handler through `with_shared_run_async`
+    Shared,
+    /// Like `Own`, but the handles of the jobs are kept after adding them, so each job keeps its
+    /// data next to the copy in the metadata store
+    KeptHandles,
+}
+
+async fn bytes_per_job(handlers: Handlers) -> Result<isize, JobSchedulerError> {
+    let sched = JobScheduler::new().await?;
+    let shared: Arc<SharedJobToRunAsync> = Arc::new(|_uuid, _l| Box::pin(async move {}));
+    let mut kept = Vec::new();
+    let before = LIVE.load(Ordering::SeqCst);
+    for i in 0..JOBS {
+        let builder = JobBuilder::new()
+            .with_cron_job_type()
+            .with_schedule("0 0 * * * *")?;
+        let builder = match handlers {
+            Handlers::Shared => builder.with_shared_run_async(shared.clone()),
+            Handlers::Own | Handlers::KeptHandles => {
+                builder.with_run_async(Box::new(move |_uuid, _l| {
+                    Box::pin(async move {
+                        let _ = i;
+                    })
+                }))
+            }
+        };
+        let job: Job = builder.build()?;
+        if let Handlers::KeptHandles = handlers {
+            kept.push(job.clone());
+        }
+        sched.add(job).await?;
+    }
+    let kept_size = (kept.capacity() * std::mem::size_of::<Job>()) as isize;
+    let after = LIVE.load(Ordering::SeqCst);
+    Ok((after - before - kept_size) / JOBS as isize)
+}
+
+#[tokio::main]
+async fn main() {
+    for handlers in [Handlers::Own, Handlers::Shared, Handlers::KeptHandles] {
+        let started = Instant::now();
+        let bytes = bytes_per_job(handlers)
+            .await
+            .expect("Could not add the jobs");
+        println!(
+            "{:?}: {} bytes per job for {} jobs, added in {:?}",
+            handlers,
+            bytes,
+            JOBS,
+            started.elapsed()
+        );
+    }
+}
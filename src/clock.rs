@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+///
+/// Where the scheduler gets the time from and how it waits, set with `JobScheduler::set_clock`.
+/// The scheduler loop, the runner and the scheduler's methods go by it, and jobs work out their
+/// first fire by it when they're added. The metadata stores are given its time where they need
+/// one.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+}
+
+///
+/// The system clock and tokio's timer, what the scheduler uses unless it's given another clock
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+///
+/// A clock that goes by tokio's time from where it's started, for tests that pause tokio's time
+/// with `tokio::time::pause` and move it on with `advance` instead of waiting. While tokio's time
+/// is paused and nothing else is to be done, the runtime moves it on to the next sleep that's
+/// due, so a test can sleep through hours of schedule in no time.
+///
+/// ```rust
+/// # use chrono::{TimeZone, Utc};
+/// # use std::sync::atomic::{AtomicUsize, Ordering};
+/// # use std::sync::Arc;
+/// # use std::time::Duration;
+/// # use tokio_cron_scheduler::{Job, JobScheduler, MockClock};
+/// #[tokio::test(start_paused = true)]
+/// async fn fires_every_minute() {
+///     let hits = Arc::new(AtomicUsize::new(0));
+///     let counted = hits.clone();
+///     let clock = MockClock::starting_at(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 30).unwrap());
+///     let mut sched = JobScheduler::new().await.unwrap();
+///     sched.set_clock(Arc::new(clock.clone()));
+///     sched.add(Job::new("0 * * * * *", move |_, _| { counted.fetch_add(1, Ordering::SeqCst); }).unwrap()).await.unwrap();
+///     sched.start().await.unwrap();
+///     clock.advance(Duration::from_secs(60)).await;
+///     assert_eq!(hits.load(Ordering::SeqCst), 1);
+/// }
+/// # fn main() {}
+/// ```
+#[cfg(feature = "test-util")]
+#[derive(Clone, Debug)]
+pub struct MockClock {
+    start: DateTime<Utc>,
+    started: tokio::time::Instant,
+}
+
+#[cfg(feature = "test-util")]
+impl MockClock {
+    ///
+    /// A clock starting at the system time
+    pub fn new() -> Self {
+        Self::starting_at(Utc::now())
+    }
+
+    ///
+    /// A clock starting at `start`
+    pub fn starting_at(start: DateTime<Utc>) -> Self {
+        Self {
+            start,
+            started: tokio::time::Instant::now(),
+        }
+    }
+
+    ///
+    /// Move tokio's paused time, and with it this clock, on by `duration`, waking the sleeps that
+    /// are due by then
+    pub async fn advance(&self, duration: Duration) {
+        tokio::time::advance(duration).await;
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        let elapsed = tokio::time::Instant::now().duration_since(self.started);
+        chrono::Duration::from_std(elapsed)
+            .ok()
+            .and_then(|elapsed| self.start.checked_add_signed(elapsed))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
@@ -1,14 +1,33 @@
+use crate::clock::{Clock, SystemClock};
 #[cfg(not(feature = "has_bytes"))]
 use crate::job::job_data::{JobState, NotificationData};
 #[cfg(feature = "has_bytes")]
 use crate::job::job_data_prost::{JobState, NotificationData};
 use crate::job::to_code::{JobCode, NotificationCode};
-use crate::job::{JobToRunAsync, NotificationId};
-use crate::store::{MetaDataStorage, NotificationStore};
+use crate::job::{
+    cron_steps, AfterJob, Calendar, ConcurrencyLimit, JobQueue, JobToRunAsync, NotificationId,
+    Overlaps, RetryPolicy, RunBudget, RunGap, TokenBucket,
+};
+use crate::job_event::JobEvent;
+use crate::job_scheduler::{
+    AddInterceptor, JobAddedHandler, JobChangedHandler, JobFailureHandler, LateHandler,
+    LeadershipCheck, ScheduleProvider, SharedShutdownNotification, TickHandler,
+};
+use crate::name_conflict::NameConflict;
+use crate::scheduler::TICK_INTERVAL;
+use crate::scheduler_activity::SchedulerActivity;
+use crate::simple::SimpleHistoryStore;
+use crate::stats::SchedulerStats;
+use crate::store::{CheckpointStore, HistoryStore, MetaDataStorage, NotificationStore};
 use crate::{JobSchedulerError, JobStoredData, OnJobNotification};
+use chrono::{DateTime, FixedOffset, Utc};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::broadcast::Sender;
-use tokio::sync::RwLock;
+use tokio::sync::{watch, Notify, RwLock};
+use tracing::{error, Level};
 use uuid::Uuid;
 
 pub type NotificationDeletedResult =
@@ -25,13 +44,112 @@ pub struct Context {
     pub notify_created_tx: Sender<Result<Uuid, (JobSchedulerError, Option<Uuid>)>>,
     pub notify_delete_tx: Sender<(Uuid, Option<Vec<JobState>>)>,
     pub notify_deleted_tx: Sender<NotificationDeletedResult>,
+    pub events_tx: Sender<JobEvent>,
     // TODO need to add when notification was deleted and there's no more references to it
     pub metadata_storage: Arc<RwLock<Box<dyn MetaDataStorage + Send + Sync>>>,
     pub notification_storage: Arc<RwLock<Box<dyn NotificationStore + Send + Sync>>>,
     pub job_code: Arc<RwLock<Box<dyn JobCode + Send + Sync>>>,
     pub notification_code: Arc<RwLock<Box<dyn NotificationCode + Send + Sync>>>,
+    pub stats: Arc<RwLock<SchedulerStats>>,
+    pub job_failure_handler: Arc<RwLock<Option<Box<JobFailureHandler>>>>,
+    pub job_added_handler: Arc<std::sync::RwLock<Option<Box<JobAddedHandler>>>>,
+    /// The code run after a shutdown, see `set_shutdown_handler`. It's taken by the shutdown, so
+    /// it runs once.
+    pub shutdown_handler: Arc<std::sync::RwLock<Option<SharedShutdownNotification>>>,
+    /// The code run when a shutdown starts, see `set_pre_shutdown_hook`. Like the shutdown
+    /// handler it runs once.
+    pub pre_shutdown_hook: Arc<std::sync::RwLock<Option<SharedShutdownNotification>>>,
+    pub job_changed_handler: Arc<std::sync::RwLock<Option<Box<JobChangedHandler>>>>,
+    pub late_handler: Arc<std::sync::RwLock<Option<Box<LateHandler>>>>,
+    pub tick_handler: Arc<std::sync::RwLock<Option<Box<TickHandler>>>>,
+    pub leadership_check: Arc<std::sync::RwLock<Option<Box<LeadershipCheck>>>>,
+    pub add_interceptor: Arc<std::sync::RwLock<Option<Box<AddInterceptor>>>>,
+    pub backpressure: Arc<std::sync::RwLock<Option<watch::Receiver<bool>>>>,
+    /// Turns true once the readiness future set with `set_ready_signal` is done
+    pub ready: Arc<std::sync::RwLock<Option<watch::Receiver<bool>>>>,
+    /// Milliseconds since the epoch of the scheduler loop's last pass, 0 before it started
+    pub heartbeat: Arc<AtomicU64>,
+    /// The clock set with `set_clock`
+    pub clock: Arc<std::sync::RwLock<Arc<dyn Clock>>>,
+    /// Milliseconds since the epoch of when the scheduler loop wakes for its next pass, 0 while
+    /// the loop isn't sleeping towards one
+    pub next_wake: Arc<AtomicU64>,
+    /// Wakes the scheduler loop for a pass before its sleep is over, when the next ticks were
+    /// worked out again
+    pub wake: Arc<Notify>,
+    /// The shortest time between two runs of a job
+    pub min_interval: Arc<RwLock<Duration>>,
+    /// The longest the scheduler loop sleeps between passes, see `set_max_sleep`
+    pub max_sleep: Arc<RwLock<Duration>>,
+    /// How long a shutdown on a signal or a token waits for the running jobs, see
+    /// `set_shutdown_grace`
+    pub shutdown_grace: Arc<RwLock<Option<Duration>>>,
+    pub(crate) concurrency_limit: Arc<ConcurrencyLimit>,
+    /// How many runs of one shot jobs may go at the same time, on top of the global limit
+    pub(crate) one_shot_limit: Arc<ConcurrencyLimit>,
+    /// The concurrency limits of the jobs with a tag, by tag
+    pub(crate) tag_limits: Arc<RwLock<HashMap<String, Arc<ConcurrencyLimit>>>>,
+    pub(crate) tag_gaps: Arc<RwLock<HashMap<String, Arc<RunGap>>>>,
+    pub(crate) tag_rate_limits: Arc<RwLock<HashMap<String, Arc<TokenBucket>>>>,
+    /// The named queues jobs can send their runs to
+    pub(crate) queues: Arc<RwLock<HashMap<String, Arc<JobQueue>>>>,
+    /// The jobs that don't allow overlapping runs with a run going or waiting
+    pub(crate) overlaps: Overlaps,
+    /// How far ahead a cron job added to the scheduler must fire
+    pub schedule_horizon: Arc<RwLock<Duration>>,
+    /// What adding a job with the name of another job does
+    pub name_conflict: Arc<RwLock<NameConflict>>,
+    pub checkpoint_store: Arc<RwLock<Option<Box<dyn CheckpointStore + Send + Sync>>>>,
+    pub history_store: Arc<RwLock<Option<Box<dyn HistoryStore + Send + Sync>>>>,
+    /// The at least once jobs, with how far back their missed fires are run
+    pub at_least_once: Arc<RwLock<HashMap<Uuid, Duration>>>,
+    /// The budgets of jobs that didn't exceed them yet
+    pub budgets: Arc<RwLock<HashMap<Uuid, RunBudget>>>,
+    /// How late after their scheduled time jobs may fire before it's reported
+    pub max_lateness: Arc<RwLock<HashMap<Uuid, Duration>>>,
+    /// How long jobs may go without a run before they're fired anyway, with when their last run
+    /// started, or the limit was set when they didn't run since
+    pub max_silence: Arc<RwLock<HashMap<Uuid, Silence>>>,
+    /// The jobs fired after other jobs instead of on their schedule
+    pub after_jobs: Arc<RwLock<HashMap<Uuid, AfterJob>>>,
+    /// The ids of the jobs named with `JobBuilder::with_name`, by their names
+    pub job_names: Arc<RwLock<HashMap<String, Uuid>>>,
+    /// Exclusions that hold for all the jobs on top of their own calendars
+    pub calendar: Arc<RwLock<Option<Calendar>>>,
+    /// The levels the runs of jobs are logged at
+    pub log_levels: Arc<RwLock<HashMap<Uuid, Level>>>,
+    pub timezone: Arc<RwLock<Option<FixedOffset>>>,
+    /// Extra fires of jobs on top of their schedules
+    pub one_offs: Arc<RwLock<HashMap<Uuid, Vec<DateTime<Utc>>>>>,
+    /// When the jobs fired by their schedules were due, taken by the run that's started for it
+    pub fire_times: Arc<RwLock<HashMap<Uuid, DateTime<Utc>>>>,
+    /// The jobs that don't run again after a failed run until their failure is reset
+    pub skip_after_failure: Arc<RwLock<HashSet<Uuid>>>,
+    /// The jobs run on a task shared by the inline jobs instead of a task of their own
+    pub inline: Arc<RwLock<HashSet<Uuid>>>,
+    /// The jobs made with sync code, whose code is replaced with `update_closure` rather than
+    /// `update_closure_async`
+    pub sync_jobs: Arc<RwLock<HashSet<Uuid>>>,
+    /// The cron jobs that get their schedule from a callback after each fire
+    pub schedule_providers: Arc<RwLock<HashMap<Uuid, Box<ScheduleProvider>>>>,
+    /// The jobs that don't fire until the given time
+    pub paused_until: Arc<RwLock<HashMap<Uuid, DateTime<Utc>>>>,
+    /// The jobs that are removed after their first run that's done, with when they stop retrying
+    pub until_done: Arc<RwLock<HashMap<Uuid, DateTime<Utc>>>>,
+    /// The jobs that are run again after a failed run, with how many attempts failed in a row
+    pub retry_policies: Arc<RwLock<HashMap<Uuid, (RetryPolicy, u32)>>>,
+    /// How many jobs are in the metadata store, updated when jobs are added or removed
+    pub job_count: Arc<watch::Sender<usize>>,
+    /// Set to true when the scheduler starts shutting down, so jobs can wind down on their own
+    pub shutting_down: Arc<watch::Sender<bool>>,
+    /// Whether runs are going, updated when the number of running runs goes from or to zero
+    pub activity: Arc<watch::Sender<SchedulerActivity>>,
 }
 
+///
+/// How long a job may go without a run, and when its last run started
+pub type Silence = (Duration, DateTime<Utc>);
+
 impl Context {
     pub fn new(
         metadata_storage: Arc<RwLock<Box<dyn MetaDataStorage + Send + Sync>>>,
@@ -49,6 +167,7 @@ impl Context {
         let (notify_created_tx, _notify_created_rx) = tokio::sync::broadcast::channel(200);
         let (notify_delete_tx, _notify_delete_rx) = tokio::sync::broadcast::channel(200);
         let (notify_deleted_tx, _notify_deleted_rx) = tokio::sync::broadcast::channel(200);
+        let (events_tx, _events_rx) = tokio::sync::broadcast::channel(200);
 
         Self {
             job_activation_tx,
@@ -61,10 +180,163 @@ impl Context {
             notify_created_tx,
             notify_delete_tx,
             notify_deleted_tx,
+            events_tx,
             metadata_storage,
             notification_storage,
             job_code,
             notification_code,
+            stats: Arc::new(RwLock::new(SchedulerStats::default())),
+            job_failure_handler: Arc::new(RwLock::new(None)),
+            job_added_handler: Arc::new(std::sync::RwLock::new(None)),
+            shutdown_handler: Arc::new(std::sync::RwLock::new(None)),
+            pre_shutdown_hook: Arc::new(std::sync::RwLock::new(None)),
+            job_changed_handler: Arc::new(std::sync::RwLock::new(None)),
+            late_handler: Arc::new(std::sync::RwLock::new(None)),
+            tick_handler: Arc::new(std::sync::RwLock::new(None)),
+            leadership_check: Arc::new(std::sync::RwLock::new(None)),
+            add_interceptor: Arc::new(std::sync::RwLock::new(None)),
+            backpressure: Arc::new(std::sync::RwLock::new(None)),
+            ready: Arc::new(std::sync::RwLock::new(None)),
+            heartbeat: Arc::new(AtomicU64::new(0)),
+            clock: Arc::new(std::sync::RwLock::new(Arc::new(SystemClock))),
+            next_wake: Arc::new(AtomicU64::new(0)),
+            wake: Arc::new(Notify::new()),
+            min_interval: Arc::new(RwLock::new(Duration::from_millis(1))),
+            max_sleep: Arc::new(RwLock::new(TICK_INTERVAL)),
+            shutdown_grace: Arc::new(RwLock::new(None)),
+            concurrency_limit: Arc::new(ConcurrencyLimit::default()),
+            one_shot_limit: Arc::new(ConcurrencyLimit::default()),
+            tag_limits: Arc::new(RwLock::new(HashMap::new())),
+            tag_gaps: Arc::new(RwLock::new(HashMap::new())),
+            tag_rate_limits: Arc::new(RwLock::new(HashMap::new())),
+            queues: Arc::new(RwLock::new(HashMap::new())),
+            overlaps: Arc::new(RwLock::new(HashMap::new())),
+            schedule_horizon: Arc::new(RwLock::new(cron_steps::SCHEDULE_HORIZON)),
+            name_conflict: Arc::new(RwLock::new(NameConflict::default())),
+            checkpoint_store: Arc::new(RwLock::new(None)),
+            history_store: Arc::new(RwLock::new(Some(Box::new(SimpleHistoryStore::default())))),
+            at_least_once: Arc::new(RwLock::new(HashMap::new())),
+            budgets: Arc::new(RwLock::new(HashMap::new())),
+            max_lateness: Arc::new(RwLock::new(HashMap::new())),
+            max_silence: Arc::new(RwLock::new(HashMap::new())),
+            after_jobs: Arc::new(RwLock::new(HashMap::new())),
+            job_names: Arc::new(RwLock::new(HashMap::new())),
+            calendar: Arc::new(RwLock::new(None)),
+            log_levels: Arc::new(RwLock::new(HashMap::new())),
+            timezone: Arc::new(RwLock::new(None)),
+            one_offs: Arc::new(RwLock::new(HashMap::new())),
+            fire_times: Arc::new(RwLock::new(HashMap::new())),
+            skip_after_failure: Arc::new(RwLock::new(HashSet::new())),
+            inline: Arc::new(RwLock::new(HashSet::new())),
+            sync_jobs: Arc::new(RwLock::new(HashSet::new())),
+            schedule_providers: Arc::new(RwLock::new(HashMap::new())),
+            paused_until: Arc::new(RwLock::new(HashMap::new())),
+            until_done: Arc::new(RwLock::new(HashMap::new())),
+            retry_policies: Arc::new(RwLock::new(HashMap::new())),
+            job_count: Arc::new(watch::channel(0).0),
+            shutting_down: Arc::new(watch::channel(false).0),
+            activity: Arc::new(watch::channel(SchedulerActivity::Idle).0),
+        }
+    }
+
+    ///
+    /// Whether the backpressure signal says to hold off firing jobs
+    pub(crate) fn backpressured(&self) -> bool {
+        self.backpressure
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map(|rx| *rx.borrow())
+            .unwrap_or_default()
+    }
+
+    ///
+    /// The clock the scheduler goes by
+    pub(crate) fn clock(&self) -> Arc<dyn Clock> {
+        self.clock.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    ///
+    /// The time by the scheduler's clock
+    pub(crate) fn now(&self) -> DateTime<Utc> {
+        self.clock().now()
+    }
+
+    ///
+    /// Send an event to the event streams, stamped by the scheduler's clock
+    pub(crate) fn send_event(
+        &self,
+        job_id: Uuid,
+        name: String,
+        state: JobState,
+        error: Option<String>,
+    ) {
+        // Fails only when nobody's listening
+        let _ = self.events_tx.send(JobEvent {
+            job_id,
+            name,
+            state,
+            at: self.now(),
+            error,
+        });
+    }
+
+    ///
+    /// Whether the readiness future set with `set_ready_signal` is done, or there is none
+    pub(crate) fn ready(&self) -> bool {
+        self.ready
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map(|rx| *rx.borrow())
+            .unwrap_or(true)
+    }
+
+    ///
+    /// Tell the activity watchers when the number of running runs went from or to zero
+    pub(crate) fn update_activity(&self, running: usize) {
+        let activity = if running == 0 {
+            SchedulerActivity::Idle
+        } else {
+            SchedulerActivity::Busy
+        };
+        self.activity.send_if_modified(|current| {
+            let changed = *current != activity;
+            *current = activity;
+            changed
+        });
+    }
+
+    ///
+    /// Keep the name a job has now, an empty name keeps none
+    pub(crate) async fn name_job(&self, job_id: Uuid, name: &str) {
+        let mut w = self.job_names.write().await;
+        w.retain(|_, id| *id != job_id);
+        if !name.is_empty() {
+            w.insert(name.to_string(), job_id);
+        }
+    }
+
+    ///
+    /// Count the jobs in the metadata store again for the watchers of the job count. The store
+    /// lists all jobs for that, so it's left out while nobody watches.
+    pub(crate) async fn update_job_count(&self) {
+        if self.job_count.receiver_count() == 0 {
+            return;
+        }
+        let jobs = {
+            let mut r = self.metadata_storage.write().await;
+            r.list_jobs().await
+        };
+        match jobs {
+            Ok(jobs) => {
+                self.job_count.send_if_modified(|count| {
+                    let changed = *count != jobs.len();
+                    *count = jobs.len();
+                    changed
+                });
+            }
+            Err(e) => error!("Error counting the jobs {:?}", e),
         }
     }
 }
@@ -82,10 +354,62 @@ impl Clone for Context {
             notify_created_tx: self.notify_created_tx.clone(),
             notify_delete_tx: self.notify_delete_tx.clone(),
             notify_deleted_tx: self.notify_deleted_tx.clone(),
+            events_tx: self.events_tx.clone(),
             metadata_storage: self.metadata_storage.clone(),
             notification_storage: self.notification_storage.clone(),
             job_code: self.job_code.clone(),
             notification_code: self.notification_code.clone(),
+            stats: self.stats.clone(),
+            job_failure_handler: self.job_failure_handler.clone(),
+            job_added_handler: self.job_added_handler.clone(),
+            shutdown_handler: self.shutdown_handler.clone(),
+            pre_shutdown_hook: self.pre_shutdown_hook.clone(),
+            job_changed_handler: self.job_changed_handler.clone(),
+            late_handler: self.late_handler.clone(),
+            tick_handler: self.tick_handler.clone(),
+            leadership_check: self.leadership_check.clone(),
+            add_interceptor: self.add_interceptor.clone(),
+            backpressure: self.backpressure.clone(),
+            ready: self.ready.clone(),
+            heartbeat: self.heartbeat.clone(),
+            clock: self.clock.clone(),
+            next_wake: self.next_wake.clone(),
+            wake: self.wake.clone(),
+            min_interval: self.min_interval.clone(),
+            max_sleep: self.max_sleep.clone(),
+            shutdown_grace: self.shutdown_grace.clone(),
+            concurrency_limit: self.concurrency_limit.clone(),
+            one_shot_limit: self.one_shot_limit.clone(),
+            tag_limits: self.tag_limits.clone(),
+            tag_gaps: self.tag_gaps.clone(),
+            tag_rate_limits: self.tag_rate_limits.clone(),
+            queues: self.queues.clone(),
+            overlaps: self.overlaps.clone(),
+            schedule_horizon: self.schedule_horizon.clone(),
+            name_conflict: self.name_conflict.clone(),
+            checkpoint_store: self.checkpoint_store.clone(),
+            history_store: self.history_store.clone(),
+            at_least_once: self.at_least_once.clone(),
+            budgets: self.budgets.clone(),
+            max_lateness: self.max_lateness.clone(),
+            max_silence: self.max_silence.clone(),
+            after_jobs: self.after_jobs.clone(),
+            job_names: self.job_names.clone(),
+            calendar: self.calendar.clone(),
+            log_levels: self.log_levels.clone(),
+            timezone: self.timezone.clone(),
+            one_offs: self.one_offs.clone(),
+            fire_times: self.fire_times.clone(),
+            skip_after_failure: self.skip_after_failure.clone(),
+            inline: self.inline.clone(),
+            sync_jobs: self.sync_jobs.clone(),
+            schedule_providers: self.schedule_providers.clone(),
+            paused_until: self.paused_until.clone(),
+            until_done: self.until_done.clone(),
+            retry_policies: self.retry_policies.clone(),
+            job_count: self.job_count.clone(),
+            shutting_down: self.shutting_down.clone(),
+            activity: self.activity.clone(),
         }
     }
 }
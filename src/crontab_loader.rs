@@ -0,0 +1,226 @@
+use crate::job::{parse_cron, JobBuilder};
+use crate::job_scheduler::JobsSchedulerLocked;
+use crate::{JobSchedulerError, SharedJobToRunAsync};
+use cron::Schedule;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::{Mutex, Notify};
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+use uuid::Uuid;
+
+///
+/// Jobs declared in a crontab like file instead of in code. Every line that isn't empty or a
+/// `#` comment is a schedule followed by the name of a handler registered with `with_handler`:
+///
+/// ```text
+/// # sec min hour day month weekday
+/// 0 */5 * * * *   sync_accounts
+/// 0 30 6 * * *    nightly_report
+/// ```
+///
+/// The jobs are added with the handler's name as their name. `reload` makes the scheduler match
+/// the file again: jobs for new lines are added, jobs whose line is gone are removed and jobs
+/// whose schedule changed are rescheduled in place. `watch` reloads whenever the file changes.
+///
+/// ```rust,no_run
+/// # use std::sync::Arc;
+/// # use std::time::Duration;
+/// # use tokio_cron_scheduler::{CrontabLoader, JobScheduler, SharedJobToRunAsync};
+/// # async fn example(mut sched: JobScheduler, sync_accounts: Arc<SharedJobToRunAsync>, nightly_report: Arc<SharedJobToRunAsync>) -> Result<(), Box<dyn std::error::Error>> {
+/// let loader = CrontabLoader::new(sched.clone(), "/etc/myapp/crontab")
+///     .with_handler("sync_accounts", sync_accounts)
+///     .with_handler("nightly_report", nightly_report);
+/// loader.reload().await?;
+/// loader.watch(Duration::from_secs(10)).await;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct CrontabLoader {
+    scheduler: JobsSchedulerLocked,
+    path: PathBuf,
+    handlers: HashMap<String, Arc<SharedJobToRunAsync>>,
+    loaded: Arc<Mutex<HashMap<String, (Uuid, Schedule)>>>,
+    stop: Arc<Notify>,
+    task: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+///
+/// What a `CrontabLoader::reload` changed, by handler name
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CrontabChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub rescheduled: Vec<String>,
+}
+
+impl CrontabChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.rescheduled.is_empty()
+    }
+}
+
+impl CrontabLoader {
+    pub fn new<P: Into<PathBuf>>(scheduler: JobsSchedulerLocked, path: P) -> Self {
+        Self {
+            scheduler,
+            path: path.into(),
+            handlers: HashMap::new(),
+            loaded: Arc::new(Mutex::new(HashMap::new())),
+            stop: Arc::new(Notify::new()),
+            task: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    ///
+    /// Run `handler` for the lines of the file naming `name`
+    pub fn with_handler<S: Into<String>>(
+        mut self,
+        name: S,
+        handler: Arc<SharedJobToRunAsync>,
+    ) -> Self {
+        self.handlers.insert(name.into(), handler);
+        self
+    }
+
+    ///
+    /// Parse the lines of a crontab, checking their schedules and that their handlers are
+    /// registered. A handler can be on one line only.
+    pub fn parse(&self, contents: &str) -> Result<Vec<(String, Schedule)>, JobSchedulerError> {
+        let mut lines: Vec<(String, Schedule)> = vec![];
+        for (n, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid =
+                |why: String| JobSchedulerError::InvalidCrontab(format!("line {}: {}", n + 1, why));
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let (name, schedule) = match fields.split_last() {
+                Some((name, schedule)) if !schedule.is_empty() => (*name, schedule.join(" ")),
+                _ => return Err(invalid("expected a schedule and a handler".to_string())),
+            };
+            if !self.handlers.contains_key(name) {
+                return Err(invalid(format!("no handler named {}", name)));
+            }
+            if lines.iter().any(|(other, _)| other == name) {
+                return Err(invalid(format!("{} is on more than one line", name)));
+            }
+            let schedule =
+                parse_cron(&schedule).map_err(|e| invalid(format!("{}: {:?}", schedule, e)))?;
+            lines.push((name.to_string(), schedule));
+        }
+        Ok(lines)
+    }
+
+    ///
+    /// Read the file and make the scheduler's jobs match it. When the file can't be read or one
+    /// of its lines is wrong nothing is changed.
+    pub async fn reload(&self) -> Result<CrontabChanges, JobSchedulerError> {
+        let path = self.path.clone();
+        let contents = tokio::task::spawn_blocking(move || std::fs::read_to_string(path))
+            .await
+            .map_err(|e| JobSchedulerError::InvalidCrontab(e.to_string()))?
+            .map_err(|e| {
+                JobSchedulerError::InvalidCrontab(format!("{}: {}", self.path.display(), e))
+            })?;
+        let lines = self.parse(&contents)?;
+
+        let mut loaded = self.loaded.lock().await;
+        let mut changes = CrontabChanges::default();
+        let gone = loaded
+            .keys()
+            .filter(|name| !lines.iter().any(|(line, _)| line == *name))
+            .cloned()
+            .collect::<Vec<_>>();
+        for name in gone {
+            if let Some((job_id, _)) = loaded.remove(&name) {
+                self.scheduler.remove(&job_id).await?;
+                changes.removed.push(name);
+            }
+        }
+        for (name, schedule) in lines {
+            match loaded.get_mut(&name) {
+                Some((_, current)) if current.to_string() == schedule.to_string() => {}
+                Some((job_id, current)) => {
+                    self.scheduler.reschedule(job_id, schedule.clone()).await?;
+                    *current = schedule;
+                    changes.rescheduled.push(name);
+                }
+                None => {
+                    let job = JobBuilder::new()
+                        .with_name(name.clone())
+                        .with_cron_job_type()
+                        .with_schedule(schedule.clone())?
+                        .with_shared_run_async(self.handlers[&name].clone())
+                        .build()?;
+                    let job_id = self.scheduler.add(job).await?;
+                    loaded.insert(name.clone(), (job_id, schedule));
+                    changes.added.push(name);
+                }
+            }
+        }
+        Ok(changes)
+    }
+
+    ///
+    /// Check the file's modification time `every` so often and reload it when it changed, until
+    /// `stop` is called. A reload that fails is logged and leaves the jobs as they were.
+    pub async fn watch(&self, every: Duration) {
+        let loader = self.clone();
+        let task = tokio::spawn(async move {
+            let mut seen = loader.modified();
+            loop {
+                if tokio::time::timeout(every, loader.stop.notified())
+                    .await
+                    .is_ok()
+                {
+                    break;
+                }
+                let modified = loader.modified();
+                if modified == seen {
+                    continue;
+                }
+                seen = modified;
+                match loader.reload().await {
+                    Ok(changes) if !changes.is_empty() => {
+                        info!("Reloaded {}: {:?}", loader.path.display(), changes)
+                    }
+                    Ok(_) => {}
+                    Err(e) => error!("Error reloading {} {:?}", loader.path.display(), e),
+                }
+            }
+        });
+        let old = {
+            let mut w = self.task.lock().await;
+            w.replace(task)
+        };
+        if let Some(old) = old {
+            old.abort();
+        }
+    }
+
+    ///
+    /// Stop watching the file. The jobs it added stay in the scheduler.
+    pub async fn stop(&self) {
+        self.stop.notify_one();
+        let task = {
+            let mut w = self.task.lock().await;
+            w.take()
+        };
+        if let Some(task) = task {
+            if let Err(e) = task.await {
+                error!("Error stopping the crontab watch {:?}", e);
+            }
+        }
+    }
+
+    fn modified(&self) -> Option<SystemTime> {
+        std::fs::metadata(&self.path)
+            .and_then(|meta| meta.modified())
+            .ok()
+    }
+}
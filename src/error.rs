@@ -1,5 +1,6 @@
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub enum JobSchedulerError {
@@ -25,11 +26,32 @@ pub enum JobSchedulerError {
     NoNextTick,
     CantListGuids,
     CantListNextTicks,
+    CantListJobs,
     NotifyOnStateError,
     ParseSchedule,
     JobTypeNotSet,
     RunOrRunAsyncNotSet,
     ScheduleNotSet,
+    JobNotFound,
+    NameTaken(String),
+    ParseHashedSchedule(String),
+    InvalidCron(String),
+    NoCheckpointStore,
+    NoHistoryStore,
+    NotOneShot,
+    InvalidTimezone(String),
+    UnsatisfiableSchedule(String),
+    WaitTimedOut,
+    JobPanicked(String),
+    RunFailed(String),
+    RunCancelled,
+    RunTimedOut(Duration),
+    RunBudgetExceeded(String),
+    JobRejected(String),
+    InvalidTrigger(String),
+    InvalidCrontab(String),
+    LeaseFailed(String),
+    ClosureKindMismatch(String),
     #[cfg(feature = "nats_storage")]
     BuilderNeedsField(String),
     #[cfg(feature = "nats_storage")]
@@ -0,0 +1,144 @@
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::JobState;
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::JobState;
+use crate::stats::SkipReason;
+use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
+use std::time::Duration;
+use uuid::Uuid;
+
+// The names differ from the ones in `metrics_text`, so both can go out on the same endpoint
+const JOBS: &str = "tokio_cron_jobs";
+const RUNNING: &str = "tokio_cron_runs_running";
+const RUNS_STARTED: &str = "tokio_cron_runs_started_total";
+const RUNS_COMPLETED: &str = "tokio_cron_runs_completed_total";
+const RUNS_FAILED: &str = "tokio_cron_runs_failed_total";
+const RUNS_SKIPPED: &str = "tokio_cron_runs_skipped_total";
+const RUNS_THROTTLED: &str = "tokio_cron_runs_throttled_total";
+const RUN_DURATION: &str = "tokio_cron_run_duration_seconds";
+const FIRE_LATENESS: &str = "tokio_cron_fire_lateness_seconds";
+const TICKS: &str = "tokio_cron_ticks_total";
+const TICK_DURATION: &str = "tokio_cron_tick_duration_seconds";
+const FIRES: &str = "tokio_cron_fires_total";
+const ACTIVATION_QUEUE: &str = "tokio_cron_activation_queue_depth";
+const NOTIFICATIONS: &str = "tokio_cron_notifications_total";
+const NOTIFICATION_CALLBACKS: &str = "tokio_cron_notification_callbacks_total";
+const NOTIFICATION_QUEUE: &str = "tokio_cron_notification_queue_depth";
+
+///
+/// Describe the metrics the scheduler records through the `metrics` facade to the recorder.
+/// Call it after the recorder is installed, the metrics are recorded without it as well.
+///
+/// ```rust
+/// // Once a recorder is installed, e.g. with metrics_exporter_prometheus::PrometheusBuilder
+/// tokio_cron_scheduler::describe_metrics();
+/// ```
+pub fn describe_metrics() {
+    describe_gauge!(JOBS, "Number of jobs in the scheduler");
+    describe_gauge!(RUNNING, "Number of runs going");
+    describe_counter!(RUNS_STARTED, "Number of runs started per job");
+    describe_counter!(RUNS_COMPLETED, "Number of runs per job that went well");
+    describe_counter!(RUNS_FAILED, "Number of runs per job that failed");
+    describe_counter!(
+        RUNS_SKIPPED,
+        "Number of fires per job that didn't lead to a run, by why"
+    );
+    describe_counter!(
+        RUNS_THROTTLED,
+        "Number of runs per job a rate limit delayed or skipped, by tag"
+    );
+    describe_histogram!(
+        RUN_DURATION,
+        metrics::Unit::Seconds,
+        "How long the runs of a job took"
+    );
+    describe_histogram!(
+        FIRE_LATENESS,
+        metrics::Unit::Seconds,
+        "How long after its scheduled time a job fired"
+    );
+    describe_counter!(TICKS, "Number of passes of the scheduler loop");
+    describe_histogram!(
+        TICK_DURATION,
+        metrics::Unit::Seconds,
+        "How long a pass of the scheduler loop took"
+    );
+    describe_counter!(FIRES, "Number of fires sent to the job runner");
+    describe_gauge!(
+        ACTIVATION_QUEUE,
+        "Number of fires waiting for the job runner"
+    );
+    describe_counter!(NOTIFICATIONS, "Number of job notifications, by state");
+    describe_counter!(
+        NOTIFICATION_CALLBACKS,
+        "Number of notification callbacks run, by state"
+    );
+    describe_gauge!(
+        NOTIFICATION_QUEUE,
+        "Number of job notifications waiting for the notification runner"
+    );
+}
+
+pub(crate) fn jobs(count: usize) {
+    gauge!(JOBS).set(count as f64);
+}
+
+pub(crate) fn run_started(job_id: Uuid, running: usize) {
+    counter!(RUNS_STARTED, "id" => job_id.to_string()).increment(1);
+    gauge!(RUNNING).set(running as f64);
+}
+
+pub(crate) fn run_finished(job_id: Uuid, failed: bool, duration: Duration, running: usize) {
+    let id = job_id.to_string();
+    if failed {
+        counter!(RUNS_FAILED, "id" => id.clone()).increment(1);
+    } else {
+        counter!(RUNS_COMPLETED, "id" => id.clone()).increment(1);
+    }
+    histogram!(RUN_DURATION, "id" => id).record(duration.as_secs_f64());
+    gauge!(RUNNING).set(running as f64);
+}
+
+pub(crate) fn run_skipped(job_id: Uuid, reason: SkipReason) {
+    let reason = match reason {
+        SkipReason::Overlap => "overlap",
+        SkipReason::AfterFailure => "after_failure",
+        SkipReason::QueueFull => "queue_full",
+        SkipReason::Excluded => "excluded",
+        SkipReason::RateLimited => "rate_limited",
+    };
+    counter!(RUNS_SKIPPED, "id" => job_id.to_string(), "reason" => reason).increment(1);
+}
+
+pub(crate) fn run_throttled(job_id: Uuid, tag: &str, skipped: bool) {
+    let action = if skipped { "skip" } else { "delay" };
+    counter!(
+        RUNS_THROTTLED,
+        "id" => job_id.to_string(),
+        "tag" => tag.to_string(),
+        "action" => action
+    )
+    .increment(1);
+}
+
+pub(crate) fn fired_late(job_id: Uuid, lateness: Duration) {
+    histogram!(FIRE_LATENESS, "id" => job_id.to_string()).record(lateness.as_secs_f64());
+}
+
+pub(crate) fn tick(took: Duration, fired: usize, queued: usize) {
+    counter!(TICKS).increment(1);
+    histogram!(TICK_DURATION).record(took.as_secs_f64());
+    counter!(FIRES).increment(fired as u64);
+    gauge!(ACTIVATION_QUEUE).set(queued as f64);
+}
+
+pub(crate) fn activation_received(queued: usize) {
+    gauge!(ACTIVATION_QUEUE).set(queued as f64);
+}
+
+pub(crate) fn notification(state: JobState, callbacks: usize, queued: usize) {
+    let state = format!("{:?}", state);
+    counter!(NOTIFICATIONS, "state" => state.clone()).increment(1);
+    counter!(NOTIFICATION_CALLBACKS, "state" => state).increment(callbacks as u64);
+    gauge!(NOTIFICATION_QUEUE).set(queued as f64);
+}
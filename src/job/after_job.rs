@@ -0,0 +1,33 @@
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::After;
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::After;
+use std::time::Duration;
+use uuid::Uuid;
+
+///
+/// Fire a job `delay` after each run of the job `job_id` that is done, instead of on its own
+/// schedule, see `set_after_job`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AfterJob {
+    pub job_id: Uuid,
+    pub delay: Duration,
+}
+
+impl AfterJob {
+    pub(crate) fn from_stored(after: &After) -> Option<Self> {
+        Some(Self {
+            job_id: after.job_id.as_ref()?.into(),
+            delay: Duration::from_millis(after.delay_ms),
+        })
+    }
+}
+
+impl From<AfterJob> for After {
+    fn from(after: AfterJob) -> Self {
+        Self {
+            job_id: Some(after.job_id.into()),
+            delay_ms: after.delay.as_millis().min(u64::MAX as u128) as u64,
+        }
+    }
+}
@@ -1,10 +1,20 @@
 use crate::job::cron_job::CronJob;
+use crate::job::cron_steps::{parse_cron, validate_steps};
+use crate::job::hashed_schedule;
 #[cfg(not(feature = "has_bytes"))]
 pub use crate::job::job_data::{JobStoredData, JobType, Uuid};
 #[cfg(feature = "has_bytes")]
 pub use crate::job::job_data_prost::{JobStoredData, JobType, Uuid};
-use crate::job::{nop, nop_async, JobLocked};
-use crate::{JobSchedulerError, JobToRun, JobToRunAsync};
+use crate::job::non_cron_job::NonCronJob;
+use crate::job::random;
+use crate::job::{
+    named_timezone, nop, nop_async, AfterJob, Calendar, FirstFire, IntervalSchedule, JobLocked,
+    MisfirePolicy, OverlapPolicy,
+};
+use crate::{
+    JobContext, JobPayload, JobSchedulerError, JobToRun, JobToRunAsync, JobToRunWithContext,
+    SharedJobToRunAsync,
+};
 use chrono::{Offset, TimeZone, Utc};
 use core::time::Duration;
 use cron::Schedule;
@@ -29,6 +39,27 @@ pub struct JobBuilder<T> {
     pub duration: Option<Duration>,
     pub repeating: Option<bool>,
     pub instant: Option<Instant>,
+    pub overlap: Option<OverlapPolicy>,
+    pub start_immediately: Option<bool>,
+    pub stable_jitter: Option<Duration>,
+    pub random_jitter: Option<Duration>,
+    pub extra: Option<Vec<u8>>,
+    pub tags: Option<Vec<String>>,
+    pub queue: Option<String>,
+    pub order_key: Option<i64>,
+    pub misfire: Option<MisfirePolicy>,
+    pub timeout: Option<Duration>,
+    pub name: Option<String>,
+    pub priority: Option<i32>,
+    pub after: Option<AfterJob>,
+    pub calendar: Option<Calendar>,
+    pub interval: Option<IntervalSchedule>,
+}
+
+impl Default for JobBuilder<Utc> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl JobBuilder<Utc> {
@@ -43,11 +74,26 @@ impl JobBuilder<Utc> {
             duration: None,
             repeating: None,
             instant: None,
+            overlap: None,
+            start_immediately: None,
+            stable_jitter: None,
+            random_jitter: None,
+            extra: None,
+            tags: None,
+            queue: None,
+            order_key: None,
+            misfire: None,
+            timeout: None,
+            name: None,
+            priority: None,
+            after: None,
+            calendar: None,
+            interval: None,
         }
     }
 }
 
-impl<T: TimeZone> JobBuilder<T> {
+impl<T: TimeZone + 'static> JobBuilder<T> {
     pub fn with_timezone<U: TimeZone>(self, timezone: U) -> JobBuilder<U> {
         JobBuilder {
             timezone: Some(timezone),
@@ -59,6 +105,21 @@ impl<T: TimeZone> JobBuilder<T> {
             duration: self.duration,
             repeating: self.repeating,
             instant: self.instant,
+            overlap: self.overlap,
+            start_immediately: self.start_immediately,
+            stable_jitter: self.stable_jitter,
+            random_jitter: self.random_jitter,
+            extra: self.extra,
+            tags: self.tags,
+            queue: self.queue,
+            order_key: self.order_key,
+            misfire: self.misfire,
+            timeout: self.timeout,
+            name: self.name,
+            priority: self.priority,
+            after: self.after,
+            calendar: self.calendar,
+            interval: self.interval,
         }
     }
 
@@ -69,6 +130,13 @@ impl<T: TimeZone> JobBuilder<T> {
         }
     }
 
+    ///
+    /// Give the job a fixed id instead of a random one, so it keeps the same id across restarts.
+    /// Adding a job with the id of a job that is already in the scheduler replaces that job.
+    pub fn with_id(self, job_id: UuidUuid) -> Self {
+        self.with_job_id(job_id.into())
+    }
+
     pub fn with_job_type(self, job_type: JobType) -> Self {
         Self {
             job_type: Some(job_type),
@@ -105,22 +173,249 @@ impl<T: TimeZone> JobBuilder<T> {
         let schedule: Schedule = schedule
             .try_into()
             .map_err(|_| JobSchedulerError::ParseSchedule)?;
+        validate_steps(&schedule.to_string())?;
+        Ok(Self {
+            schedule: Some(schedule),
+            ..self
+        })
+    }
+
+    ///
+    /// Set a cron schedule that can use Jenkins-style `H` elements, for instance `0 H H(0-5) * * *`.
+    /// Each `H` is replaced by a value derived from the job id, so that jobs with the same schedule
+    /// are spread out while a job keeps the same times. Set the job id first with `with_job_id` for
+    /// the times to stay the same across restarts.
+    pub fn with_hashed_schedule(self, schedule: &str) -> Result<Self, JobSchedulerError> {
+        let job_id = self
+            .job_id
+            .clone()
+            .unwrap_or_else(|| UuidUuid::new_v4().into());
+        let expanded = hashed_schedule::expand(schedule, &(&job_id).into())?;
+        let schedule = parse_cron(&expanded)?;
         Ok(Self {
+            job_id: Some(job_id),
             schedule: Some(schedule),
             ..self
         })
     }
 
+    ///
+    /// Move every fire of the job by a number of whole seconds within `window`, derived from the
+    /// job id. Jobs on the same schedule are spread over the window, while a job keeps the same
+    /// offset, also after a restart when its job id is set with `with_job_id`.
+    pub fn with_stable_jitter(self, window: Duration) -> Self {
+        Self {
+            stable_jitter: Some(window),
+            ..self
+        }
+    }
+
+    ///
+    /// Move every fire of a cron job by a random number of whole seconds within `window`, drawn
+    /// again for every fire, so jobs on the same schedule across instances don't all fire at once.
+    /// The delay is part of the job's next tick, so the scheduler sleeps until the moved fire.
+    /// Keep the window shorter than the time between fires, a delay past the next fire passes it
+    /// over. A repeated job is moved once, on its first fire, and keeps its interval from there.
+    pub fn with_jitter(self, window: Duration) -> Self {
+        Self {
+            random_jitter: Some(window),
+            ..self
+        }
+    }
+
+    pub fn with_overlap_policy(self, overlap: OverlapPolicy) -> Self {
+        Self {
+            overlap: Some(overlap),
+            ..self
+        }
+    }
+
+    ///
+    /// What happens with the fires of the job that were missed, for instance while the scheduler
+    /// was down, see `MisfirePolicy`
+    pub fn with_misfire_policy(self, misfire: MisfirePolicy) -> Self {
+        Self {
+            misfire: Some(misfire),
+            ..self
+        }
+    }
+
+    ///
+    /// Cancel a run of the job that takes longer than `timeout`. The run fails with
+    /// `JobSchedulerError::RunTimedOut` and sends `JobState::TimedOut`.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    ///
+    /// Give the job a name that stays the same across restarts, to look it up with
+    /// `get_job_by_name` or remove it with `remove_by_name`. What `add` does when another job has
+    /// the name already is set with `set_name_conflict`.
+    pub fn with_name<S: Into<String>>(self, name: S) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..self
+        }
+    }
+
     pub fn with_run_sync(self, job: Box<JobToRun>) -> Self {
         Self {
-            run: Some(Box::new(job)),
+            run: Some(job),
             ..self
         }
     }
 
     pub fn with_run_async(self, job: Box<JobToRunAsync>) -> Self {
         Self {
-            run_async: Some(Box::new(job)),
+            run_async: Some(job),
+            ..self
+        }
+    }
+
+    ///
+    /// Run a handler that is shared with other jobs. Each job only holds a reference to it, the
+    /// handler can tell the jobs apart by their id or by the `extra` set with `with_extra`, which
+    /// is in the `JobContext` of the run.
+    ///
+    /// ```rust,no_run
+    /// # use std::sync::Arc;
+    /// # use tokio_cron_scheduler::{JobBuilder, JobContext, JobScheduler, SharedJobToRunAsync};
+    /// # async fn sync_region(_region: &[u8]) {}
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// let handler: Arc<SharedJobToRunAsync> = Arc::new(|_uuid, _l| Box::pin(async move {
+    ///     let region = JobContext::current().map(|ctx| ctx.extra).unwrap_or_default();
+    ///     sync_region(&region).await;
+    /// }));
+    /// for region in ["eu", "us"] {
+    ///     let job = JobBuilder::new()
+    ///         .with_cron_job_type()
+    ///         .with_schedule("0 0 * * * *")?
+    ///         .with_extra(region.as_bytes().to_vec())
+    ///         .with_shared_run_async(handler.clone())
+    ///         .build()?;
+    ///     sched.add(job).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_shared_run_async(self, job: Arc<SharedJobToRunAsync>) -> Self {
+        self.with_run_async(Box::new(move |job_id, job_scheduler| {
+            job(job_id, job_scheduler)
+        }))
+    }
+
+    ///
+    /// Run a handler that gets the `JobContext` of each run as its argument, with the job's name,
+    /// scheduled time, attempt and payload, see `JobContext::handler`.
+    ///
+    /// ```rust,no_run
+    /// # use std::sync::Arc;
+    /// # use tokio_cron_scheduler::{JobBuilder, JobSchedulerError};
+    /// # async fn sync_region(_region: &str, _at: chrono::DateTime<chrono::Utc>) {}
+    /// # fn example() -> Result<(), JobSchedulerError> {
+    /// let job = JobBuilder::new()
+    ///     .with_cron_job_type()
+    ///     .with_schedule("0 0 * * * *")?
+    ///     .with_payload(&"eu".to_string())
+    ///     .with_context_run_async(Arc::new(|ctx, _l| Box::pin(async move {
+    ///         let region: String = ctx.payload().unwrap_or_default();
+    ///         sync_region(&region, ctx.scheduled).await;
+    ///     })))
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_context_run_async(self, job: Arc<JobToRunWithContext>) -> Self {
+        self.with_run_async(JobContext::handler(job))
+    }
+
+    ///
+    /// Tags kept with the job in the metadata store, for handling jobs as a group, for instance
+    /// with `set_tag_concurrency`
+    pub fn with_tags<I, S>(self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            tags: Some(tags.into_iter().map(Into::into).collect()),
+            ..self
+        }
+    }
+
+    ///
+    /// Send the runs of the job to the queue with this name, with its own workers, see
+    /// `add_queue`
+    pub fn with_queue<S: Into<String>>(self, queue: S) -> Self {
+        Self {
+            queue: Some(queue.into()),
+            ..self
+        }
+    }
+
+    ///
+    /// Where the job goes among the jobs that are due at the same time: they're fired, with their
+    /// scheduled notifications sent, by priority first, see `with_priority`, then from the lowest
+    /// order key to the highest, and by job id for the same order key. Defaults to 0. The runs go at
+    /// the same time once fired, so which of them starts first isn't kept to.
+    pub fn with_order_key(self, order_key: i64) -> Self {
+        Self {
+            order_key: Some(order_key),
+            ..self
+        }
+    }
+
+    ///
+    /// Which runs go first when they wait for a concurrency limit, like the one set with
+    /// `set_max_concurrency`: a run only starts when no run with a higher priority is waiting.
+    /// Defaults to 0, runs with the same priority start in no particular order. As long as runs
+    /// with a higher priority keep coming, the ones with a lower priority keep waiting. Of the
+    /// jobs due at the same time, the ones with a higher priority are fired first.
+    pub fn with_priority(self, priority: i32) -> Self {
+        Self {
+            priority: Some(priority),
+            ..self
+        }
+    }
+
+    ///
+    /// Fire the job after every run of another job that's done instead of on its own schedule,
+    /// as `set_after_job` does once the job is added. It's kept with the job in the metadata
+    /// store, and `add` checks that the other job is there and no job ends up fired after itself.
+    pub fn with_after_job(self, after: AfterJob) -> Self {
+        Self {
+            after: Some(after),
+            ..self
+        }
+    }
+
+    ///
+    /// Dates, days of the week and times of the day the job doesn't fire on in its time zone,
+    /// whatever its schedule. The fires that fall on them are passed over or deferred to the end
+    /// of the exclusion, as the calendar's action says. The calendar is kept with the job in the
+    /// metadata store.
+    pub fn with_calendar(self, calendar: Calendar) -> Self {
+        Self {
+            calendar: Some(calendar),
+            ..self
+        }
+    }
+
+    ///
+    /// Keep a typed payload as the job's extra data, see `JobPayload`
+    pub fn with_payload<P: JobPayload>(self, payload: &P) -> Self {
+        self.with_extra(payload.to_extra())
+    }
+
+    ///
+    /// Data kept with the job in the metadata store, for instance which of the jobs sharing a
+    /// handler this is
+    pub fn with_extra(self, extra: Vec<u8>) -> Self {
+        Self {
+            extra: Some(extra),
             ..self
         }
     }
@@ -133,6 +428,42 @@ impl<T: TimeZone> JobBuilder<T> {
         }
     }
 
+    ///
+    /// For a repeated job, whether the first run is on the first tick after the job is added instead
+    /// of one interval later. Later runs follow every interval after the first run.
+    pub fn with_start_immediately(self, start_immediately: bool) -> Self {
+        Self {
+            start_immediately: Some(start_immediately),
+            ..self
+        }
+    }
+
+    ///
+    /// A repeated job on an interval schedule, with its start, end and number of runs kept with
+    /// the job in the metadata store. The job is removed once the schedule is over.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::{IntervalSchedule, JobBuilder, JobSchedulerError};
+    /// # async fn sync() {}
+    /// # fn example() -> Result<(), JobSchedulerError> {
+    /// let job = JobBuilder::new()
+    ///     .with_interval(IntervalSchedule::every(Duration::from_secs(900)).aligned())
+    ///     .with_run_async(Box::new(|_id, _sched| Box::pin(async move { sync().await })))
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_interval(self, interval: IntervalSchedule) -> Self {
+        Self {
+            job_type: Some(JobType::Repeated),
+            duration: Some(Duration::from_secs(interval.every_seconds())),
+            repeating: Some(true),
+            interval: Some(interval),
+            ..self
+        }
+    }
+
     pub fn after_seconds(self, seconds: u64) -> Self {
         Self {
             duration: Some(Duration::from_secs(seconds)),
@@ -158,6 +489,25 @@ impl<T: TimeZone> JobBuilder<T> {
             return Err(JobSchedulerError::RunOrRunAsyncNotSet);
         }
         let async_job = run_async.is_some();
+        let job_id = self
+            .job_id
+            .clone()
+            .unwrap_or_else(|| UuidUuid::new_v4().into());
+        let jitter_seconds = self
+            .stable_jitter
+            .map(|window| hashed_schedule::stable_jitter(&(&job_id).into(), window.as_secs()))
+            .unwrap_or_default() as u32;
+        let jitter = chrono::Duration::seconds(jitter_seconds as i64);
+        let random_jitter_seconds = self
+            .random_jitter
+            .map(|window| window.as_secs().min(u32::MAX as u64) as u32)
+            .unwrap_or_default();
+        let random_delay =
+            chrono::Duration::seconds(random::below(random_jitter_seconds as u64) as i64);
+        let timeout_ms = self
+            .timeout
+            .map(|t| (t.as_millis() as u64).max(1))
+            .unwrap_or_default();
 
         match job_type {
             JobType::Cron => {
@@ -176,24 +526,24 @@ impl<T: TimeZone> JobBuilder<T> {
 
                 Ok(JobLocked(Arc::new(RwLock::new(Box::new(CronJob {
                     data: JobStoredData {
-                        id: self.job_id.or(Some(UuidUuid::new_v4().into())),
+                        id: Some(job_id.clone()),
                         last_updated: None,
                         last_tick: None,
                         next_tick: match &self.timezone {
                             Some(timezone) => schedule
-                                .upcoming(timezone.clone())
+                                .after(&(Utc::now().with_timezone(timezone) - jitter))
                                 .next()
-                                .map(|t| t.timestamp() as u64)
+                                .map(|t| (t + jitter + random_delay).timestamp() as u64)
                                 .unwrap_or(0),
                             None => schedule
-                                .upcoming(Utc)
+                                .after(&(Utc::now() - jitter))
                                 .next()
-                                .map(|t| t.timestamp() as u64)
+                                .map(|t| (t + jitter + random_delay).timestamp() as u64)
                                 .unwrap_or(0),
                         },
                         job_type: JobType::Cron.into(),
                         count: 0,
-                        extra: vec![],
+                        extra: self.extra.unwrap_or_default(),
                         ran: false,
                         stopped: false,
                         #[cfg(feature = "has_bytes")]
@@ -207,13 +557,116 @@ impl<T: TimeZone> JobBuilder<T> {
                             schedule: schedule.to_string(),
                         })),
                         time_offset_seconds,
+                        overlap: self.overlap.unwrap_or_default().into(),
+                        jitter_seconds,
+                        tags: self.tags.unwrap_or_default(),
+                        queue: self.queue.unwrap_or_default(),
+                        order_key: self.order_key.unwrap_or_default(),
+                        timezone: self
+                            .timezone
+                            .as_ref()
+                            .map(named_timezone)
+                            .unwrap_or_default(),
+                        misfire: self.misfire.map(i32::from).unwrap_or_default(),
+                        timeout_ms,
+                        name: self.name.unwrap_or_default(),
+                        priority: self.priority.unwrap_or_default(),
+                        random_jitter_seconds,
+                        after: self.after.map(Into::into),
+                        exclusions: self.calendar.map(Into::into),
+                        interval_bounds: None,
+                        paused: false,
+                    },
+                    run: run.unwrap_or(Box::new(nop)),
+                    run_async: run_async.unwrap_or(Box::new(nop_async)),
+                    async_job,
+                    first_fire: FirstFire::Cron {
+                        delay: random_delay,
+                    },
+                })))))
+            }
+            JobType::Repeated => {
+                let duration = match (self.duration, self.repeating) {
+                    (Some(duration), Some(true)) => duration,
+                    _ => return Err(JobSchedulerError::NoNextTick),
+                };
+                let now = Utc::now();
+                let start_immediately = self.start_immediately.unwrap_or_default();
+                let first_fire = match self.interval {
+                    Some(schedule) => FirstFire::Interval {
+                        schedule,
+                        immediately: start_immediately,
+                        delay: jitter + random_delay,
+                    },
+                    None => FirstFire::Repeated {
+                        immediately: start_immediately,
+                        delay: jitter + random_delay,
+                    },
+                };
+                let interval_bounds = match self.interval {
+                    Some(interval) => Some(
+                        interval
+                            .bounds(now, start_immediately, jitter + random_delay)
+                            .ok_or(JobSchedulerError::NoNextTick)?,
+                    ),
+                    None => None,
+                };
+                let first = match interval_bounds.as_ref() {
+                    Some(bounds) => Utc.timestamp_opt(bounds.first as i64, 0).single(),
+                    None if start_immediately => Some(now + jitter + random_delay),
+                    None => now
+                        .checked_add_signed(chrono::Duration::seconds(duration.as_secs() as i64))
+                        .map(|t| t + jitter + random_delay),
+                };
+
+                Ok(JobLocked(Arc::new(RwLock::new(Box::new(NonCronJob {
+                    data: JobStoredData {
+                        id: Some(job_id.clone()),
+                        last_updated: None,
+                        last_tick: None,
+                        next_tick: first.map(|t| t.timestamp() as u64).unwrap_or(0),
+                        job_type: JobType::Repeated.into(),
+                        count: 0,
+                        extra: self.extra.unwrap_or_default(),
+                        ran: false,
+                        stopped: false,
+                        #[cfg(feature = "has_bytes")]
+                        job: Some(job_data_prost::job_stored_data::Job::NonCronJob(
+                            job_data_prost::NonCronJob {
+                                repeating: true,
+                                repeated_every: duration.as_secs(),
+                            },
+                        )),
+                        #[cfg(not(feature = "has_bytes"))]
+                        job: Some(job_data::job_stored_data::Job::NonCronJob(
+                            job_data::NonCronJob {
+                                repeating: true,
+                                repeated_every: duration.as_secs(),
+                            },
+                        )),
+                        time_offset_seconds: 0,
+                        overlap: self.overlap.unwrap_or_default().into(),
+                        jitter_seconds,
+                        tags: self.tags.unwrap_or_default(),
+                        queue: self.queue.unwrap_or_default(),
+                        order_key: self.order_key.unwrap_or_default(),
+                        timezone: String::new(),
+                        misfire: self.misfire.map(i32::from).unwrap_or_default(),
+                        timeout_ms,
+                        name: self.name.unwrap_or_default(),
+                        priority: self.priority.unwrap_or_default(),
+                        random_jitter_seconds,
+                        after: self.after.map(Into::into),
+                        exclusions: self.calendar.map(Into::into),
+                        interval_bounds,
+                        paused: false,
                     },
                     run: run.unwrap_or(Box::new(nop)),
                     run_async: run_async.unwrap_or(Box::new(nop_async)),
                     async_job,
+                    first_fire,
                 })))))
             }
-            JobType::Repeated => Err(JobSchedulerError::NoNextTick),
             JobType::OneShot => Err(JobSchedulerError::NoNextTick),
         }
     }
@@ -0,0 +1,182 @@
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::{Exclusions, TimeWindow};
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::{Exclusions, TimeWindow};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// How far `Calendar::next_allowed` looks ahead, in steps of a day or a window
+const LOOKAHEAD: usize = 1000;
+
+///
+/// What happens to a fire that falls in a calendar's exclusions
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CalendarAction {
+    /// Pass the fire over, the job goes on with its next fire
+    #[default]
+    Skip,
+    /// Fire at the first time after the exclusion is over
+    Defer,
+}
+
+///
+/// Dates, days of the week and times of the day a job doesn't fire on, in the job's time zone,
+/// see `JobBuilder::with_calendar` and `JobScheduler::set_global_calendar`
+///
+/// ```rust
+/// # use chrono::{NaiveDate, NaiveTime, Weekday};
+/// # use tokio_cron_scheduler::Calendar;
+/// let calendar = Calendar::new()
+///     .exclude_weekday(Weekday::Sat)
+///     .exclude_weekday(Weekday::Sun)
+///     .exclude_date(NaiveDate::from_ymd_opt(2026, 12, 25).unwrap())
+///     .exclude_window(
+///         NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+///         NaiveTime::from_hms_opt(1, 0, 0).unwrap(),
+///     );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Calendar {
+    pub dates: Vec<NaiveDate>,
+    pub weekdays: Vec<Weekday>,
+    /// From the first time up to the second one, past midnight when the second one is earlier
+    pub windows: Vec<(NaiveTime, NaiveTime)>,
+    pub action: CalendarAction,
+}
+
+impl Calendar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn exclude_date(mut self, date: NaiveDate) -> Self {
+        self.dates.push(date);
+        self
+    }
+
+    pub fn exclude_weekday(mut self, weekday: Weekday) -> Self {
+        self.weekdays.push(weekday);
+        self
+    }
+
+    pub fn exclude_window(mut self, start: NaiveTime, end: NaiveTime) -> Self {
+        self.windows.push((start, end));
+        self
+    }
+
+    pub fn with_action(self, action: CalendarAction) -> Self {
+        Self { action, ..self }
+    }
+
+    fn day_excluded(&self, date: NaiveDate) -> bool {
+        self.dates.contains(&date) || self.weekdays.contains(&date.weekday())
+    }
+
+    ///
+    /// The end of the window `time` is in, on the day after when the window goes past midnight
+    /// and `time` is before it
+    fn window_end(&self, at: NaiveDateTime) -> Option<NaiveDateTime> {
+        let time = at.time();
+        self.windows
+            .iter()
+            .filter_map(|(start, end)| match start.cmp(end) {
+                std::cmp::Ordering::Less if *start <= time && time < *end => {
+                    Some(at.date().and_time(*end))
+                }
+                std::cmp::Ordering::Greater if *start <= time => {
+                    at.date().succ_opt().map(|date| date.and_time(*end))
+                }
+                std::cmp::Ordering::Greater if time < *end => Some(at.date().and_time(*end)),
+                _ => None,
+            })
+            .max()
+    }
+
+    ///
+    /// Whether a local time falls in the exclusions
+    pub fn excludes(&self, at: NaiveDateTime) -> bool {
+        self.day_excluded(at.date()) || self.window_end(at).is_some()
+    }
+
+    ///
+    /// The first local time from `at` on that doesn't fall in the exclusions, None when there's
+    /// none in the next few years
+    pub fn next_allowed(&self, at: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut at = at;
+        for _ in 0..LOOKAHEAD {
+            if self.day_excluded(at.date()) {
+                at = at.date().succ_opt()?.and_time(NaiveTime::MIN);
+            } else if let Some(end) = self.window_end(at) {
+                at = end;
+            } else {
+                return Some(at);
+            }
+        }
+        None
+    }
+
+    pub(crate) fn from_stored(exclusions: &Exclusions) -> Self {
+        Self {
+            dates: exclusions
+                .dates
+                .iter()
+                .filter_map(|days| NaiveDate::from_num_days_from_ce_opt(*days))
+                .collect(),
+            weekdays: exclusions
+                .weekdays
+                .iter()
+                .filter_map(|day| WEEKDAYS.get(*day as usize).copied())
+                .collect(),
+            windows: exclusions
+                .windows
+                .iter()
+                .filter_map(|window| {
+                    Some((
+                        NaiveTime::from_num_seconds_from_midnight_opt(window.start_second, 0)?,
+                        NaiveTime::from_num_seconds_from_midnight_opt(window.end_second, 0)?,
+                    ))
+                })
+                .collect(),
+            action: if exclusions.defer {
+                CalendarAction::Defer
+            } else {
+                CalendarAction::Skip
+            },
+        }
+    }
+}
+
+impl From<Calendar> for Exclusions {
+    fn from(calendar: Calendar) -> Self {
+        Self {
+            dates: calendar
+                .dates
+                .iter()
+                .map(|date| date.num_days_from_ce())
+                .collect(),
+            weekdays: calendar
+                .weekdays
+                .iter()
+                .map(|day| day.num_days_from_monday())
+                .collect(),
+            windows: calendar
+                .windows
+                .iter()
+                .map(|(start, end)| TimeWindow {
+                    start_second: start.num_seconds_from_midnight(),
+                    end_second: end.num_seconds_from_midnight(),
+                })
+                .collect(),
+            defer: calendar.action == CalendarAction::Defer,
+        }
+    }
+}
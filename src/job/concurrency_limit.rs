@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+///
+/// How many runs of all jobs together may go at the same time. The limit can change while runs
+/// are going, lowering it lets the running ones finish and holds new ones back until fewer run.
+/// Runs waiting with a higher priority get the places that come free first.
+#[derive(Default)]
+pub(crate) struct ConcurrencyLimit {
+    state: Mutex<LimitState>,
+    released: Notify,
+}
+
+#[derive(Default)]
+struct LimitState {
+    max: Option<usize>,
+    going: usize,
+    // How many runs wait with each priority
+    waiting: BTreeMap<i32, usize>,
+}
+
+impl LimitState {
+    fn unwait(&mut self, priority: i32) {
+        if let Some(count) = self.waiting.get_mut(&priority) {
+            *count -= 1;
+            if *count == 0 {
+                self.waiting.remove(&priority);
+            }
+        }
+    }
+}
+
+///
+/// A run waiting for a limit, which stops counting as waiting when dropped, so a run that is
+/// dropped while it waits doesn't hold back the runs with a lower priority
+struct Waiting<'a> {
+    limit: &'a ConcurrencyLimit,
+    priority: i32,
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        {
+            let mut state = self.limit.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.unwait(self.priority);
+        }
+        // The runs with a lower priority may go now
+        self.limit.released.notify_waiters();
+    }
+}
+
+impl ConcurrencyLimit {
+    pub(crate) fn max(&self) -> Option<usize> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).max
+    }
+
+    pub(crate) fn set_max(&self, max: Option<usize>) {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).max = max;
+        self.released.notify_waiters();
+    }
+
+    ///
+    /// Wait until another run may start and no run with a higher priority waits, and count it as
+    /// going until the permit is dropped
+    pub(crate) async fn acquire(self: &Arc<Self>, priority: i32) -> LimitPermit {
+        let mut waiting = None;
+        loop {
+            // Listen before looking, so a release in between isn't missed
+            let released = self.released.notified();
+            tokio::pin!(released);
+            released.as_mut().enable();
+            {
+                let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+                let free = state.max.map(|max| state.going < max).unwrap_or(true);
+                let first = state
+                    .waiting
+                    .range((Bound::Excluded(priority), Bound::Unbounded))
+                    .next()
+                    .is_none();
+                if free && first {
+                    state.going += 1;
+                    drop(state);
+                    drop(waiting);
+                    return LimitPermit(self.clone());
+                }
+                if waiting.is_none() {
+                    *state.waiting.entry(priority).or_default() += 1;
+                    waiting = Some(Waiting {
+                        limit: self,
+                        priority,
+                    });
+                }
+            }
+            released.await;
+        }
+    }
+
+    fn release(&self) {
+        {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.going = state.going.saturating_sub(1);
+        }
+        self.released.notify_waiters();
+    }
+}
+
+///
+/// A run counted as going by a limit, released when dropped so a run that is dropped before it's
+/// done, like when the runtime shuts down, doesn't keep its place
+pub(crate) struct LimitPermit(Arc<ConcurrencyLimit>);
+
+impl Drop for LimitPermit {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
@@ -1,5 +1,6 @@
 use crate::context::Context;
 use crate::job::{JobLocked, JobToRunAsync};
+use crate::stats::SchedulerStats;
 use crate::store::MetaDataStorage;
 use crate::{JobSchedulerError, JobStoredData};
 use std::future::Future;
@@ -18,6 +19,7 @@ impl JobCreator {
         storage: Arc<RwLock<Box<dyn MetaDataStorage + Send + Sync>>>,
         mut rx: Receiver<(JobStoredData, Arc<RwLock<Box<JobToRunAsync>>>)>,
         tx_created: Sender<Result<Uuid, (JobSchedulerError, Option<Uuid>)>>,
+        stats: Arc<RwLock<SchedulerStats>>,
     ) {
         loop {
             let val = rx.recv().await;
@@ -46,6 +48,10 @@ impl JobCreator {
                     continue;
                 }
             }
+            {
+                let mut stats = stats.write().await;
+                stats.added(uuid);
+            }
             if let Err(e) = tx_created.send(Ok(uuid)) {
                 error!("Error sending created job {:?}", e);
             }
@@ -59,9 +65,12 @@ impl JobCreator {
         let rx = context.job_create_tx.subscribe();
         let tx_created = context.job_created_tx.clone();
         let storage = context.metadata_storage.clone();
+        let stats = context.stats.clone();
 
         Box::pin(async move {
-            tokio::spawn(JobCreator::listen_to_additions(storage, rx, tx_created));
+            tokio::spawn(JobCreator::listen_to_additions(
+                storage, rx, tx_created, stats,
+            ));
             Ok(())
         })
     }
@@ -78,33 +87,9 @@ impl JobCreator {
             return Err(e);
         }
         let data = data.unwrap();
-        let job: Box<JobToRunAsync> = Box::new(move |job_id, job_scheduler| {
-            let job = job.clone();
-            Box::pin(async move {
-                let job_done = {
-                    let w = job.0.write();
-                    if let Err(e) = w {
-                        error!("Error getting job {:?}", e);
-                        return;
-                    }
-                    let mut w = w.unwrap();
-                    w.run(job_scheduler)
-                };
-                let job_done = job_done.await;
-                match job_done {
-                    Err(e) => {
-                        error!("Error running job {:?} {:?}", job_id, e);
-                    }
-                    Ok(val) => {
-                        if !val {
-                            error!("Error running job {:?}", job_id);
-                        }
-                    }
-                }
-            })
-        });
-
-        let job = Arc::new(RwLock::new(job));
+        let name = data.name.clone();
+        let is_async = job.is_async()?;
+        let job = Arc::new(RwLock::new(job.into_run()));
         if let Err(_e) = tx.send((data, job)) {
             error!("Error sending new job");
             return Err(JobSchedulerError::CantAdd);
@@ -112,15 +97,21 @@ impl JobCreator {
 
         while let Ok(val) = rx.recv().await {
             match val {
-                Ok(ret_uuid) => {
-                    if ret_uuid == uuid {
-                        return Ok(uuid);
+                Ok(ret_uuid) if ret_uuid == uuid => {
+                    context.name_job(uuid, &name).await;
+                    {
+                        let mut w = context.sync_jobs.write().await;
+                        if is_async {
+                            w.remove(&uuid);
+                        } else {
+                            w.insert(uuid);
+                        }
                     }
+                    context.update_job_count().await;
+                    return Ok(uuid);
                 }
-                Err((e, Some(ret_uuid))) => {
-                    if ret_uuid == uuid {
-                        return Err(e);
-                    }
+                Err((e, Some(ret_uuid))) if ret_uuid == uuid => {
+                    return Err(e);
                 }
                 _ => {}
             }
@@ -0,0 +1,140 @@
+use crate::JobSchedulerError;
+use chrono::{DateTime, TimeZone, Utc};
+use cron::Schedule;
+use std::str::FromStr;
+use std::time::Duration;
+
+///
+/// How far ahead a cron schedule must fire to be taken, unless `set_schedule_horizon` says otherwise
+pub(crate) const SCHEDULE_HORIZON: Duration = Duration::from_secs(5 * 365 * 24 * 60 * 60);
+
+const FIELDS: [(&str, u32, u32); 7] = [
+    ("seconds", 0, 59),
+    ("minutes", 0, 59),
+    ("hours", 0, 23),
+    ("day of month", 1, 31),
+    ("month", 1, 12),
+    ("day of week", 1, 7),
+    ("year", 1970, 2099),
+];
+
+///
+/// Check the step values of a cron schedule, like the `15` in `*/15`. A step has to be at least 1
+/// and at most the number of values in its field, a larger step only ever matches its start.
+pub(crate) fn validate_steps(schedule: &str) -> Result<(), JobSchedulerError> {
+    for (value, (field, min, max)) in schedule.split_whitespace().zip(FIELDS.iter()) {
+        for element in value.split(',') {
+            let step = match element.split_once('/') {
+                Some((_, step)) => step,
+                None => continue,
+            };
+            let size = max - min + 1;
+            match step.parse::<u32>() {
+                Ok(step) if (1..=size).contains(&step) => {}
+                _ => {
+                    return Err(JobSchedulerError::InvalidCron(format!(
+                        "step {:?} in the {} field should be between 1 and {}",
+                        step, field, size
+                    )))
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+///
+/// Parse a cron expression the way jobs do, checking its fields and step values.
+///
+/// ```rust
+/// # use tokio_cron_scheduler::{parse_cron, JobSchedulerError};
+/// # fn main() -> Result<(), JobSchedulerError> {
+/// let schedule = parse_cron("0 */15 * * * *")?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn parse_cron(expr: &str) -> Result<Schedule, JobSchedulerError> {
+    // The steps are checked first, the cron parser rejects some of them without naming the field
+    validate_steps(expr)?;
+    Schedule::from_str(expr).map_err(|_| JobSchedulerError::ParseSchedule)
+}
+
+///
+/// Check a cron expression without creating a job, for instance before storing one a user
+/// entered. It's accepted when a job with this schedule can be created. Unlike `parse_cron` it
+/// says what's wrong, with `InvalidCron`, and it takes a schedule that doesn't fire within
+/// the default schedule horizon of 5 years, like `0 0 0 30 2 *`, for a mistake and returns `UnsatisfiableSchedule`.
+///
+/// ```rust,no_run
+/// # use tokio_cron_scheduler::{validate_cron, JobSchedulerError};
+/// # struct Form {
+/// #     schedule: String,
+/// # }
+/// # fn bad_request(why: String) -> Result<(), String> {
+/// #     Err(why)
+/// # }
+/// # fn example(form: Form) -> Result<(), String> {
+/// if let Err(JobSchedulerError::InvalidCron(why)) = validate_cron(&form.schedule) {
+///     return bad_request(why);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn validate_cron(expr: &str) -> Result<(), JobSchedulerError> {
+    check_cron(expr, &Utc::now(), SCHEDULE_HORIZON).map(|_| ())
+}
+
+///
+/// `validate_cron` from `now` with another horizon, giving the schedule
+pub(crate) fn check_cron<TZ: TimeZone>(
+    expr: &str,
+    now: &DateTime<TZ>,
+    horizon: Duration,
+) -> Result<Schedule, JobSchedulerError> {
+    // Five fields is the classic crontab, which is one field short here
+    let fields = expr.split_whitespace().count();
+    if !expr.trim_start().starts_with('@') && !(6..=FIELDS.len()).contains(&fields) {
+        return Err(JobSchedulerError::InvalidCron(format!(
+            "{} fields, a schedule has seconds, minutes, hours, day of month, month, day of week \
+             and optionally year",
+            fields
+        )));
+    }
+    validate_steps(expr)?;
+    let schedule = Schedule::from_str(expr)
+        .map_err(|e| JobSchedulerError::InvalidCron(format!("{:?}: {}", expr, e)))?;
+    let horizon = chrono::Duration::from_std(horizon).unwrap_or(chrono::Duration::MAX);
+    let fires = schedule
+        .after(now)
+        .next()
+        .map(|next| next.signed_duration_since(now) <= horizon)
+        .unwrap_or_default();
+    if !fires {
+        return Err(JobSchedulerError::UnsatisfiableSchedule(expr.to_string()));
+    }
+    Ok(schedule)
+}
+
+///
+/// The next `n` times a cron expression fires after `after`, in the timezone of `after`, for
+/// instance to show a user what a schedule they entered means before a job is made with it
+///
+/// ```rust
+/// # use chrono::Utc;
+/// # use tokio_cron_scheduler::{preview_cron, JobSchedulerError};
+/// # fn main() -> Result<(), JobSchedulerError> {
+/// let berlin = Utc::now().with_timezone(&chrono_tz::Europe::Berlin);
+/// for at in preview_cron("0 30 9 * * Mon-Fri", &berlin, 5)? {
+///     println!("{}", at);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn preview_cron<TZ: TimeZone>(
+    expr: &str,
+    after: &DateTime<TZ>,
+    n: usize,
+) -> Result<Vec<DateTime<TZ>>, JobSchedulerError> {
+    let schedule = parse_cron(expr)?;
+    Ok(schedule.after(after).take(n).collect())
+}
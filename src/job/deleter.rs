@@ -1,5 +1,12 @@
 use crate::context::Context;
-use crate::store::MetaDataStorage;
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::JobState;
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::JobState;
+use crate::job::lifecycle;
+use crate::job::to_code::NotificationCode;
+use crate::stats::SchedulerStats;
+use crate::store::{MetaDataStorage, NotificationStore};
 use crate::JobSchedulerError;
 use std::future::Future;
 use std::pin::Pin;
@@ -13,10 +20,56 @@ use uuid::Uuid;
 pub struct JobDeleter {}
 
 impl JobDeleter {
+    async fn run_removed_notifications(
+        job_id: Uuid,
+        notification_storage: &Arc<RwLock<Box<dyn NotificationStore + Send + Sync>>>,
+        notification_code: &Arc<RwLock<Box<dyn NotificationCode + Send + Sync>>>,
+    ) {
+        let notifications = {
+            let mut storage = notification_storage.write().await;
+            storage
+                .list_notification_guids_for_job_and_state(job_id, JobState::Removed)
+                .await
+        };
+        let notifications = match notifications {
+            Ok(notifications) => notifications,
+            Err(e) => {
+                error!("Error getting removal notifications {:?}", e);
+                return;
+            }
+        };
+        let mut handles = vec![];
+        {
+            let mut code = notification_code.write().await;
+            for notification_id in notifications {
+                match code.get(notification_id).await {
+                    Ok(Some(code)) => {
+                        handles.push(tokio::spawn(async move {
+                            let mut code = code.write().await;
+                            (code)(job_id, notification_id, JobState::Removed).await;
+                        }));
+                    }
+                    _ => {
+                        error!("Could not get notification code for {:?}", notification_id);
+                    }
+                }
+            }
+        }
+        for handle in handles {
+            if let Err(e) = handle.await {
+                error!("Error running removal notification {:?}", e);
+            }
+        }
+    }
+
     async fn listen_to_removals(
         storage: Arc<RwLock<Box<dyn MetaDataStorage + Send + Sync>>>,
         mut rx: Receiver<Uuid>,
         tx_deleted: Sender<Result<Uuid, (JobSchedulerError, Option<Uuid>)>>,
+        stats: Arc<RwLock<SchedulerStats>>,
+        notification_storage: Arc<RwLock<Box<dyn NotificationStore + Send + Sync>>>,
+        notification_code: Arc<RwLock<Box<dyn NotificationCode + Send + Sync>>>,
+        context: Context,
     ) {
         loop {
             let val = rx.recv().await;
@@ -25,8 +78,16 @@ impl JobDeleter {
                 break;
             }
             let uuid = val.unwrap();
-            {
+            let name = {
                 let mut storage = storage.write().await;
+                let name = if context.events_tx.receiver_count() > 0 {
+                    match storage.get(uuid).await {
+                        Ok(Some(data)) => data.name,
+                        _ => String::new(),
+                    }
+                } else {
+                    String::new()
+                };
                 let delete = storage.delete(uuid).await;
                 if let Err(e) = delete {
                     error!("Error deleting {:?}", e);
@@ -35,6 +96,23 @@ impl JobDeleter {
                     }
                     continue;
                 }
+                name
+            };
+            lifecycle::event(uuid, JobState::Removed);
+            context.send_event(uuid, name, JobState::Removed, None);
+            JobDeleter::run_removed_notifications(uuid, &notification_storage, &notification_code)
+                .await;
+            {
+                let mut stats = stats.write().await;
+                stats.removed(&uuid);
+            }
+            {
+                let mut w = context.history_store.write().await;
+                if let Some(store) = w.as_mut() {
+                    if let Err(e) = store.delete(uuid).await {
+                        error!("Error removing the history of {:?} {:?}", uuid, e);
+                    }
+                }
             }
             if let Err(e) = tx_deleted.send(Ok(uuid)) {
                 error!("Error sending error {:?}", e);
@@ -49,9 +127,21 @@ impl JobDeleter {
         let rx = context.job_delete_tx.subscribe();
         let tx_deleted = context.job_deleted_tx.clone();
         let storage = context.metadata_storage.clone();
+        let stats = context.stats.clone();
+        let notification_storage = context.notification_storage.clone();
+        let notification_code = context.notification_code.clone();
+        let context = context.clone();
 
         Box::pin(async move {
-            tokio::spawn(JobDeleter::listen_to_removals(storage, rx, tx_deleted));
+            tokio::spawn(JobDeleter::listen_to_removals(
+                storage,
+                rx,
+                tx_deleted,
+                stats,
+                notification_storage,
+                notification_code,
+                context,
+            ));
             Ok(())
         })
     }
@@ -70,6 +160,75 @@ impl JobDeleter {
             match deleted {
                 Ok(uuid) => {
                     if uuid == job_id {
+                        {
+                            let mut w = context.at_least_once.write().await;
+                            w.remove(&job_id);
+                        }
+                        {
+                            let mut w = context.budgets.write().await;
+                            w.remove(&job_id);
+                        }
+                        {
+                            let mut w = context.max_lateness.write().await;
+                            w.remove(&job_id);
+                        }
+                        {
+                            let mut w = context.max_silence.write().await;
+                            w.remove(&job_id);
+                        }
+                        {
+                            let mut w = context.after_jobs.write().await;
+                            w.remove(&job_id);
+                        }
+                        {
+                            let mut w = context.job_names.write().await;
+                            w.retain(|_, id| *id != job_id);
+                        }
+                        {
+                            let mut w = context.log_levels.write().await;
+                            w.remove(&job_id);
+                        }
+                        {
+                            let mut w = context.one_offs.write().await;
+                            w.remove(&job_id);
+                        }
+                        {
+                            let mut w = context.fire_times.write().await;
+                            w.remove(&job_id);
+                        }
+                        {
+                            let mut w = context.skip_after_failure.write().await;
+                            w.remove(&job_id);
+                        }
+                        {
+                            let mut w = context.inline.write().await;
+                            w.remove(&job_id);
+                        }
+                        {
+                            let mut w = context.sync_jobs.write().await;
+                            w.remove(&job_id);
+                        }
+                        {
+                            let mut w = context.overlaps.write().await;
+                            w.remove(&job_id);
+                        }
+                        {
+                            let mut w = context.schedule_providers.write().await;
+                            w.remove(&job_id);
+                        }
+                        {
+                            let mut w = context.paused_until.write().await;
+                            w.remove(&job_id);
+                        }
+                        {
+                            let mut w = context.until_done.write().await;
+                            w.remove(&job_id);
+                        }
+                        {
+                            let mut w = context.retry_policies.write().await;
+                            w.remove(&job_id);
+                        }
+                        context.update_job_count().await;
                         return Ok(());
                     } else {
                         continue;
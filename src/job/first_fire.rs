@@ -0,0 +1,91 @@
+use crate::job::IntervalSchedule;
+use crate::{JobSchedulerError, JobStoredData};
+use chrono::{DateTime, FixedOffset, Utc};
+use std::time::{Duration, Instant};
+
+///
+/// How a job that's made works out its first fire. It's done by the system clock when the job is
+/// made and again by the scheduler's clock when the job is added, so a job added to a scheduler
+/// on a `MockClock` fires on that clock. Jobs taken from a metadata store or a snapshot keep the
+/// next tick they have.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FirstFire {
+    /// The next tick the job has
+    #[default]
+    Kept,
+    /// The first fire of the cron schedule after the job is added, `delay` after it
+    Cron { delay: chrono::Duration },
+    /// One interval after the job is added, or right away when `immediately`, `delay` after it
+    Repeated {
+        immediately: bool,
+        delay: chrono::Duration,
+    },
+    /// The interval schedule fixed from when the job is added
+    Interval {
+        schedule: IntervalSchedule,
+        immediately: bool,
+        delay: chrono::Duration,
+    },
+    /// The wait after the job is added
+    After(Duration),
+    /// The time the instant is at when the job is added
+    At(Instant),
+}
+
+impl FirstFire {
+    ///
+    /// Set the first fire of a job's data from `now`
+    pub(crate) fn apply(
+        &self,
+        data: &mut JobStoredData,
+        now: DateTime<Utc>,
+    ) -> Result<(), JobSchedulerError> {
+        let first = match *self {
+            FirstFire::Kept => return Ok(()),
+            FirstFire::Cron { delay } => {
+                let offset = FixedOffset::east_opt(data.time_offset_seconds)
+                    .unwrap_or(FixedOffset::east_opt(0).unwrap());
+                data.next_cron_tick(&now.with_timezone(&offset))
+                    .map(|next| next.with_timezone(&Utc) + delay)
+            }
+            FirstFire::Repeated { immediately, delay } => {
+                let every = data.repeated_every().unwrap_or_default() as i64;
+                match immediately {
+                    true => Some(now + delay),
+                    false => now
+                        .checked_add_signed(chrono::Duration::seconds(every))
+                        .map(|first| first + delay),
+                }
+            }
+            FirstFire::Interval {
+                schedule,
+                immediately,
+                delay,
+            } => {
+                let bounds = schedule
+                    .bounds(now, immediately, delay)
+                    .ok_or(JobSchedulerError::NoNextTick)?;
+                let first = bounds.first;
+                data.interval_bounds = Some(bounds);
+                data.next_tick = first;
+                return Ok(());
+            }
+            FirstFire::After(wait) => now.checked_add_signed(chrono::Duration::seconds(
+                wait.as_secs().min(i64::MAX as u64) as i64,
+            )),
+            FirstFire::At(instant) => {
+                let at =
+                    chrono::Duration::from_std(instant.saturating_duration_since(Instant::now()))
+                        .ok()
+                        .and_then(|wait| now.checked_add_signed(wait))
+                        .ok_or(JobSchedulerError::NoNextTick)?;
+                // Rounded up as a one shot job at a time is, so it doesn't fire before the instant
+                data.next_tick =
+                    (at.timestamp() + i64::from(at.timestamp_subsec_nanos() > 0)).max(1) as u64;
+                return Ok(());
+            }
+        };
+        data.set_next_tick(first);
+        Ok(())
+    }
+}
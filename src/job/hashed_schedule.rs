@@ -0,0 +1,121 @@
+use crate::JobSchedulerError;
+use uuid::Uuid;
+
+const FIELDS: [(&str, u64, u64); 6] = [
+    ("seconds", 0, 59),
+    ("minutes", 0, 59),
+    ("hours", 0, 23),
+    // Like Jenkins, keep to days every month has
+    ("day of month", 1, 28),
+    ("month", 1, 12),
+    ("day of week", 1, 7),
+];
+
+const SUPPORTED: &str = "only H, H(a-b), H/n and H(a-b)/n are supported";
+
+fn hash_for_field(job_id: &Uuid, field: usize) -> u64 {
+    let id = job_id.as_u128();
+    // splitmix64 finalizer, stable across builds and platforms
+    let mut z = ((id >> 64) as u64 ^ id as u64)
+        .wrapping_add((field as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+///
+/// A number of seconds below `window` derived from the job id, the same for the same job id
+pub(crate) fn stable_jitter(job_id: &Uuid, window: u64) -> u64 {
+    if window == 0 {
+        return 0;
+    }
+    hash_for_field(job_id, FIELDS.len()) % window
+}
+
+fn error(field: &str, element: &str, reason: &str) -> JobSchedulerError {
+    JobSchedulerError::ParseHashedSchedule(format!(
+        "{:?} in the {} field: {}",
+        element, field, reason
+    ))
+}
+
+fn parse_number(field: &str, element: &str, val: &str) -> Result<u64, JobSchedulerError> {
+    val.trim()
+        .parse::<u64>()
+        .map_err(|_| error(field, element, "expected a number"))
+}
+
+fn expand_element(element: &str, field: usize, job_id: &Uuid) -> Result<String, JobSchedulerError> {
+    let (name, min, max) = FIELDS[field];
+    let rest = &element[1..];
+
+    let (from, to, rest) = if let Some(range) = rest.strip_prefix('(') {
+        let end = range
+            .find(')')
+            .ok_or_else(|| error(name, element, "missing closing bracket"))?;
+        let (from, to) = range[..end]
+            .split_once('-')
+            .ok_or_else(|| error(name, element, "range should look like H(a-b)"))?;
+        let from = parse_number(name, element, from)?;
+        let to = parse_number(name, element, to)?;
+        if from > to || from < min || to > max {
+            return Err(error(
+                name,
+                element,
+                &format!("range should go up and be within {}-{}", min, max),
+            ));
+        }
+        (from, to, &range[end + 1..])
+    } else {
+        (min, max, rest)
+    };
+
+    let hash = hash_for_field(job_id, field);
+    if rest.is_empty() {
+        return Ok((from + hash % (to - from + 1)).to_string());
+    }
+    let step = rest
+        .strip_prefix('/')
+        .ok_or_else(|| error(name, element, SUPPORTED))?;
+    let step = parse_number(name, element, step)?;
+    if step == 0 {
+        return Err(error(name, element, "step should be more than 0"));
+    }
+    let start = from + hash % step.min(to - from + 1);
+    Ok(format!("{}-{}/{}", start, to, step))
+}
+
+///
+/// Replace the Jenkins-style `H` elements of a cron schedule with values derived from the job id.
+/// The same job id always gives the same schedule, different job ids are spread over the range.
+///
+/// Supported are `H`, `H(a-b)`, `H/n` and `H(a-b)/n` in the seconds through day of week fields.
+pub(crate) fn expand(schedule: &str, job_id: &Uuid) -> Result<String, JobSchedulerError> {
+    let mut fields = vec![];
+    for (i, value) in schedule.split_whitespace().enumerate() {
+        // Names like THU or MARCH also have an H in them
+        let is_hashed =
+            |e: &str| e == "H" || (e.contains('H') && !e.chars().all(|c| c.is_ascii_alphabetic()));
+        if !value.split(',').any(is_hashed) {
+            fields.push(value.to_string());
+            continue;
+        }
+        if i >= FIELDS.len() {
+            return Err(error("year", value, "H is not supported for the year"));
+        }
+        let elements = value
+            .split(',')
+            .map(|e| {
+                if !is_hashed(e) {
+                    Ok(e.to_string())
+                } else if e.starts_with('H') {
+                    expand_element(e, i, job_id)
+                } else {
+                    Err(error(FIELDS[i].0, e, SUPPORTED))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        fields.push(elements.join(","));
+    }
+    Ok(fields.join(" "))
+}
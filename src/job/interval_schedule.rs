@@ -0,0 +1,126 @@
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::IntervalBounds;
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::IntervalBounds;
+use chrono::{DateTime, TimeZone, Utc};
+use std::time::Duration;
+
+///
+/// Fires every `every`, in whole seconds of at least one, from `start` on and before `end`, at
+/// most `max_runs` times. Without a start the first fire is one interval after the job is added.
+/// When aligned the fires are on the multiples of the interval since the epoch, so every 15
+/// minutes fires at :00, :15, :30 and :45, from the first multiple after the start, or after the
+/// job is added, on. The fires are fixed by the scheduler's clock when the job is added. Give it
+/// to a job with `JobBuilder::with_interval` or `Job::new_interval`.
+///
+/// ```rust
+/// # use chrono::{TimeZone, Utc};
+/// # use std::time::Duration;
+/// # use tokio_cron_scheduler::IntervalSchedule;
+/// // Every 90 seconds from 12:00 until 18:00
+/// let schedule = IntervalSchedule::every(Duration::from_secs(90))
+///     .starting_at(Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap())
+///     .ending_at(Utc.with_ymd_and_hms(2026, 6, 1, 18, 0, 0).unwrap());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IntervalSchedule {
+    pub every: Duration,
+    pub aligned: bool,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    /// The schedule is over after this many fire times, whether all of them ran or not
+    pub max_runs: Option<u32>,
+}
+
+impl IntervalSchedule {
+    pub fn every(every: Duration) -> Self {
+        Self {
+            every,
+            aligned: false,
+            start: None,
+            end: None,
+            max_runs: None,
+        }
+    }
+
+    pub fn aligned(self) -> Self {
+        Self {
+            aligned: true,
+            ..self
+        }
+    }
+
+    pub fn starting_at(self, start: DateTime<Utc>) -> Self {
+        Self {
+            start: Some(start),
+            ..self
+        }
+    }
+
+    pub fn ending_at(self, end: DateTime<Utc>) -> Self {
+        Self {
+            end: Some(end),
+            ..self
+        }
+    }
+
+    pub fn with_max_runs(self, max_runs: u32) -> Self {
+        Self {
+            max_runs: Some(max_runs),
+            ..self
+        }
+    }
+
+    pub(crate) fn every_seconds(&self) -> u64 {
+        self.every.as_secs().max(1)
+    }
+
+    ///
+    /// The first fire of the schedule made at `now`, at `now` itself when it has no start and
+    /// starts `immediately`
+    fn first(&self, now: DateTime<Utc>, immediately: bool) -> Option<i64> {
+        let every = self.every_seconds() as i64;
+        let now = now.timestamp();
+        match (self.start.map(|start| start.timestamp()), self.aligned) {
+            (Some(start), true) => Some((start - 1).div_euclid(every) * every + every),
+            (None, true) => Some((now.div_euclid(every) + 1) * every),
+            (Some(start), false) => Some(start),
+            (None, false) if immediately => Some(now),
+            (None, false) => now.checked_add(every),
+        }
+    }
+
+    ///
+    /// The schedule fixed from `now` on, as it's stored with the job. None when it doesn't fire
+    /// at all.
+    pub(crate) fn bounds(
+        &self,
+        now: DateTime<Utc>,
+        immediately: bool,
+        delay: chrono::Duration,
+    ) -> Option<IntervalBounds> {
+        let first = self
+            .first(now, immediately)?
+            .checked_add(delay.num_seconds())?;
+        let end = self.end.map(|end| end.timestamp());
+        if self.max_runs == Some(0) || end.is_some_and(|end| first >= end) || first <= 0 {
+            return None;
+        }
+        Some(IntervalBounds {
+            first: first as u64,
+            end: end.map(|end| end.max(0) as u64).unwrap_or_default(),
+            max_runs: self.max_runs.unwrap_or_default(),
+        })
+    }
+
+    pub(crate) fn from_stored(every: u64, bounds: &IntervalBounds) -> Self {
+        let at = |seconds: u64| Utc.timestamp_opt(seconds as i64, 0).single();
+        Self {
+            every: Duration::from_secs(every),
+            aligned: false,
+            start: at(bounds.first),
+            end: Some(bounds.end).filter(|end| *end > 0).and_then(at),
+            max_runs: Some(bounds.max_runs).filter(|max_runs| *max_runs > 0),
+        }
+    }
+}
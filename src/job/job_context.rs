@@ -0,0 +1,173 @@
+use crate::job::{JobError, JobToRunAsync, JobToRunWithContext};
+use chrono::{DateTime, Utc};
+use std::cell::RefCell;
+use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+
+tokio::task_local! {
+    static JOB_CONTEXT: JobContext;
+    /// The error a fallible job's run ended with
+    static RUN_FAILURE: RefCell<Option<JobError>>;
+}
+
+///
+/// Information about the run of a job, available from inside the job's closure
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JobContext {
+    pub job_id: Uuid,
+    /// The job's name, empty when it has none
+    pub name: String,
+    /// When the run was due on the job's schedule, or when it started for runs that weren't
+    /// fired by the schedule, like retries and one off fires
+    pub scheduled: DateTime<Utc>,
+    /// The attempt of the run, counting from 1, higher for the retries after failed runs
+    pub attempt: u32,
+    pub next_run: Option<DateTime<Utc>>,
+    pub run_count: u64,
+    /// The job's extra data, see `JobBuilder::with_extra` and `JobBuilder::with_payload`
+    pub extra: Vec<u8>,
+}
+
+///
+/// A typed payload kept as a job's extra data, so it's stored with the job and is there again
+/// for a job restored from a metadata store. Implement it with the encoding of your choice.
+///
+/// ```rust
+/// # use tokio_cron_scheduler::JobPayload;
+/// struct Report {
+///     region: String,
+/// }
+///
+/// impl JobPayload for Report {
+///     fn to_extra(&self) -> Vec<u8> {
+///         self.region.as_bytes().to_vec()
+///     }
+///     fn from_extra(extra: &[u8]) -> Option<Self> {
+///         let region = String::from_utf8(extra.to_vec()).ok()?;
+///         Some(Report { region })
+///     }
+/// }
+/// ```
+pub trait JobPayload: Sized {
+    fn to_extra(&self) -> Vec<u8>;
+    fn from_extra(extra: &[u8]) -> Option<Self>;
+}
+
+impl JobPayload for Vec<u8> {
+    fn to_extra(&self) -> Vec<u8> {
+        self.clone()
+    }
+
+    fn from_extra(extra: &[u8]) -> Option<Self> {
+        Some(extra.to_vec())
+    }
+}
+
+impl JobPayload for String {
+    fn to_extra(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn from_extra(extra: &[u8]) -> Option<Self> {
+        String::from_utf8(extra.to_vec()).ok()
+    }
+}
+
+impl JobContext {
+    ///
+    /// The context of the job currently running on this task. Returns None when called outside
+    /// of a job run, or from a task spawned by the job itself.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{Job, JobContext, JobSchedulerError};
+    /// # fn example() -> Result<(), JobSchedulerError> {
+    /// let job = Job::new_async("1/5 * * * * *", |_uuid, _l| {
+    ///     Box::pin(async move {
+    ///         if let Some(ctx) = JobContext::current() {
+    ///             println!("This is run #{}, next at {:?}", ctx.run_count, ctx.next_run);
+    ///         }
+    ///     })
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn current() -> Option<JobContext> {
+        JOB_CONTEXT.try_with(|ctx| ctx.clone()).ok()
+    }
+
+    ///
+    /// The job's extra data as a typed payload, None when it doesn't decode as one
+    pub fn payload<P: JobPayload>(&self) -> Option<P> {
+        P::from_extra(&self.extra)
+    }
+
+    ///
+    /// The run of a handler that gets the context of each run as its argument. One handler can
+    /// serve many jobs, each with its own payload, also when the jobs are restored from a
+    /// metadata store and the handler is looked up again for them.
+    ///
+    /// ```rust,no_run
+    /// # use std::sync::Arc;
+    /// # use tokio_cron_scheduler::{JobContext, JobScheduler, JobToRunWithContext, SchedulerState};
+    /// # async fn example(mut sched: JobScheduler, state: SchedulerState) -> Result<(), Box<dyn std::error::Error>> {
+    /// let handler: Arc<JobToRunWithContext> = Arc::new(|ctx, _l| Box::pin(async move {
+    ///     let region: String = ctx.payload().unwrap_or_default();
+    ///     println!("Syncing {} for {:?}, attempt {}", region, ctx.scheduled, ctx.attempt);
+    /// }));
+    /// let ids = sched.import_state(state, |_data| Some(JobContext::handler(handler.clone()))).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn handler(handler: Arc<JobToRunWithContext>) -> Box<JobToRunAsync> {
+        Box::new(move |job_id, job_scheduler| {
+            let handler = handler.clone();
+            Box::pin(async move {
+                let ctx = JobContext::current().unwrap_or_else(|| JobContext::outside(job_id));
+                handler(ctx, job_scheduler).await
+            })
+        })
+    }
+
+    ///
+    /// The context of a job run directly with `Job::run`, outside of the runner
+    fn outside(job_id: Uuid) -> Self {
+        Self {
+            job_id,
+            name: String::new(),
+            scheduled: Utc::now(),
+            attempt: 1,
+            next_run: None,
+            run_count: 0,
+            extra: vec![],
+        }
+    }
+
+    ///
+    /// Run a job's run with this context, Err when it's a fallible job's run that failed
+    pub(crate) async fn scope<F: std::future::Future<Output = ()>>(
+        self,
+        f: F,
+    ) -> Result<(), JobError> {
+        RUN_FAILURE
+            .scope(RefCell::new(None), async move {
+                JOB_CONTEXT.scope(self, f).await;
+                match RUN_FAILURE.with(|failure| failure.take()) {
+                    Some(failure) => Err(failure),
+                    None => Ok(()),
+                }
+            })
+            .await
+    }
+
+    ///
+    /// Mark the run going on this task as failed, for the runs of fallible jobs
+    pub(crate) fn fail(failure: JobError) {
+        // Like a job run directly with `Job::run`
+        if RUN_FAILURE.try_with(|_| ()).is_err() {
+            error!("Run failed outside of the runner {}", failure);
+            return;
+        }
+        RUN_FAILURE.with(|run| *run.borrow_mut() = Some(failure));
+    }
+}
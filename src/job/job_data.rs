@@ -1,17 +1,55 @@
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct CronJob {
     pub schedule: String,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct NonCronJob {
     pub repeating: bool,
     pub repeated_every: u64,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct Uuid {
     pub id1: u64,
     pub id2: u64,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct Overlap {
+    pub kind: i32,
+    pub max_depth: u32,
+    pub drop_oldest: bool,
+}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct After {
+    pub job_id: ::core::option::Option<Uuid>,
+    pub delay_ms: u64,
+}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct TimeWindow {
+    pub start_second: u32,
+    pub end_second: u32,
+}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct Exclusions {
+    pub dates: Vec<i32>,
+    pub weekdays: Vec<u32>,
+    pub windows: Vec<TimeWindow>,
+    pub defer: bool,
+}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct IntervalBounds {
+    pub first: u64,
+    pub end: u64,
+    pub max_runs: u32,
+}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct JobStoredData {
     pub id: ::core::option::Option<Uuid>,
@@ -25,10 +63,26 @@ pub struct JobStoredData {
     pub stopped: bool,
     pub job: ::core::option::Option<job_stored_data::Job>,
     pub time_offset_seconds: i32,
+    pub overlap: ::core::option::Option<Overlap>,
+    pub jitter_seconds: u32,
+    pub tags: Vec<String>,
+    pub queue: String,
+    pub order_key: i64,
+    pub timezone: String,
+    pub misfire: i32,
+    pub timeout_ms: u64,
+    pub name: String,
+    pub priority: i32,
+    pub random_jitter_seconds: u32,
+    pub after: ::core::option::Option<After>,
+    pub exclusions: ::core::option::Option<Exclusions>,
+    pub interval_bounds: ::core::option::Option<IntervalBounds>,
+    pub paused: bool,
 }
 
 /// Nested message and enum types in `JobStoredData`.
 pub mod job_stored_data {
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[derive(Clone, PartialEq, Debug)]
     #[repr(i32)]
     pub enum Job {
@@ -36,42 +90,55 @@ pub mod job_stored_data {
         NonCronJob(super::NonCronJob),
     }
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct JobIdAndNotification {
     pub job_id: ::core::option::Option<Uuid>,
     pub notification_id: ::core::option::Option<Uuid>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct NotificationData {
     pub job_id: ::core::option::Option<JobIdAndNotification>,
     pub job_states: Vec<i32>,
     pub extra: Vec<u8>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(dead_code)]
 #[derive(Clone, PartialEq, Debug)]
 pub struct NotificationIdAndState {
     pub notification_id: ::core::option::Option<Uuid>,
     pub job_state: i32,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct JobAndNextTick {
     pub id: ::core::option::Option<Uuid>,
     pub job_type: i32,
     pub next_tick: u64,
     pub last_tick: ::core::option::Option<u64>,
+    pub order_key: i64,
+    pub priority: i32,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct ListOfUuids {
     pub uuids: Vec<Uuid>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(dead_code)]
 #[derive(Clone, PartialEq, Debug)]
 pub struct JobAndNotifications {
     pub job_id: ::core::option::Option<Uuid>,
     pub notification_ids: Vec<Uuid>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(dead_code)]
 #[derive(Clone, PartialEq, Debug)]
 pub struct ListOfJobsAndNotifications {
     pub job_and_notifications: Vec<JobAndNotifications>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, FromPrimitive, ToPrimitive)]
 #[repr(i32)]
 pub enum JobState {
@@ -80,7 +147,12 @@ pub enum JobState {
     Started = 2,
     Done = 3,
     Removed = 4,
+    Failed = 5,
+    Retrying = 6,
+    TimedOut = 7,
+    Cancelled = 8,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, FromPrimitive, ToPrimitive)]
 #[repr(i32)]
 pub enum JobType {
@@ -88,6 +160,24 @@ pub enum JobType {
     Repeated = 1,
     OneShot = 2,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, FromPrimitive, ToPrimitive)]
+#[repr(i32)]
+pub enum OverlapKind {
+    Allow = 0,
+    Skip = 1,
+    Queue = 2,
+    CancelPrevious = 3,
+}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, FromPrimitive, ToPrimitive)]
+#[repr(i32)]
+pub enum MisfireKind {
+    Unset = 0,
+    FireOnce = 1,
+    FireAll = 2,
+    Ignore = 3,
+}
 
 impl JobState {
     pub fn from_i32(x: i32) -> Option<Self> {
@@ -97,6 +187,10 @@ impl JobState {
             2 => Some(Self::Started),
             3 => Some(Self::Done),
             4 => Some(Self::Removed),
+            5 => Some(Self::Failed),
+            6 => Some(Self::Retrying),
+            7 => Some(Self::TimedOut),
+            8 => Some(Self::Cancelled),
             _ => None,
         }
     }
@@ -113,6 +207,30 @@ impl JobType {
     }
 }
 
+impl OverlapKind {
+    pub fn from_i32(x: i32) -> Option<Self> {
+        match x {
+            0 => Some(Self::Allow),
+            1 => Some(Self::Skip),
+            2 => Some(Self::Queue),
+            3 => Some(Self::CancelPrevious),
+            _ => None,
+        }
+    }
+}
+
+impl MisfireKind {
+    pub fn from_i32(x: i32) -> Option<Self> {
+        match x {
+            0 => Some(Self::Unset),
+            1 => Some(Self::FireOnce),
+            2 => Some(Self::FireAll),
+            3 => Some(Self::Ignore),
+            _ => None,
+        }
+    }
+}
+
 impl From<JobState> for i32 {
     fn from(val: JobState) -> Self {
         val as i32
@@ -125,6 +243,18 @@ impl From<JobType> for i32 {
     }
 }
 
+impl From<OverlapKind> for i32 {
+    fn from(val: OverlapKind) -> Self {
+        val as i32
+    }
+}
+
+impl From<MisfireKind> for i32 {
+    fn from(val: MisfireKind) -> Self {
+        val as i32
+    }
+}
+
 impl JobStoredData {
     pub fn job_type(&self) -> JobType {
         JobType::from_i32(self.job_type).unwrap()
@@ -1,9 +1,11 @@
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CronJob {
     #[prost(string, tag = "1")]
     pub schedule: ::prost::alloc::string::String,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct NonCronJob {
@@ -12,6 +14,7 @@ pub struct NonCronJob {
     #[prost(uint64, tag = "2")]
     pub repeated_every: u64,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Uuid {
@@ -20,6 +23,60 @@ pub struct Uuid {
     #[prost(uint64, tag = "2")]
     pub id2: u64,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Overlap {
+    #[prost(enumeration = "OverlapKind", tag = "1")]
+    pub kind: i32,
+    #[prost(uint32, tag = "2")]
+    pub max_depth: u32,
+    #[prost(bool, tag = "3")]
+    pub drop_oldest: bool,
+}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct After {
+    #[prost(message, optional, tag = "1")]
+    pub job_id: ::core::option::Option<Uuid>,
+    #[prost(uint64, tag = "2")]
+    pub delay_ms: u64,
+}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TimeWindow {
+    #[prost(uint32, tag = "1")]
+    pub start_second: u32,
+    #[prost(uint32, tag = "2")]
+    pub end_second: u32,
+}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Exclusions {
+    #[prost(int32, repeated, tag = "1")]
+    pub dates: ::prost::alloc::vec::Vec<i32>,
+    #[prost(uint32, repeated, tag = "2")]
+    pub weekdays: ::prost::alloc::vec::Vec<u32>,
+    #[prost(message, repeated, tag = "3")]
+    pub windows: ::prost::alloc::vec::Vec<TimeWindow>,
+    #[prost(bool, tag = "4")]
+    pub defer: bool,
+}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IntervalBounds {
+    #[prost(uint64, tag = "1")]
+    pub first: u64,
+    #[prost(uint64, tag = "2")]
+    pub end: u64,
+    #[prost(uint32, tag = "3")]
+    pub max_runs: u32,
+}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct JobStoredData {
@@ -43,11 +100,42 @@ pub struct JobStoredData {
     pub stopped: bool,
     #[prost(int32, tag = "12")]
     pub time_offset_seconds: i32,
+    #[prost(message, optional, tag = "13")]
+    pub overlap: ::core::option::Option<Overlap>,
+    #[prost(uint32, tag = "14")]
+    pub jitter_seconds: u32,
+    #[prost(string, repeated, tag = "15")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(string, tag = "16")]
+    pub queue: ::prost::alloc::string::String,
+    #[prost(int64, tag = "17")]
+    pub order_key: i64,
+    #[prost(string, tag = "18")]
+    pub timezone: ::prost::alloc::string::String,
+    #[prost(enumeration = "MisfireKind", tag = "19")]
+    pub misfire: i32,
+    #[prost(uint64, tag = "20")]
+    pub timeout_ms: u64,
+    #[prost(string, tag = "21")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(int32, tag = "22")]
+    pub priority: i32,
+    #[prost(uint32, tag = "23")]
+    pub random_jitter_seconds: u32,
+    #[prost(message, optional, tag = "24")]
+    pub after: ::core::option::Option<After>,
+    #[prost(message, optional, tag = "25")]
+    pub exclusions: ::core::option::Option<Exclusions>,
+    #[prost(message, optional, tag = "26")]
+    pub interval_bounds: ::core::option::Option<IntervalBounds>,
+    #[prost(bool, tag = "27")]
+    pub paused: bool,
     #[prost(oneof = "job_stored_data::Job", tags = "6, 7")]
     pub job: ::core::option::Option<job_stored_data::Job>,
 }
 /// Nested message and enum types in `JobStoredData`.
 pub mod job_stored_data {
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Job {
@@ -57,6 +145,7 @@ pub mod job_stored_data {
         NonCronJob(super::NonCronJob),
     }
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct JobIdAndNotification {
@@ -65,6 +154,7 @@ pub struct JobIdAndNotification {
     #[prost(message, optional, tag = "2")]
     pub notification_id: ::core::option::Option<Uuid>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct NotificationData {
@@ -75,6 +165,7 @@ pub struct NotificationData {
     #[prost(bytes = "vec", tag = "3")]
     pub extra: ::prost::alloc::vec::Vec<u8>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct NotificationIdAndState {
@@ -83,6 +174,7 @@ pub struct NotificationIdAndState {
     #[prost(enumeration = "JobState", tag = "2")]
     pub job_state: i32,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct JobAndNextTick {
@@ -94,13 +186,19 @@ pub struct JobAndNextTick {
     pub next_tick: u64,
     #[prost(uint64, optional, tag = "4")]
     pub last_tick: ::core::option::Option<u64>,
+    #[prost(int64, tag = "5")]
+    pub order_key: i64,
+    #[prost(int32, tag = "6")]
+    pub priority: i32,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListOfUuids {
     #[prost(message, repeated, tag = "1")]
     pub uuids: ::prost::alloc::vec::Vec<Uuid>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct JobAndNotifications {
@@ -109,12 +207,14 @@ pub struct JobAndNotifications {
     #[prost(message, repeated, tag = "2")]
     pub notification_ids: ::prost::alloc::vec::Vec<Uuid>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListOfJobsAndNotifications {
     #[prost(message, repeated, tag = "1")]
     pub job_and_notifications: ::prost::alloc::vec::Vec<JobAndNotifications>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum JobState {
@@ -123,6 +223,10 @@ pub enum JobState {
     Started = 2,
     Done = 3,
     Removed = 4,
+    Failed = 5,
+    Retrying = 6,
+    TimedOut = 7,
+    Cancelled = 8,
 }
 impl JobState {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -136,6 +240,10 @@ impl JobState {
             JobState::Started => "Started",
             JobState::Done => "Done",
             JobState::Removed => "Removed",
+            JobState::Failed => "Failed",
+            JobState::Retrying => "Retrying",
+            JobState::TimedOut => "TimedOut",
+            JobState::Cancelled => "Cancelled",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -146,10 +254,15 @@ impl JobState {
             "Started" => Some(Self::Started),
             "Done" => Some(Self::Done),
             "Removed" => Some(Self::Removed),
+            "Failed" => Some(Self::Failed),
+            "Retrying" => Some(Self::Retrying),
+            "TimedOut" => Some(Self::TimedOut),
+            "Cancelled" => Some(Self::Cancelled),
             _ => None,
         }
     }
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum JobType {
@@ -179,3 +292,69 @@ impl JobType {
         }
     }
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum OverlapKind {
+    Allow = 0,
+    Skip = 1,
+    Queue = 2,
+    CancelPrevious = 3,
+}
+impl OverlapKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            OverlapKind::Allow => "Allow",
+            OverlapKind::Skip => "Skip",
+            OverlapKind::Queue => "Queue",
+            OverlapKind::CancelPrevious => "CancelPrevious",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "Allow" => Some(Self::Allow),
+            "Skip" => Some(Self::Skip),
+            "Queue" => Some(Self::Queue),
+            "CancelPrevious" => Some(Self::CancelPrevious),
+            _ => None,
+        }
+    }
+}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum MisfireKind {
+    Unset = 0,
+    FireOnce = 1,
+    FireAll = 2,
+    Ignore = 3,
+}
+impl MisfireKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            MisfireKind::Unset => "Unset",
+            MisfireKind::FireOnce => "FireOnce",
+            MisfireKind::FireAll => "FireAll",
+            MisfireKind::Ignore => "Ignore",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "Unset" => Some(Self::Unset),
+            "FireOnce" => Some(Self::FireOnce),
+            "FireAll" => Some(Self::FireAll),
+            "Ignore" => Some(Self::Ignore),
+            _ => None,
+        }
+    }
+}
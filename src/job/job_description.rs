@@ -0,0 +1,61 @@
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::JobState;
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::JobState;
+use crate::job::JobInfo;
+use crate::job_scheduler::JobsSchedulerLocked;
+use crate::JobStoredData;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+///
+/// A job as described by the metadata store, see `MetaDataStorage::describe`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JobDescription {
+    pub info: JobInfo,
+    /// Whether the job is stopped, for instance with `stop_graceful` or by its run budget
+    pub stopped: bool,
+    /// Whether the job is paused with `pause` until it's resumed
+    pub paused: bool,
+    /// The next fires of the job's schedule, starting with its next tick
+    pub upcoming: Vec<DateTime<Utc>>,
+}
+
+impl JobDescription {
+    ///
+    /// Describe a job with the next `upcoming` fires of its schedule
+    pub fn from_stored(data: &JobStoredData, upcoming: usize) -> Option<Self> {
+        let info = JobInfo::from_stored(data)?;
+        let mut fires = Vec::with_capacity(upcoming.min(16));
+        let mut next = info.next_tick;
+        while let Some(at) = next {
+            if fires.len() >= upcoming {
+                break;
+            }
+            fires.push(at);
+            next = JobsSchedulerLocked::fire_after(data, at);
+        }
+        Some(Self {
+            info,
+            stopped: data.stopped,
+            paused: data.paused,
+            upcoming: fires,
+        })
+    }
+}
+
+///
+/// What the scheduler knows about a job right now, as given by `list_jobs`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JobStatus {
+    pub description: JobDescription,
+    /// Whether a run of the job is going
+    pub running: bool,
+    /// Whether the job is paused with `pause`, or with `pause_until` and the pause isn't over yet
+    pub paused: bool,
+    /// `JobState::Done` when the last run that finished went well, `JobState::Failed` when it
+    /// failed or timed out, None before the first run finished
+    pub last_run: Option<JobState>,
+    /// How long the last run that finished took
+    pub last_duration: Option<Duration>,
+}
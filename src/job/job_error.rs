@@ -0,0 +1,61 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+
+///
+/// A failed run of a job, as passed to the handler set with `set_job_failure_handler`. The cause
+/// can be downcast, a panicking job gives `JobSchedulerError::JobPanicked`, a run cancelled by the
+/// runtime shutting down `JobSchedulerError::RunCancelled`, a run going over the job's timeout
+/// `JobSchedulerError::RunTimedOut` and a job going over its budget
+/// `JobSchedulerError::RunBudgetExceeded`. A fallible job's run gives the error it returned.
+///
+/// ```rust,no_run
+/// # use tokio_cron_scheduler::{JobError, JobScheduler, JobSchedulerError};
+/// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+/// sched.set_job_failure_handler(Box::new(|failure: JobError| {
+///     Box::pin(async move {
+///         if let Some(JobSchedulerError::JobPanicked(message)) = failure.error.downcast_ref() {
+///             eprintln!("{} panicked: {}", failure.job_id, message);
+///         }
+///     })
+/// })).await;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct JobError {
+    pub job_id: Uuid,
+    /// The attempt of the run that failed, counting from 1
+    pub attempt: u32,
+    pub error: Box<dyn Error + Send + Sync>,
+}
+
+impl JobError {
+    pub fn new<E: Into<Box<dyn Error + Send + Sync>>>(
+        job_id: Uuid,
+        attempt: u32,
+        error: E,
+    ) -> Self {
+        Self {
+            job_id,
+            attempt,
+            error: error.into(),
+        }
+    }
+}
+
+impl Display for JobError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Job {} failed on attempt {}: {}",
+            self.job_id, self.attempt, self.error
+        )
+    }
+}
+
+impl Error for JobError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.error.as_ref())
+    }
+}
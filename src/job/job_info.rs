@@ -0,0 +1,77 @@
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::JobType;
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::JobType;
+use crate::job::{AfterJob, Calendar, IntervalSchedule, MisfirePolicy};
+use crate::JobStoredData;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use uuid::Uuid;
+
+///
+/// A snapshot of a job's metadata as found in the metadata store
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JobInfo {
+    pub id: Uuid,
+    /// The name the job was given with `JobBuilder::with_name`
+    pub name: Option<String>,
+    pub job_type: JobType,
+    pub schedule: Option<String>,
+    pub repeated_every: Option<u64>,
+    /// The interval schedule of a repeated job, from its first fire on, see
+    /// `JobBuilder::with_interval`
+    pub interval: Option<IntervalSchedule>,
+    pub next_tick: Option<DateTime<Utc>>,
+    pub last_tick: Option<DateTime<Utc>>,
+    pub time_offset_seconds: i32,
+    /// The name of the timezone the cron schedule follows, see `Job::new_tz`
+    pub timezone: Option<String>,
+    pub tags: Vec<String>,
+    /// The queue the job's runs are sent to, see `add_queue`
+    pub queue: Option<String>,
+    /// Where the job goes among the jobs due at the same time, see `JobBuilder::with_order_key`
+    pub order_key: i64,
+    /// Which runs go first when they wait for a concurrency limit, see `JobBuilder::with_priority`
+    pub priority: i32,
+    /// The job this job is fired after, see `JobBuilder::with_after_job`
+    pub after: Option<AfterJob>,
+    /// When the job doesn't fire, see `JobBuilder::with_calendar`
+    pub calendar: Option<Calendar>,
+    /// What happens with the missed fires of the job, see `JobBuilder::with_misfire_policy`
+    pub misfire_policy: Option<MisfirePolicy>,
+    /// How long a run of the job may take, see `JobBuilder::with_timeout`
+    pub timeout: Option<Duration>,
+}
+
+impl JobInfo {
+    pub(crate) fn from_stored(data: &JobStoredData) -> Option<Self> {
+        let id: Uuid = data.id.as_ref()?.into();
+        let job_type = JobType::from_i32(data.job_type)?;
+        Some(Self {
+            id,
+            name: Some(data.name.clone()).filter(|name| !name.is_empty()),
+            job_type,
+            schedule: data.schedule().map(|s| s.to_string()),
+            repeated_every: match job_type {
+                JobType::Repeated => data.repeated_every(),
+                _ => None,
+            },
+            interval: match (data.repeated_every(), data.interval_bounds.as_ref()) {
+                (Some(every), Some(bounds)) => Some(IntervalSchedule::from_stored(every, bounds)),
+                _ => None,
+            },
+            next_tick: data.next_tick_utc(),
+            last_tick: data.last_tick_utc(),
+            time_offset_seconds: data.time_offset_seconds,
+            timezone: Some(data.timezone.clone()).filter(|tz| !tz.is_empty()),
+            tags: data.tags.clone(),
+            queue: Some(data.queue.clone()).filter(|q| !q.is_empty()),
+            order_key: data.order_key,
+            priority: data.priority,
+            after: data.after.as_ref().and_then(AfterJob::from_stored),
+            calendar: data.exclusions.as_ref().map(Calendar::from_stored),
+            misfire_policy: MisfirePolicy::from_i32(data.misfire),
+            timeout: data.timeout(),
+        })
+    }
+}
@@ -0,0 +1,118 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+
+pub(crate) type QueuedRun = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+///
+/// How busy a queue added with `add_queue` is
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueueStats {
+    pub workers: usize,
+    /// How many runs may wait for a worker
+    pub capacity: usize,
+    /// How many runs are waiting for a worker
+    pub depth: usize,
+    /// How many runs the workers are busy with
+    pub in_flight: usize,
+}
+
+///
+/// A number of workers running the jobs sent to a queue, and at most `capacity` runs waiting for
+/// them. The workers stop once the queue is dropped and the runs waiting in it are done.
+pub(crate) struct JobQueue {
+    tx: mpsc::UnboundedSender<QueuedRun>,
+    workers: usize,
+    capacity: usize,
+    // Runs waiting or being run
+    taken: Arc<AtomicUsize>,
+    in_flight: Arc<AtomicUsize>,
+}
+
+///
+/// A place taken in a queue, given back when it's dropped without a run sent
+pub(crate) struct QueuePlace {
+    tx: mpsc::UnboundedSender<QueuedRun>,
+    taken: Arc<AtomicUsize>,
+    sent: bool,
+}
+
+impl QueuePlace {
+    pub(crate) fn send(mut self, run: QueuedRun) {
+        self.sent = self.tx.send(run).is_ok();
+    }
+}
+
+impl Drop for QueuePlace {
+    fn drop(&mut self) {
+        if !self.sent {
+            self.taken.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+impl JobQueue {
+    pub(crate) fn new(workers: usize, capacity: usize) -> Self {
+        let workers = workers.max(1);
+        let (tx, rx) = mpsc::unbounded_channel::<QueuedRun>();
+        let rx = Arc::new(Mutex::new(rx));
+        let taken = Arc::new(AtomicUsize::new(0));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        for _ in 0..workers {
+            let rx = rx.clone();
+            let taken = taken.clone();
+            let in_flight = in_flight.clone();
+            tokio::spawn(async move {
+                loop {
+                    let run = {
+                        let mut rx = rx.lock().await;
+                        rx.recv().await
+                    };
+                    let run = match run {
+                        Some(run) => run,
+                        None => break,
+                    };
+                    in_flight.fetch_add(1, Ordering::SeqCst);
+                    run.await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    taken.fetch_sub(1, Ordering::SeqCst);
+                }
+            });
+        }
+        Self {
+            tx,
+            workers,
+            capacity,
+            taken,
+            in_flight,
+        }
+    }
+
+    ///
+    /// Take a place in the queue, None when all workers are busy and `capacity` runs are waiting
+    pub(crate) fn reserve(&self) -> Option<QueuePlace> {
+        let max = self.workers + self.capacity;
+        self.taken
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |taken| {
+                (taken < max).then_some(taken + 1)
+            })
+            .ok()?;
+        Some(QueuePlace {
+            tx: self.tx.clone(),
+            taken: self.taken.clone(),
+            sent: false,
+        })
+    }
+
+    pub(crate) fn stats(&self) -> QueueStats {
+        let in_flight = self.in_flight.load(Ordering::SeqCst);
+        QueueStats {
+            workers: self.workers,
+            capacity: self.capacity,
+            depth: self.taken.load(Ordering::SeqCst).saturating_sub(in_flight),
+            in_flight,
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::job::{nop, nop_async, IntervalSchedule, JobBuilder, JobLocked};
+use crate::{JobSchedulerError, JobToRun, JobToRunAsync};
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+///
+/// When a job runs, as plain data so jobs can be read from configuration. Turned into a job with
+/// `Job::from_spec` or `Job::from_spec_async`.
+///
+/// With the `serde` feature it can be deserialized, tagged by `type`:
+///
+/// ```toml
+/// type = "cron"
+/// expr = "0 0 * * * *"
+/// tz = "+02:00"
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum JobSpec {
+    /// A cron schedule, in the timezone with the offset `tz` like `+02:00`, otherwise in UTC.
+    /// With the `timezone` feature `tz` can be a name like `Europe/Amsterdam` too, the schedule
+    /// then follows its daylight saving time.
+    Cron {
+        expr: String,
+        #[cfg_attr(feature = "serde", serde(default))]
+        tz: Option<String>,
+    },
+    /// Every `secs` seconds. When aligned the runs are on multiples of `secs` since the epoch,
+    /// so every 3600 seconds runs on the hour. The runs are from `start` on, before `end` and at
+    /// most `max_runs`, see `IntervalSchedule`.
+    Interval {
+        secs: u64,
+        #[cfg_attr(feature = "serde", serde(default))]
+        aligned: bool,
+        #[cfg_attr(feature = "serde", serde(default))]
+        start: Option<DateTime<Utc>>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        end: Option<DateTime<Utc>>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        max_runs: Option<u32>,
+    },
+    /// Once at `at`, or on the first tick when `at` has passed
+    OneShotAt { at: DateTime<Utc> },
+    /// Once, `secs` seconds from now
+    OneShotIn { secs: u64 },
+}
+
+impl JobSpec {
+    fn cron<TZ: TimeZone + 'static>(
+        expr: String,
+        timezone: TZ,
+        run: Option<Box<JobToRun>>,
+        run_async: Option<Box<JobToRunAsync>>,
+    ) -> Result<JobLocked, JobSchedulerError> {
+        let builder = JobBuilder::new()
+            .with_timezone(timezone)
+            .with_cron_job_type()
+            .with_schedule(expr.as_str())?;
+        match (run, run_async) {
+            (_, Some(run_async)) => builder.with_run_async(run_async),
+            (Some(run), None) => builder.with_run_sync(run),
+            (None, None) => builder,
+        }
+        .build()
+    }
+
+    pub(crate) fn build(
+        self,
+        run: Option<Box<JobToRun>>,
+        run_async: Option<Box<JobToRunAsync>>,
+    ) -> Result<JobLocked, JobSchedulerError> {
+        let async_job = run_async.is_some();
+        match self {
+            JobSpec::Cron { expr, tz } => {
+                #[cfg(feature = "timezone")]
+                if let Some(timezone) = tz.as_ref().and_then(|tz| tz.parse::<chrono_tz::Tz>().ok())
+                {
+                    return JobSpec::cron(expr, timezone, run, run_async);
+                }
+                let timezone = match tz {
+                    Some(tz) => FixedOffset::from_str(&tz)
+                        .map_err(|_| JobSchedulerError::InvalidTimezone(tz))?,
+                    None => FixedOffset::east_opt(0).unwrap(),
+                };
+                JobSpec::cron(expr, timezone, run, run_async)
+            }
+            JobSpec::Interval {
+                secs,
+                aligned,
+                start,
+                end,
+                max_runs,
+            } => {
+                let schedule = IntervalSchedule {
+                    every: Duration::from_secs(secs),
+                    aligned,
+                    start,
+                    end,
+                    max_runs,
+                };
+                let builder = JobBuilder::new().with_interval(schedule);
+                match (run, run_async) {
+                    (_, Some(run_async)) => builder.with_run_async(run_async),
+                    (Some(run), None) => builder.with_run_sync(run),
+                    (None, None) => builder.with_run_sync(Box::new(nop)),
+                }
+                .build()
+            }
+            JobSpec::OneShotAt { at } => JobLocked::make_new_one_shot_at_datetime(
+                at,
+                run.unwrap_or_else(|| Box::new(nop)),
+                run_async.unwrap_or_else(|| Box::new(nop_async)),
+                async_job,
+            ),
+            JobSpec::OneShotIn { secs } => JobLocked::make_new_one_shot_at_an_instant(
+                Instant::now() + Duration::from_secs(secs),
+                run.unwrap_or_else(|| Box::new(nop)),
+                run_async.unwrap_or_else(|| Box::new(nop_async)),
+                async_job,
+            ),
+        }
+    }
+}
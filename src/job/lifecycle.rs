@@ -0,0 +1,57 @@
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::JobState;
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::JobState;
+use crate::JobStoredData;
+use tracing::Span;
+use uuid::Uuid;
+
+#[cfg(feature = "tracing_spans")]
+const TARGET: &str = "tokio_cron_scheduler::lifecycle";
+
+///
+/// The span of a run of a job, with the job's id, name, schedule and tags and the number of the run.
+/// The job's own events go in it as well.
+#[cfg(feature = "tracing_spans")]
+pub(crate) fn run_span(job_id: Uuid, data: Option<&JobStoredData>, run: u64) -> Span {
+    use crate::JobType;
+
+    let job_type = data.and_then(|data| JobType::from_i32(data.job_type));
+    let schedule = data.and_then(|data| match job_type {
+        Some(JobType::Cron) => data.schedule().map(|s| s.to_string()),
+        Some(JobType::Repeated) => data
+            .repeated_every()
+            .map(|every| format!("@every {}s", every)),
+        _ => None,
+    });
+    tracing::info_span!(
+        target: TARGET,
+        "job_run",
+        job_id = %job_id,
+        name = data.map(|data| &*data.name).filter(|name| !name.is_empty()),
+        job_type = ?job_type,
+        schedule = schedule.as_deref(),
+        tags = ?data.map(|data| &data.tags),
+        run,
+    )
+}
+
+#[cfg(not(feature = "tracing_spans"))]
+pub(crate) fn run_span(_job_id: Uuid, _data: Option<&JobStoredData>, _run: u64) -> Span {
+    Span::none()
+}
+
+///
+/// An event for a job going into `state`, in the current span
+#[cfg(feature = "tracing_spans")]
+pub(crate) fn event(job_id: Uuid, state: JobState) {
+    match state {
+        JobState::Failed | JobState::TimedOut | JobState::Cancelled => {
+            tracing::warn!(target: TARGET, job_id = %job_id, state = ?state, "Job {:?}", state)
+        }
+        _ => tracing::info!(target: TARGET, job_id = %job_id, state = ?state, "Job {:?}", state),
+    }
+}
+
+#[cfg(not(feature = "tracing_spans"))]
+pub(crate) fn event(_job_id: Uuid, _state: JobState) {}
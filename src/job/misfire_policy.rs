@@ -0,0 +1,41 @@
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::MisfireKind;
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::MisfireKind;
+
+///
+/// What to do with the fires of a job that were missed, because the scheduler was down or its
+/// loop was held up. A fire is missed when it was due before the previous pass of the scheduler
+/// loop or before the loop started, or when the job has come due again since. Without a policy a
+/// cron job fires once and a repeated job fires on every pass until it's back on schedule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MisfirePolicy {
+    /// Run once for all the missed fires
+    FireOnce,
+    /// Run once for every missed fire. The runs go through the job's overlap policy,
+    /// `OverlapPolicy::queue` runs them one after another.
+    FireAll,
+    /// Pass the missed fires over
+    Ignore,
+}
+
+impl MisfirePolicy {
+    pub(crate) fn from_i32(kind: i32) -> Option<Self> {
+        match MisfireKind::from_i32(kind) {
+            Some(MisfireKind::FireOnce) => Some(MisfirePolicy::FireOnce),
+            Some(MisfireKind::FireAll) => Some(MisfirePolicy::FireAll),
+            Some(MisfireKind::Ignore) => Some(MisfirePolicy::Ignore),
+            _ => None,
+        }
+    }
+}
+
+impl From<MisfirePolicy> for i32 {
+    fn from(policy: MisfirePolicy) -> Self {
+        match policy {
+            MisfirePolicy::FireOnce => MisfireKind::FireOnce.into(),
+            MisfirePolicy::FireAll => MisfireKind::FireAll.into(),
+            MisfirePolicy::Ignore => MisfireKind::Ignore.into(),
+        }
+    }
+}
@@ -8,32 +8,76 @@ use chrono::{DateTime, Offset, TimeZone, Utc};
 use cron::Schedule;
 use cron_job::CronJob;
 use non_cron_job::NonCronJob;
+use std::any::Any;
 use std::convert::TryInto;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::{Arc, RwLock};
+use std::task::Poll;
 use std::time::{Duration, Instant, SystemTime};
-use tokio::sync::oneshot::Receiver;
+use tokio::task::LocalSet;
 use tracing::error;
 use uuid::Uuid;
 
+mod after_job;
 mod builder;
+mod calendar;
+mod concurrency_limit;
 mod creator;
 mod cron_job;
+pub(crate) mod cron_steps;
 mod deleter;
+mod first_fire;
+pub(crate) mod hashed_schedule;
+mod interval_schedule;
+mod job_context;
 #[cfg(not(feature = "has_bytes"))]
 pub mod job_data;
 #[cfg(feature = "has_bytes")]
 pub mod job_data_prost;
+mod job_description;
+mod job_error;
+mod job_info;
+mod job_queue;
+mod job_spec;
+pub(crate) mod lifecycle;
+mod misfire_policy;
 mod non_cron_job;
+mod overlap_policy;
+pub(crate) mod random;
+mod rate_limit;
+mod retry_policy;
+mod run_budget;
+mod run_gap;
 mod runner;
 pub mod to_code;
 
 use crate::notification::{NotificationCreator, NotificationDeleter};
+pub use after_job::AfterJob;
 pub use builder::JobBuilder;
+pub use calendar::{Calendar, CalendarAction};
+pub(crate) use concurrency_limit::ConcurrencyLimit;
 pub use creator::JobCreator;
+pub use cron_steps::{parse_cron, preview_cron, validate_cron};
 pub use deleter::JobDeleter;
+pub(crate) use first_fire::FirstFire;
+pub use interval_schedule::IntervalSchedule;
+pub use job_context::{JobContext, JobPayload};
+pub use job_description::{JobDescription, JobStatus};
+pub use job_error::JobError;
+pub use job_info::JobInfo;
+pub(crate) use job_queue::JobQueue;
+pub use job_queue::QueueStats;
+pub use job_spec::JobSpec;
+pub use misfire_policy::MisfirePolicy;
+pub use overlap_policy::{OverlapPolicy, QueueDrop};
+pub(crate) use rate_limit::TokenBucket;
+pub use rate_limit::{OnRateLimited, RateLimiter};
+pub use retry_policy::{Backoff, RetryPolicy};
+pub use run_budget::{OnBudgetExceeded, RunBudget};
+pub(crate) use run_gap::RunGap;
 pub use runner::JobRunner;
+pub(crate) use runner::{CatchUnwind, Overlaps};
 
 pub type JobId = Uuid;
 pub type NotificationId = Uuid;
@@ -42,6 +86,13 @@ pub type JobToRun = dyn FnMut(JobId, JobsSchedulerLocked) + Send + Sync;
 pub type JobToRunAsync =
     dyn FnMut(JobId, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync;
 
+pub type SharedJobToRunAsync =
+    dyn Fn(JobId, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync;
+
+pub type JobToRunWithContext = dyn Fn(JobContext, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>>
+    + Send
+    + Sync;
+
 pub type OnJobNotification = dyn FnMut(JobId, NotificationId, JobState) -> Pin<Box<dyn Future<Output = ()> + Send>>
     + Send
     + Sync;
@@ -59,6 +110,7 @@ fn nop_async(_uuid: Uuid, _jobs: JobsSchedulerLocked) -> Pin<Box<dyn Future<Outp
 #[derive(Clone)]
 pub struct JobLocked(pub(crate) Arc<RwLock<Box<dyn Job + Send + Sync>>>);
 
+#[allow(dead_code)]
 pub trait Job {
     fn is_cron_job(&self) -> bool;
     fn schedule(&self) -> Option<Schedule>;
@@ -79,8 +131,61 @@ pub trait Job {
     fn set_started(&mut self);
     fn job_data_from_job(&mut self) -> Result<Option<JobStoredData>, JobSchedulerError>;
     fn set_job_data(&mut self, job_data: JobStoredData) -> Result<(), JobSchedulerError>;
-    fn run(&mut self, jobs: JobScheduler) -> Receiver<bool>;
+    fn run(&mut self, jobs: JobScheduler) -> Pin<Box<dyn Future<Output = ()> + Send>>;
     fn fixed_offset_west(&self) -> i32;
+    ///
+    /// How the job works out its first fire when it's added
+    fn first_fire(&self) -> FirstFire;
+    ///
+    /// Whether the job runs async code rather than sync code
+    fn is_async(&self) -> bool;
+    ///
+    /// The run of the job without the job data, which is kept in the metadata store once the job
+    /// is added
+    fn into_run(self: Box<Self>) -> Box<JobToRunAsync>;
+}
+
+///
+/// The name of a `chrono_tz` timezone, so the job's fires follow its daylight saving time. Other
+/// timezones give an empty name, those are kept as their offset from UTC when the job is made.
+#[cfg(feature = "timezone")]
+pub(crate) fn named_timezone<TZ: TimeZone + 'static>(timezone: &TZ) -> String {
+    (timezone as &dyn std::any::Any)
+        .downcast_ref::<chrono_tz::Tz>()
+        .map(|tz| tz.name().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(not(feature = "timezone"))]
+pub(crate) fn named_timezone<TZ: TimeZone + 'static>(_timezone: &TZ) -> String {
+    String::new()
+}
+
+///
+/// Either run as one job to run, called when the returned future is first polled so a
+/// panicking sync run is caught like a failing async run
+pub(crate) fn run_only(
+    run: Box<JobToRun>,
+    run_async: Box<JobToRunAsync>,
+    async_job: bool,
+) -> Box<JobToRunAsync> {
+    let runs = Arc::new(std::sync::Mutex::new((run, run_async)));
+    Box::new(move |job_id, job_scheduler| {
+        let runs = runs.clone();
+        Box::pin(async move {
+            let job_done = {
+                // A panicking sync run poisons the lock, it was reported and the job can run again
+                let mut w = runs.lock().unwrap_or_else(|e| e.into_inner());
+                if async_job {
+                    (w.1)(job_id, job_scheduler)
+                } else {
+                    (w.0)(job_id, job_scheduler);
+                    Box::pin(std::future::ready(()))
+                }
+            };
+            job_done.await;
+        })
+    })
 }
 
 impl JobLocked {
@@ -106,7 +211,9 @@ impl JobLocked {
         Self::new_tz(schedule, Utc, run)
     }
 
-    /// Create a new cron job at a timezone.
+    /// Create a new cron job at a timezone. With the `timezone` feature a `chrono_tz::Tz` is
+    /// followed through daylight saving time, other timezones are kept as the offset they have
+    /// when the job is made.
     ///
     /// ```rust,ignore
     /// let mut sched = JobScheduler::new();
@@ -123,16 +230,18 @@ impl JobLocked {
         T: 'static,
         T: FnMut(Uuid, JobsSchedulerLocked) + Send + Sync,
         S: TryInto<Schedule, Error = E>,
-        TZ: TimeZone,
+        TZ: TimeZone + 'static,
         E: std::error::Error + 'static,
     {
         let time_offset_seconds = timezone
             .offset_from_utc_datetime(&Utc::now().naive_local())
             .fix()
             .local_minus_utc();
+        let named = named_timezone(&timezone);
         let schedule: Schedule = schedule
             .try_into()
             .map_err(|_| JobSchedulerError::ParseSchedule)?;
+        cron_steps::validate_steps(&schedule.to_string())?;
         let job_id = Uuid::new_v4();
         Ok(Self(Arc::new(RwLock::new(Box::new(CronJob {
             data: JobStoredData {
@@ -160,10 +269,28 @@ impl JobLocked {
                     schedule: schedule.to_string(),
                 })),
                 time_offset_seconds,
+                overlap: None,
+                jitter_seconds: 0,
+                tags: vec![],
+                queue: String::new(),
+                order_key: 0,
+                timezone: named,
+                misfire: 0,
+                timeout_ms: 0,
+                name: String::new(),
+                priority: 0,
+                random_jitter_seconds: 0,
+                after: None,
+                exclusions: None,
+                interval_bounds: None,
+                paused: false,
             },
             run: Box::new(run),
             run_async: Box::new(nop_async),
             async_job: false,
+            first_fire: FirstFire::Cron {
+                delay: chrono::Duration::zero(),
+            },
         })))))
     }
 
@@ -214,16 +341,18 @@ impl JobLocked {
             + Send
             + Sync,
         S: TryInto<Schedule, Error = E>,
-        TZ: TimeZone,
+        TZ: TimeZone + 'static,
         E: std::error::Error + 'static,
     {
         let time_offset_seconds = timezone
             .offset_from_utc_datetime(&Utc::now().naive_local())
             .fix()
             .local_minus_utc();
+        let named = named_timezone(&timezone);
         let schedule: Schedule = schedule
             .try_into()
             .map_err(|_| JobSchedulerError::ParseSchedule)?;
+        cron_steps::validate_steps(&schedule.to_string())?;
         let job_id = Uuid::new_v4();
         Ok(Self(Arc::new(RwLock::new(Box::new(CronJob {
             data: JobStoredData {
@@ -251,13 +380,156 @@ impl JobLocked {
                     schedule: schedule.to_string(),
                 })),
                 time_offset_seconds,
+                overlap: None,
+                jitter_seconds: 0,
+                tags: vec![],
+                queue: String::new(),
+                order_key: 0,
+                timezone: named,
+                misfire: 0,
+                timeout_ms: 0,
+                name: String::new(),
+                priority: 0,
+                random_jitter_seconds: 0,
+                after: None,
+                exclusions: None,
+                interval_bounds: None,
+                paused: false,
             },
             run: Box::new(nop),
             run_async: Box::new(run),
             async_job: true,
+            first_fire: FirstFire::Cron {
+                delay: chrono::Duration::zero(),
+            },
         })))))
     }
 
+    /// Create a new async cron job whose run can fail. A run that returns Err is failed like a
+    /// panicking run: it's passed to the job failure handler, with the attempt filled in by the
+    /// scheduler, and retried when the job has a `RetryPolicy`.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::{Backoff, Job, JobError, JobScheduler, RetryPolicy};
+    /// # async fn upload() -> Result<(), std::io::Error> {
+    /// #     Ok(())
+    /// # }
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// let job = Job::new_async_fallible("0 * * * * *", |uuid, _l| Box::pin(async move {
+    ///     upload().await.map_err(|e| JobError::new(uuid, 1, e))
+    /// }))?;
+    /// let job_id = sched.add(job).await?;
+    /// sched.set_retry_policy(&job_id, Some(RetryPolicy {
+    ///     max_attempts: 5,
+    ///     backoff: Backoff::Exponential { initial: Duration::from_secs(1), max: Duration::from_secs(30) },
+    ///     jitter: Duration::from_millis(500),
+    /// })).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_async_fallible<S, T, E>(schedule: S, mut run: T) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(
+                Uuid,
+                JobsSchedulerLocked,
+            ) -> Pin<Box<dyn Future<Output = Result<(), JobError>> + Send>>
+            + Send
+            + Sync,
+        S: TryInto<Schedule, Error = E>,
+        E: std::error::Error + 'static,
+    {
+        Self::new_async(schedule, move |job_id, job_scheduler| {
+            let ran = run(job_id, job_scheduler);
+            Box::pin(async move {
+                if let Err(e) = ran.await {
+                    JobContext::fail(e);
+                }
+            })
+        })
+    }
+
+    /// Create a new async cron job whose closure and future don't have to be `Send`.
+    /// Runs are spawned on the given `LocalSet`, so they only make progress while that
+    /// `LocalSet` is being driven, e.g. by `LocalSet::run_until` or by awaiting it.
+    ///
+    /// The scheduler waits for the run on the `LocalSet` as for any other run, so the overlap
+    /// policy, the concurrency limits, the timeout, the done notifications and the job failure
+    /// handler all follow the local run. A panicking local run fails with
+    /// `JobSchedulerError::JobPanicked`, a run that's dropped, like one that timed out, is
+    /// dropped on the `LocalSet` as well, and a run that can't finish because the `LocalSet` is
+    /// gone fails with `JobSchedulerError::RunCancelled`.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{Job, JobScheduler};
+    /// # use std::cell::RefCell;
+    /// # use std::rc::Rc;
+    /// # use tokio::task::LocalSet;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// let local = LocalSet::new();
+    /// let cache = Rc::new(RefCell::new(0));
+    /// let job = Job::new_local(&local, "1/10 * * * * *", move |_uuid, _lock| {
+    ///     let cache = cache.clone();
+    ///     Box::pin(async move {
+    ///         *cache.borrow_mut() += 1;
+    ///     })
+    /// })?;
+    /// sched.add(job).await?;
+    /// local.run_until(sched.start()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_local<S, T, E>(
+        local: &LocalSet,
+        schedule: S,
+        mut run: T,
+    ) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()>>>,
+        S: TryInto<Schedule, Error = E>,
+        E: std::error::Error + 'static,
+    {
+        type Ran = Result<(), Box<dyn Any + Send>>;
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<(
+            Uuid,
+            JobsSchedulerLocked,
+            tokio::sync::oneshot::Sender<Ran>,
+        )>();
+        local.spawn_local(async move {
+            while let Some((job_id, jobs, mut done)) = rx.recv().await {
+                let mut ran = CatchUnwind(Box::pin(run(job_id, jobs)));
+                tokio::task::spawn_local(async move {
+                    // Dropped once the scheduler no longer waits for the run
+                    let ran = std::future::poll_fn(|cx| match Pin::new(&mut ran).poll(cx) {
+                        Poll::Ready(ran) => Poll::Ready(Some(ran)),
+                        Poll::Pending => done.poll_closed(cx).map(|_| None),
+                    })
+                    .await;
+                    if let Some(ran) = ran {
+                        let _ = done.send(ran);
+                    }
+                });
+            }
+        });
+        Self::new_async(schedule, move |job_id, jobs| {
+            let (done, ran) = tokio::sync::oneshot::channel();
+            if let Err(e) = tx.send((job_id, jobs, done)) {
+                error!("Error handing {:?} to its local set {:?}", job_id, e);
+            }
+            Box::pin(async move {
+                match ran.await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(panic)) => std::panic::resume_unwind(panic),
+                    Err(_) => {
+                        JobContext::fail(JobError::new(job_id, 1, JobSchedulerError::RunCancelled))
+                    }
+                }
+            })
+        })
+    }
+
     /// Create a new cron job.
     ///
     /// ```rust,ignore
@@ -327,7 +599,7 @@ impl JobLocked {
             + Send
             + Sync,
         S: TryInto<Schedule, Error = E>,
-        TZ: TimeZone,
+        TZ: TimeZone + 'static,
         E: std::error::Error + 'static,
     {
         JobLocked::new_async_tz(schedule, timezone, run)
@@ -344,6 +616,7 @@ impl JobLocked {
             run,
             run_async,
             async_job,
+            first_fire: FirstFire::After(duration),
             data: JobStoredData {
                 id: Some(id.into()),
                 last_updated: None,
@@ -373,6 +646,21 @@ impl JobLocked {
                     },
                 )),
                 time_offset_seconds: 0,
+                overlap: None,
+                jitter_seconds: 0,
+                tags: vec![],
+                queue: String::new(),
+                order_key: 0,
+                timezone: String::new(),
+                misfire: 0,
+                timeout_ms: 0,
+                name: String::new(),
+                priority: 0,
+                random_jitter_seconds: 0,
+                after: None,
+                exclusions: None,
+                interval_bounds: None,
+                paused: false,
             },
         };
 
@@ -427,23 +715,48 @@ impl JobLocked {
         run: Box<JobToRun>,
         run_async: Box<JobToRunAsync>,
         async_job: bool,
+    ) -> Result<Self, JobSchedulerError> {
+        let at = chrono::Utc::now()
+            .checked_add_signed(chrono::Duration::seconds(
+                instant.duration_since(Instant::now()).as_secs() as i64,
+            ))
+            .ok_or(JobSchedulerError::NoNextTick)?;
+        JobLocked::make_new_one_shot_at(at, FirstFire::At(instant), run, run_async, async_job)
+    }
+
+    pub(crate) fn make_new_one_shot_at_datetime(
+        at: DateTime<Utc>,
+        run: Box<JobToRun>,
+        run_async: Box<JobToRunAsync>,
+        async_job: bool,
+    ) -> Result<Self, JobSchedulerError> {
+        JobLocked::make_new_one_shot_at(at, FirstFire::Kept, run, run_async, async_job)
+    }
+
+    fn make_new_one_shot_at(
+        at: DateTime<Utc>,
+        first_fire: FirstFire,
+        run: Box<JobToRun>,
+        run_async: Box<JobToRunAsync>,
+        async_job: bool,
     ) -> Result<Self, JobSchedulerError> {
         let id = Uuid::new_v4();
+        // What the wait was when the job was made, the fire itself is kept as `at`
+        let wait = (at - chrono::Utc::now()).num_seconds().max(0) as u64;
 
         let job = NonCronJob {
             run,
             run_async,
             async_job,
+            first_fire,
             data: JobStoredData {
                 id: Some(id.into()),
                 last_updated: None,
                 last_tick: None,
-                next_tick: chrono::Utc::now()
-                    .checked_add_signed(chrono::Duration::seconds(
-                        instant.duration_since(Instant::now()).as_secs() as i64,
-                    ))
-                    .map(|t| t.timestamp() as u64)
-                    .unwrap_or(0),
+                // Next ticks are in whole seconds, rounded up so the job doesn't fire before `at`.
+                // A time before 1970 would read as no next tick, it's fired right away instead.
+                next_tick: (at.timestamp() + i64::from(at.timestamp_subsec_nanos() > 0)).max(1)
+                    as u64,
                 job_type: JobType::OneShot.into(),
                 count: 0,
                 extra: vec![],
@@ -453,17 +766,32 @@ impl JobLocked {
                 job: Some(job_data_prost::job_stored_data::Job::NonCronJob(
                     job_data_prost::NonCronJob {
                         repeating: false,
-                        repeated_every: instant.duration_since(Instant::now()).as_secs(),
+                        repeated_every: wait,
                     },
                 )),
                 #[cfg(not(feature = "has_bytes"))]
                 job: Some(job_data::job_stored_data::Job::NonCronJob(
                     job_data::NonCronJob {
                         repeating: false,
-                        repeated_every: instant.duration_since(Instant::now()).as_secs(),
+                        repeated_every: wait,
                     },
                 )),
                 time_offset_seconds: 0,
+                overlap: None,
+                jitter_seconds: 0,
+                tags: vec![],
+                queue: String::new(),
+                order_key: 0,
+                timezone: String::new(),
+                misfire: 0,
+                timeout_ms: 0,
+                name: String::new(),
+                priority: 0,
+                random_jitter_seconds: 0,
+                after: None,
+                exclusions: None,
+                interval_bounds: None,
+                paused: false,
             },
         };
 
@@ -522,6 +850,126 @@ impl JobLocked {
         JobLocked::make_new_one_shot_at_an_instant(instant, Box::new(nop), Box::new(run), true)
     }
 
+    /// Create a new one shot job that runs at a date and time. The time is stored as it is, so
+    /// the job fires at it also when it's loaded again after a restart. A time that has passed
+    /// fires on the next pass of the scheduler loop.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{Job, JobScheduler};
+    /// # use chrono::{TimeZone, Utc};
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// let at = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+    /// let job = Job::new_one_shot_at_datetime(at, |_uuid, _lock| println!("Happy new year"))?;
+    /// sched.add(job).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_one_shot_at_datetime<T>(at: DateTime<Utc>, run: T) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) + Send + Sync,
+    {
+        JobLocked::make_new_one_shot_at_datetime(at, Box::new(run), Box::new(nop_async), false)
+    }
+
+    /// Create a new async one shot job that runs at a date and time, see
+    /// `new_one_shot_at_datetime`
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{Job, JobSchedulerError};
+    /// # struct Report {
+    /// #     due: chrono::DateTime<chrono::Utc>,
+    /// # }
+    /// # async fn send_report() {}
+    /// # fn example(report: Report) -> Result<(), JobSchedulerError> {
+    /// let job = Job::new_one_shot_at_datetime_async(report.due, |_uuid, _lock| Box::pin(async move {
+    ///     send_report().await;
+    /// }))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_one_shot_at_datetime_async<T>(
+        at: DateTime<Utc>,
+        run: T,
+    ) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync,
+    {
+        JobLocked::make_new_one_shot_at_datetime(at, Box::new(nop), Box::new(run), true)
+    }
+
+    /// Create a new job from a spec, for jobs read from configuration.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{Job, JobSchedulerError, JobSpec};
+    /// # fn example() -> Result<(), JobSchedulerError> {
+    /// let spec = JobSpec::Interval {
+    ///     secs: 3600,
+    ///     aligned: true,
+    ///     start: None,
+    ///     end: None,
+    ///     max_runs: None,
+    /// };
+    /// let job = Job::from_spec(spec, |_uuid, _lock| println!("I run on the hour"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_spec<T>(spec: JobSpec, run: T) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) + Send + Sync,
+    {
+        spec.build(Some(Box::new(run)), None)
+    }
+
+    /// Create a new async job from a spec, for jobs read from configuration.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{Job, JobSchedulerError, JobSpec};
+    /// # fn example() -> Result<(), JobSchedulerError> {
+    /// let spec = JobSpec::OneShotIn { secs: 20 };
+    /// let job = Job::from_spec_async(spec, |_uuid, _lock| Box::pin(async move {
+    ///     println!("I run once after 20 seconds")
+    /// }))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_spec_async<T>(spec: JobSpec, run: T) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync,
+    {
+        spec.build(None, Some(Box::new(run)))
+    }
+
+    ///
+    /// A job for metadata taken from another scheduler, running `run_async`
+    pub(crate) fn from_stored_data(data: JobStoredData, run_async: Box<JobToRunAsync>) -> Self {
+        let job: Box<dyn Job + Send + Sync + 'static> = if data.job_type == JobType::Cron as i32 {
+            Box::new(CronJob {
+                data,
+                run: Box::new(nop),
+                run_async,
+                async_job: true,
+                first_fire: FirstFire::Kept,
+            })
+        } else {
+            Box::new(NonCronJob {
+                data,
+                run: Box::new(nop),
+                run_async,
+                async_job: true,
+                first_fire: FirstFire::Kept,
+            })
+        };
+        Self(Arc::new(RwLock::new(job)))
+    }
+
     fn make_new_repeated(
         duration: Duration,
         run: Box<JobToRun>,
@@ -533,6 +981,10 @@ impl JobLocked {
             run,
             run_async,
             async_job,
+            first_fire: FirstFire::Repeated {
+                immediately: false,
+                delay: chrono::Duration::zero(),
+            },
             data: JobStoredData {
                 id: Some(id.into()),
                 last_updated: None,
@@ -562,6 +1014,21 @@ impl JobLocked {
                     },
                 )),
                 time_offset_seconds: 0,
+                overlap: None,
+                jitter_seconds: 0,
+                tags: vec![],
+                queue: String::new(),
+                order_key: 0,
+                timezone: String::new(),
+                misfire: 0,
+                timeout_ms: 0,
+                name: String::new(),
+                priority: 0,
+                random_jitter_seconds: 0,
+                after: None,
+                exclusions: None,
+                interval_bounds: None,
+                paused: false,
             },
         };
 
@@ -611,11 +1078,73 @@ impl JobLocked {
         JobLocked::make_new_repeated(duration, Box::new(nop), Box::new(run), true)
     }
 
+    /// Create a new job on an interval schedule, see `JobBuilder::with_interval`.
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::{IntervalSchedule, Job, JobScheduler};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let sched = JobScheduler::new().await?;
+    /// let schedule = IntervalSchedule::every(Duration::from_secs(90)).with_max_runs(10);
+    /// let job = Job::new_interval(schedule, |_uuid, _lock| {
+    ///     println!("{:?} I'm run every 90 seconds, 10 times", chrono::Utc::now());
+    /// })?;
+    /// sched.add(job).await?;
+    /// sched.start().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_interval<T>(schedule: IntervalSchedule, run: T) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) + Send + Sync,
+    {
+        JobBuilder::new()
+            .with_interval(schedule)
+            .with_run_sync(Box::new(run))
+            .build()
+    }
+
+    /// Create a new async job on an interval schedule, see `JobBuilder::with_interval`.
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::{IntervalSchedule, Job, JobScheduler};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let sched = JobScheduler::new().await?;
+    /// let schedule = IntervalSchedule::every(Duration::from_secs(900)).aligned();
+    /// let job = Job::new_interval_async(schedule, |_uuid, _lock| Box::pin(async move {
+    ///     println!("{:?} I'm run on every quarter of an hour", chrono::Utc::now());
+    /// }))?;
+    /// sched.add(job).await?;
+    /// sched.start().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_interval_async<T>(
+        schedule: IntervalSchedule,
+        run: T,
+    ) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync,
+    {
+        JobBuilder::new()
+            .with_interval(schedule)
+            .with_run_async(Box::new(run))
+            .build()
+    }
+
     ///
     /// The `tick` method returns a true if there was an invocation needed after it was last called
     /// This method will also change the last tick on itself
     pub fn tick(&mut self) -> Result<bool, JobSchedulerError> {
-        let now = Utc::now();
+        self.tick_at(Utc::now())
+    }
+
+    ///
+    /// `tick` with the time given, for instance by a `Clock`
+    pub fn tick_at(&mut self, now: DateTime<Utc>) -> Result<bool, JobSchedulerError> {
         let (job_type, last_tick, next_tick, schedule, repeated_every, ran, count) = {
             let r = self.0.read().map_err(|_| JobSchedulerError::TickError)?;
             (
@@ -657,10 +1186,20 @@ impl JobLocked {
             _ => false,
         };
 
+        let job_data = self.job_data();
+        if let Err(e) = job_data {
+            error!("Could not get job data");
+            return Err(e);
+        }
+        let job_data = job_data.unwrap();
+
         let next_tick = if must_run {
             match job_type {
                 JobType::Cron => schedule.and_then(|s| s.after(&now).next()),
                 JobType::OneShot => None,
+                JobType::Repeated if job_data.interval_bounds.is_some() => {
+                    job_data.next_interval_fire(now)
+                }
                 JobType::Repeated => repeated_every.and_then(|r| {
                     next_tick
                         .and_then(|nt| nt.checked_add_signed(chrono::Duration::seconds(r as i64)))
@@ -671,12 +1210,6 @@ impl JobLocked {
         };
         let last_tick = Some(now);
 
-        let job_data = self.job_data();
-        if let Err(e) = job_data {
-            error!("Could not get job data");
-            return Err(e);
-        }
-
         {
             let mut w = self.0.write().map_err(|_| JobSchedulerError::JobTick)?;
             w.set_next_tick(next_tick);
@@ -697,6 +1230,32 @@ impl JobLocked {
         Ok(must_run)
     }
 
+    ///
+    /// The run of the job, without the job data
+    pub(crate) fn into_run(self) -> Box<JobToRunAsync> {
+        match Arc::try_unwrap(self.0) {
+            // Nothing else holds on to the job, keep its run and leave its data to the store
+            Ok(job) => job
+                .into_inner()
+                .unwrap_or_else(|e| e.into_inner())
+                .into_run(),
+            Err(job) => {
+                let job = JobLocked(job);
+                Box::new(move |_job_id, job_scheduler| {
+                    let job = job.clone();
+                    Box::pin(async move {
+                        let job_done = {
+                            // A panicking sync run poisons the lock, it was reported and the job can run again
+                            let mut w = job.0.write().unwrap_or_else(|e| e.into_inner());
+                            w.run(job_scheduler)
+                        };
+                        job_done.await;
+                    })
+                })
+            }
+        }
+    }
+
     ///
     /// Get the GUID for the job
     ///
@@ -792,6 +1351,101 @@ impl JobLocked {
             .map(|(_, deleted)| deleted)
     }
 
+    ///
+    /// Run something when a run of the task is complete, with `JobState::Done` when it went well
+    /// `JobState::Failed` when it failed, `JobState::TimedOut` when it took longer than the
+    /// job's timeout and `JobState::Cancelled` when `OverlapPolicy::CancelPrevious` cancelled it. Meant for one shot jobs, whose single run is
+    /// complete before the job is removed: the complete notification goes out first, then the
+    /// removed notification. Returns a UUID as handle for this notification. This UUID needs to
+    /// be used when you want to remove the notification handle using `on_complete_notification_remove`.
+    ///
+    /// ```rust,no_run
+    /// # use std::sync::Arc;
+    /// # use tokio_cron_scheduler::{Job, JobNotification, JobScheduler};
+    /// # async fn example(mut sched: JobScheduler, mut job: Job) -> Result<(), Box<dyn std::error::Error>> {
+    /// let (tx, rx) = tokio::sync::oneshot::channel();
+    /// let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
+    /// job.on_complete_notification_add(&sched, Box::new(move |_job_id, _notification_id, state| {
+    ///     if let Some(tx) = tx.lock().unwrap().take() {
+    ///         let _ = tx.send(state == JobNotification::Done);
+    ///     }
+    ///     Box::pin(async move {})
+    /// })).await?;
+    /// sched.add(job).await?;
+    /// let succeeded = rx.await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn on_complete_notification_add(
+        &mut self,
+        job_scheduler: &JobsSchedulerLocked,
+        on_complete: Box<OnJobNotification>,
+    ) -> Result<Uuid, JobSchedulerError> {
+        self.on_notifications_add(
+            job_scheduler,
+            on_complete,
+            vec![
+                JobState::Done,
+                JobState::Failed,
+                JobState::TimedOut,
+                JobState::Cancelled,
+            ],
+        )
+        .await
+    }
+
+    ///
+    /// Remove the notification when a run of the task was complete. Uses the same UUID that was
+    /// returned by `on_complete_notification_add`
+    pub async fn on_complete_notification_remove(
+        &mut self,
+        job_scheduler: &JobsSchedulerLocked,
+        notification_id: &Uuid,
+    ) -> Result<bool, JobSchedulerError> {
+        self.on_notification_removal(
+            job_scheduler,
+            notification_id,
+            Some(vec![
+                JobState::Done,
+                JobState::Failed,
+                JobState::TimedOut,
+                JobState::Cancelled,
+            ]),
+        )
+        .await
+        .map(|(_, deleted)| deleted)
+    }
+
+    ///
+    /// Run something when a run of the task was cancelled for taking longer than its timeout, see
+    /// `JobBuilder::with_timeout`. Returns a UUID as handle for this notification. This UUID needs
+    /// to be used when you want to remove the notification handle using `on_timed_out_notification_remove`.
+    pub async fn on_timed_out_notification_add(
+        &mut self,
+        job_scheduler: &JobsSchedulerLocked,
+        on_timed_out: Box<OnJobNotification>,
+    ) -> Result<Uuid, JobSchedulerError> {
+        self.on_notifications_add(job_scheduler, on_timed_out, vec![JobState::TimedOut])
+            .await
+    }
+
+    ///
+    /// Remove the notification when a run of the task timed out. Uses the same UUID that was
+    /// returned by `on_timed_out_notification_add`
+    pub async fn on_timed_out_notification_remove(
+        &mut self,
+        job_scheduler: &JobsSchedulerLocked,
+        notification_id: &Uuid,
+    ) -> Result<bool, JobSchedulerError> {
+        self.on_notification_removal(
+            job_scheduler,
+            notification_id,
+            Some(vec![JobState::TimedOut]),
+        )
+        .await
+        .map(|(_, deleted)| deleted)
+    }
+
     ///
     /// Run something when the task was removed. Returns a UUID as handle for this notification. This
     /// UUID needs to be used when you want to remove the notification handle using `on_removed_notification_remove`.
@@ -857,7 +1511,107 @@ impl JobLocked {
     }
 
     ///
-    /// Set whether this job has been stopped
+    /// Set what happens when this job fires while its previous run is still going
+    pub fn set_overlap_policy(&mut self, policy: OverlapPolicy) -> Result<(), JobSchedulerError> {
+        let mut data = self.job_data()?;
+        data.overlap = policy.into();
+        self.set_job_data(data)
+    }
+
+    ///
+    /// Set what happens with the fires of this job that were missed, see `MisfirePolicy`. The
+    /// policy is kept with the job in the metadata store.
+    pub fn set_misfire_policy(
+        &mut self,
+        policy: Option<MisfirePolicy>,
+    ) -> Result<(), JobSchedulerError> {
+        let mut data = self.job_data()?;
+        data.misfire = policy.map(i32::from).unwrap_or_default();
+        self.set_job_data(data)
+    }
+
+    ///
+    /// Set how long a run of this job may take before it's cancelled, None for no limit. The
+    /// timeout is kept with the job in the metadata store.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) -> Result<(), JobSchedulerError> {
+        let mut data = self.job_data()?;
+        data.set_timeout(timeout);
+        self.set_job_data(data)
+    }
+
+    ///
+    /// Set the name of this job, None for no name, see `JobBuilder::with_name`
+    pub fn set_name(&mut self, name: Option<String>) -> Result<(), JobSchedulerError> {
+        let mut data = self.job_data()?;
+        data.name = name.unwrap_or_default();
+        self.set_job_data(data)
+    }
+
+    ///
+    /// Give this job a fixed id instead of the random one it was made with, so it keeps the same
+    /// id across restarts. Adding a job with the id of a job that is already in the scheduler
+    /// replaces that job. A hashed schedule or stable jitter keeps the times of the id the job was
+    /// made with, use `JobBuilder::with_id` for those.
+    pub fn set_job_id(&mut self, job_id: Uuid) -> Result<(), JobSchedulerError> {
+        let mut data = self.job_data()?;
+        data.id = Some(job_id.into());
+        self.set_job_data(data)
+    }
+
+    ///
+    /// Set the tags of this job, kept with it in the metadata store
+    pub fn set_tags(&mut self, tags: Vec<String>) -> Result<(), JobSchedulerError> {
+        let mut data = self.job_data()?;
+        data.tags = tags;
+        self.set_job_data(data)
+    }
+
+    ///
+    /// Send the runs of this job to the queue with this name, see `add_queue`. None runs them
+    /// right away.
+    pub fn set_queue(&mut self, queue: Option<String>) -> Result<(), JobSchedulerError> {
+        let mut data = self.job_data()?;
+        data.queue = queue.unwrap_or_default();
+        self.set_job_data(data)
+    }
+
+    ///
+    /// Where this job goes among the jobs that are due at the same time, see
+    /// `JobBuilder::with_order_key`
+    pub fn set_order_key(&mut self, order_key: i64) -> Result<(), JobSchedulerError> {
+        let mut data = self.job_data()?;
+        data.order_key = order_key;
+        self.set_job_data(data)
+    }
+
+    ///
+    /// Fire this job after every run of another job that's done, see `JobBuilder::with_after_job`
+    pub fn set_after_job(&mut self, after: Option<AfterJob>) -> Result<(), JobSchedulerError> {
+        let mut data = self.job_data()?;
+        data.after = after.map(Into::into);
+        self.set_job_data(data)
+    }
+
+    ///
+    /// Dates and times this job doesn't fire on, see `JobBuilder::with_calendar`
+    pub fn set_calendar(&mut self, calendar: Option<Calendar>) -> Result<(), JobSchedulerError> {
+        let mut data = self.job_data()?;
+        data.exclusions = calendar.map(Into::into);
+        self.set_job_data(data)
+    }
+
+    ///
+    /// Which runs of this job go first when they wait for a concurrency limit or are due at the
+    /// same time as other jobs, see `JobBuilder::with_priority`
+    pub fn set_priority(&mut self, priority: i32) -> Result<(), JobSchedulerError> {
+        let mut data = self.job_data()?;
+        data.priority = priority;
+        self.set_job_data(data)
+    }
+
+    ///
+    /// Set whether this job has been stopped. A stopped job is not run when it fires, the flag is
+    /// kept in the metadata store.
     pub fn set_stop(&mut self, stop: bool) -> Result<(), JobSchedulerError> {
         let mut w = self
             .0
@@ -880,4 +1634,27 @@ impl JobLocked {
             _ => Err(JobSchedulerError::GetJobData),
         }
     }
+
+    ///
+    /// Whether the job runs async code rather than sync code
+    pub(crate) fn is_async(&self) -> Result<bool, JobSchedulerError> {
+        let r = self.0.read().map_err(|_| JobSchedulerError::GetJobData)?;
+        Ok(r.is_async())
+    }
+
+    ///
+    /// Work the first fire of the job out again from `now`, the time of the scheduler's clock
+    /// when it's added, see `FirstFire`
+    pub(crate) fn anchor(&mut self, now: DateTime<Utc>) -> Result<(), JobSchedulerError> {
+        let first_fire = {
+            let r = self.0.read().map_err(|_| JobSchedulerError::GetJobData)?;
+            r.first_fire()
+        };
+        if first_fire == FirstFire::Kept {
+            return Ok(());
+        }
+        let mut data = self.job_data()?;
+        first_fire.apply(&mut data, now)?;
+        self.set_job_data(data)
+    }
 }
@@ -2,12 +2,12 @@
 use crate::job::job_data::{JobStoredData, JobType};
 #[cfg(feature = "has_bytes")]
 use crate::job::job_data_prost::{JobStoredData, JobType};
-use crate::job::{Job, JobToRunAsync};
+use crate::job::{run_only, FirstFire, Job, JobToRunAsync};
 use crate::{JobScheduler, JobSchedulerError, JobToRun};
 use chrono::{DateTime, Utc};
 use cron::Schedule;
-use tokio::sync::oneshot::Receiver;
-use tracing::error;
+use std::future::Future;
+use std::pin::Pin;
 use uuid::Uuid;
 
 pub struct NonCronJob {
@@ -15,6 +15,7 @@ pub struct NonCronJob {
     pub run_async: Box<JobToRunAsync>,
     pub data: JobStoredData,
     pub async_job: bool,
+    pub(crate) first_fire: FirstFire,
 }
 
 impl Job for NonCronJob {
@@ -112,28 +113,30 @@ impl Job for NonCronJob {
         Ok(())
     }
 
-    fn run(&mut self, jobs: JobScheduler) -> Receiver<bool> {
-        let (tx, rx) = tokio::sync::oneshot::channel();
+    fn run(&mut self, jobs: JobScheduler) -> Pin<Box<dyn Future<Output = ()> + Send>> {
         let job_id = self.job_id();
 
         if !self.async_job {
             (self.run)(job_id, jobs);
-            if let Err(e) = tx.send(true) {
-                error!("Error notifying done {:?}", e);
-            }
+            Box::pin(std::future::ready(()))
         } else {
-            let future = (self.run_async)(job_id, jobs);
-            tokio::task::spawn(async move {
-                future.await;
-                if let Err(e) = tx.send(true) {
-                    error!("Error notifying done {:?}", e);
-                }
-            });
+            (self.run_async)(job_id, jobs)
         }
-        rx
     }
 
     fn fixed_offset_west(&self) -> i32 {
         self.data.time_offset_seconds
     }
+
+    fn first_fire(&self) -> FirstFire {
+        self.first_fire
+    }
+
+    fn is_async(&self) -> bool {
+        self.async_job
+    }
+
+    fn into_run(self: Box<Self>) -> Box<JobToRunAsync> {
+        run_only(self.run, self.run_async, self.async_job)
+    }
 }
@@ -0,0 +1,86 @@
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::{Overlap, OverlapKind};
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::{Overlap, OverlapKind};
+
+///
+/// Which queued run is dropped when a queue is full
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QueueDrop {
+    Oldest,
+    Newest,
+}
+
+///
+/// What to do when a job fires while its previous run is still going
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum OverlapPolicy {
+    /// Run concurrently with the previous run
+    #[default]
+    Allow,
+    /// Don't run this time
+    Skip,
+    /// Run once the previous run is done. At most `max_depth` runs wait, when another one fires
+    /// `drop` decides which one is left out.
+    Queue { max_depth: u32, drop: QueueDrop },
+    /// Cancel the previous run and run this time. The cancelled run sends `JobState::Cancelled`,
+    /// it isn't retried and isn't passed to the failure handler. Inline jobs run one after
+    /// another, so they don't overlap to begin with.
+    CancelPrevious,
+}
+
+impl OverlapPolicy {
+    ///
+    /// Queue one run, dropping the oldest waiting run when another one fires
+    pub fn queue() -> Self {
+        OverlapPolicy::Queue {
+            max_depth: 1,
+            drop: QueueDrop::Oldest,
+        }
+    }
+}
+
+impl From<Option<&Overlap>> for OverlapPolicy {
+    fn from(overlap: Option<&Overlap>) -> Self {
+        let overlap = match overlap {
+            Some(overlap) => overlap,
+            None => return OverlapPolicy::Allow,
+        };
+        match OverlapKind::from_i32(overlap.kind) {
+            Some(OverlapKind::Skip) => OverlapPolicy::Skip,
+            Some(OverlapKind::CancelPrevious) => OverlapPolicy::CancelPrevious,
+            Some(OverlapKind::Queue) => OverlapPolicy::Queue {
+                max_depth: overlap.max_depth,
+                drop: if overlap.drop_oldest {
+                    QueueDrop::Oldest
+                } else {
+                    QueueDrop::Newest
+                },
+            },
+            _ => OverlapPolicy::Allow,
+        }
+    }
+}
+
+impl From<OverlapPolicy> for Option<Overlap> {
+    fn from(policy: OverlapPolicy) -> Self {
+        match policy {
+            OverlapPolicy::Allow => None,
+            OverlapPolicy::Skip => Some(Overlap {
+                kind: OverlapKind::Skip.into(),
+                max_depth: 0,
+                drop_oldest: false,
+            }),
+            OverlapPolicy::Queue { max_depth, drop } => Some(Overlap {
+                kind: OverlapKind::Queue.into(),
+                max_depth,
+                drop_oldest: drop == QueueDrop::Oldest,
+            }),
+            OverlapPolicy::CancelPrevious => Some(Overlap {
+                kind: OverlapKind::CancelPrevious.into(),
+                max_depth: 0,
+                drop_oldest: false,
+            }),
+        }
+    }
+}
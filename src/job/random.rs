@@ -0,0 +1,12 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+///
+/// A random number below `bound`, another one every time, 0 for a bound of 0. Good enough to
+/// spread runs out, not for anything that has to be unpredictable.
+pub(crate) fn below(bound: u64) -> u64 {
+    if bound == 0 {
+        return 0;
+    }
+    RandomState::new().build_hasher().finish() % bound
+}
@@ -0,0 +1,133 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+///
+/// What happens to a run of a job that fires when its rate limit has no room left
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OnRateLimited {
+    /// Wait until there's room again, the runs that wait start one after another at the rate
+    #[default]
+    Delay,
+    /// Skip the run, counted as `SkipReason::RateLimited`
+    Skip,
+}
+
+///
+/// At most `runs` runs per `per` of all the jobs with a tag together, see `set_tag_rate_limit`.
+/// Up to `runs` runs can start right after each other when there was room for them, after that
+/// they're spread out over `per`.
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use tokio_cron_scheduler::RateLimiter;
+/// // The downstream API takes one call every 10 seconds
+/// let limiter = RateLimiter::new(1, Duration::from_secs(10));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimiter {
+    pub runs: u32,
+    pub per: Duration,
+    pub on_limited: OnRateLimited,
+}
+
+impl RateLimiter {
+    pub fn new(runs: u32, per: Duration) -> Self {
+        Self {
+            runs,
+            per,
+            on_limited: OnRateLimited::default(),
+        }
+    }
+
+    pub fn with_on_limited(self, on_limited: OnRateLimited) -> Self {
+        Self { on_limited, ..self }
+    }
+
+    fn capacity(&self) -> f64 {
+        self.runs.max(1) as f64
+    }
+
+    ///
+    /// How long until the bucket has room for another run
+    fn refill_time(&self) -> Duration {
+        self.per.div_f64(self.capacity())
+    }
+}
+
+///
+/// The token bucket of a rate limit. The runs that wait take their token right away, leaving the
+/// bucket below empty, so the ones after them wait longer.
+pub(crate) struct TokenBucket {
+    // The limit, the tokens and when they were counted
+    state: Mutex<(RateLimiter, f64, Instant)>,
+}
+
+impl TokenBucket {
+    pub(crate) fn new(limiter: RateLimiter) -> Self {
+        Self {
+            state: Mutex::new((limiter, limiter.capacity(), Instant::now())),
+        }
+    }
+
+    pub(crate) fn set_limiter(&self, limiter: RateLimiter) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        TokenBucket::refill(&mut state);
+        state.0 = limiter;
+        state.1 = state.1.min(limiter.capacity());
+    }
+
+    pub(crate) fn on_limited(&self) -> OnRateLimited {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .0
+            .on_limited
+    }
+
+    fn refill(state: &mut (RateLimiter, f64, Instant)) {
+        let now = Instant::now();
+        let refill_time = state.0.refill_time();
+        state.1 = if refill_time.is_zero() {
+            state.0.capacity()
+        } else {
+            let refilled = (now - state.2).as_secs_f64() / refill_time.as_secs_f64();
+            (state.1 + refilled).min(state.0.capacity())
+        };
+        state.2 = now;
+    }
+
+    ///
+    /// Take room for a run when there's room now, in one go so two runs can't both take the
+    /// last of it
+    pub(crate) fn try_reserve(&self) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        TokenBucket::refill(&mut state);
+        if state.1 < 1.0 {
+            return false;
+        }
+        state.1 -= 1.0;
+        true
+    }
+
+    ///
+    /// Give back the room taken by `try_reserve` for a run that doesn't start after all
+    pub(crate) fn give_back(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        TokenBucket::refill(&mut state);
+        state.1 = (state.1 + 1.0).min(state.0.capacity());
+    }
+
+    ///
+    /// Take room for a run, returning how long the run has to wait for it
+    pub(crate) fn reserve(&self) -> Duration {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        TokenBucket::refill(&mut state);
+        state.1 -= 1.0;
+        if state.1 >= 0.0 {
+            Duration::ZERO
+        } else {
+            state.0.refill_time().mul_f64(-state.1)
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::job::random;
+use std::time::Duration;
+
+///
+/// How long to wait before the next attempt after a failed run
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backoff {
+    /// The same wait after every failed attempt
+    Fixed(Duration),
+    /// `initial` after the first failed attempt, doubled after each one after that, up to `max`
+    Exponential { initial: Duration, max: Duration },
+}
+
+///
+/// Runs a job again after a failed run, until it went well or it failed `max_attempts` times in a
+/// row, counting the first run. The attempts are fired on top of the job's schedule, which goes
+/// on as it was, fires of the schedule in between count as attempts too. A random wait of up to
+/// `jitter` is added to the backoff, so jobs that failed together don't all try again at once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub backoff: Backoff,
+    pub jitter: Duration,
+}
+
+impl RetryPolicy {
+    ///
+    /// How long to wait after failed attempt `attempt`, counting from 1, jitter included
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let backoff = match self.backoff {
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential { initial, max } => 2u32
+                .checked_pow(attempt.saturating_sub(1))
+                .and_then(|factor| initial.checked_mul(factor))
+                .unwrap_or(max)
+                .min(max),
+        };
+        let jitter = self.jitter.as_micros().min(u64::MAX as u128) as u64;
+        if jitter == 0 {
+            return backoff;
+        }
+        backoff.saturating_add(Duration::from_micros(random::below(
+            jitter.saturating_add(1),
+        )))
+    }
+}
@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+///
+/// What happens to a job once its runs took longer than its budget all together
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnBudgetExceeded {
+    /// Stop the job, like `Job::set_stop`
+    Disable,
+    /// Remove the job
+    Remove,
+    /// Only report it to the job failure handler
+    Notify,
+}
+
+///
+/// How long the runs of a job may take all together, measured in wall clock time. There's no
+/// budget of CPU time: the runs are tasks sharing tokio's worker threads, which keep no CPU time
+/// per task, and timing the polls of a run would leave out the work it hands to other tasks or
+/// blocking threads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RunBudget {
+    pub wallclock: Duration,
+    pub on_exceeded: OnBudgetExceeded,
+}
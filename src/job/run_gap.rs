@@ -0,0 +1,38 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+///
+/// How far apart the runs of the jobs with a tag start, see `set_tag_gap`
+pub(crate) struct RunGap {
+    // The gap, and when the last run that waited for it started or will start
+    state: Mutex<(Duration, Option<Instant>)>,
+}
+
+impl RunGap {
+    pub(crate) fn new(gap: Duration) -> Self {
+        Self {
+            state: Mutex::new((gap, None)),
+        }
+    }
+
+    pub(crate) fn set_gap(&self, gap: Duration) {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).0 = gap;
+    }
+
+    ///
+    /// Wait until the gap after the start of the run before this one is over. The start is
+    /// taken right away, so runs waiting at the same time start a gap apart from each other.
+    pub(crate) async fn wait(&self) {
+        let start = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let start = match state.1 {
+                Some(last) => now.max(last + state.0),
+                None => now,
+            };
+            state.1 = Some(start);
+            start
+        };
+        tokio::time::sleep_until(start.into()).await;
+    }
+}
@@ -1,63 +1,995 @@
 use crate::context::Context;
+#[cfg(feature = "metrics")]
+use crate::instruments;
 #[cfg(not(feature = "has_bytes"))]
-use crate::job::job_data::JobState;
+use crate::job::job_data::{JobState, JobType};
 #[cfg(feature = "has_bytes")]
-use crate::job::job_data_prost::JobState;
-use crate::job::to_code::JobCode;
-use crate::job_scheduler::JobsSchedulerLocked;
+use crate::job::job_data_prost::{JobState, JobType};
+use crate::job::{
+    lifecycle, ConcurrencyLimit, JobContext, JobDeleter, JobError, JobQueue, OnBudgetExceeded,
+    OnRateLimited, OverlapPolicy, QueueDrop, RunGap, TokenBucket,
+};
+use crate::job_scheduler::{JobFailureHandler, JobsSchedulerLocked};
+use crate::stats::SkipReason;
+use crate::store::RunRecord;
 use crate::JobSchedulerError;
+use chrono::{DateTime, Utc};
+use std::any::Any;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::error::Error;
 use std::future::Future;
+use std::panic::AssertUnwindSafe;
 use std::pin::Pin;
 use std::sync::Arc;
-use tokio::sync::broadcast::{Receiver, Sender};
+use std::task::{Context as TaskContext, Poll};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
 use tokio::sync::RwLock;
-use tracing::error;
+use tokio::task::{AbortHandle, JoinError};
+use tracing::{error, warn, Instrument, Level};
 use uuid::Uuid;
 
+///
+/// An event at a level only known at runtime
+macro_rules! log_at {
+    ($level:expr, $($arg:tt)+) => {
+        match $level {
+            Level::TRACE => tracing::trace!($($arg)+),
+            Level::DEBUG => tracing::debug!($($arg)+),
+            Level::INFO => tracing::info!($($arg)+),
+            Level::WARN => tracing::warn!($($arg)+),
+            Level::ERROR => tracing::error!($($arg)+),
+        }
+    };
+}
+
+pub(crate) type Overlaps = Arc<RwLock<HashMap<Uuid, Overlap>>>;
+
+///
+/// Gives a panic of the future it wraps as an error, for the runs of inline jobs, which aren't
+/// spawned on a task that would catch it
+pub(crate) struct CatchUnwind<F>(pub(crate) Pin<Box<F>>);
+
+impl<F: Future> Future for CatchUnwind<F> {
+    type Output = Result<F::Output, Box<dyn Any + Send>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        let inner = self.0.as_mut();
+        match std::panic::catch_unwind(AssertUnwindSafe(|| inner.poll(cx))) {
+            Ok(Poll::Ready(done)) => Poll::Ready(Ok(done)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(panic) => Poll::Ready(Err(panic)),
+        }
+    }
+}
+
+///
+/// A job with a run going, and the fire times of the runs waiting for it. A job that cancels its
+/// previous run keeps the last run instead, and how many of the runs it cancelled haven't seen it
+/// yet.
+#[derive(Default)]
+pub(crate) struct Overlap {
+    running: bool,
+    queue: VecDeque<DateTime<Utc>>,
+    last_run: Option<AbortHandle>,
+    cancelled: u32,
+}
+
+///
+/// Counts a run as failed when it's dropped before it's done, like when the runtime shuts down
+/// while it's going, so the job isn't left counted as running
+struct RunningGuard<'a> {
+    context: &'a Context,
+    job_id: Uuid,
+    started: Instant,
+    done: bool,
+}
+
+impl Drop for RunningGuard<'_> {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        error!("Run of {:?} was dropped before it was done", self.job_id);
+        match self.context.stats.try_write() {
+            Ok(mut w) => {
+                w.finished(self.job_id, true, self.started.elapsed());
+                self.context.update_activity(w.running);
+            }
+            Err(_) => error!("Could not count the run of {:?} as done", self.job_id),
+        }
+    }
+}
+
+///
+/// Lets the next run of a job start when the runs are dropped before they're done, and forgets
+/// the job once its last run is
+struct OverlapGuard {
+    overlaps: Overlaps,
+    job_id: Uuid,
+    done: bool,
+}
+
+impl Drop for OverlapGuard {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        match self.overlaps.try_write() {
+            Ok(mut w) => {
+                w.remove(&self.job_id);
+            }
+            Err(_) => error!("Could not mark {:?} as no longer running", self.job_id),
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct JobRunner {}
 
 impl JobRunner {
-    async fn listen_for_activations(
-        job_code: Arc<RwLock<Box<dyn JobCode + Send + Sync>>>,
-        mut rx: Receiver<Uuid>,
-        tx_notify: Sender<(Uuid, JobState)>,
-        job_scheduler: JobsSchedulerLocked,
+    fn failure_error(e: JoinError) -> Box<dyn Error + Send + Sync> {
+        // The task of the run was cancelled before it was done, like when the runtime shuts down
+        if e.is_cancelled() {
+            return Box::new(JobSchedulerError::RunCancelled);
+        }
+        if !e.is_panic() {
+            return Box::new(e);
+        }
+        JobRunner::panic_error(e.into_panic())
+    }
+
+    fn panic_error(panic: Box<dyn Any + Send>) -> Box<dyn Error + Send + Sync> {
+        Box::new(JobRunner::panicked(panic))
+    }
+
+    pub(crate) fn panicked(panic: Box<dyn Any + Send>) -> JobSchedulerError {
+        let message = if let Some(s) = panic.downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = panic.downcast_ref::<String>() {
+            s.clone()
+        } else {
+            "job panicked".to_string()
+        };
+        JobSchedulerError::JobPanicked(message)
+    }
+
+    ///
+    /// Wait for a run, failing with `RunTimedOut` once it takes longer than `timeout`
+    async fn timed<F: Future>(
+        timeout: Option<Duration>,
+        run: F,
+    ) -> Result<F::Output, Box<dyn Error + Send + Sync>> {
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, run)
+                .await
+                .map_err(|_| Box::new(JobSchedulerError::RunTimedOut(timeout)) as _),
+            None => Ok(run.await),
+        }
+    }
+
+    async fn report_failure(
+        handler: &Arc<RwLock<Option<Box<JobFailureHandler>>>>,
+        job_id: Uuid,
+        attempt: u32,
+        error: Box<dyn Error + Send + Sync>,
     ) {
-        loop {
-            let val = rx.recv().await;
-            if let Err(e) = val {
-                error!("Error receiving {:?}", e);
-                break;
+        let mut w = handler.write().await;
+        if let Some(handler) = w.as_mut() {
+            (handler)(JobError::new(job_id, attempt, error)).await;
+        }
+    }
+
+    ///
+    /// Apply the budget of a job once its runs took longer than it all together
+    async fn check_budget(context: &Context, job_id: Uuid, total: Duration) {
+        let budget = {
+            let mut w = context.budgets.write().await;
+            match w.get(&job_id) {
+                Some(budget) if total > budget.wallclock => w.remove(&job_id),
+                _ => None,
             }
-            let uuid = val.unwrap();
-            {
-                let tx = tx_notify.clone();
+        };
+        let budget = match budget {
+            Some(budget) => budget,
+            None => return,
+        };
+        warn!(
+            "Runs of {:?} took {:?}, more than the budget of {:?}",
+            job_id, total, budget.wallclock
+        );
+        match budget.on_exceeded {
+            OnBudgetExceeded::Disable => {
+                let mut w = context.metadata_storage.write().await;
+                if let Ok(Some(mut data)) = w.get(job_id).await {
+                    data.stopped = true;
+                    if let Err(e) = w.add_or_update(data).await {
+                        error!("Error disabling {:?} {:?}", job_id, e);
+                    }
+                }
+            }
+            OnBudgetExceeded::Remove => {
+                let context = context.clone();
                 tokio::spawn(async move {
-                    if let Err(e) = tx.send((uuid, JobState::Started)) {
-                        error!("Error sending error listening for activation {:?}", e);
+                    if let Err(e) = JobDeleter::remove(&context, &job_id).await {
+                        error!("Error removing job {:?} {:?}", job_id, e);
                     }
                 });
             }
-            let mut w = job_code.write().await;
-            let code = w.get(uuid).await;
-            match code {
+            OnBudgetExceeded::Notify => {}
+        }
+        let error = JobSchedulerError::RunBudgetExceeded(format!(
+            "runs took {:?}, more than the budget of {:?}",
+            total, budget.wallclock
+        ));
+        JobRunner::report_failure(&context.job_failure_handler, job_id, 1, Box::new(error)).await;
+    }
+
+    async fn skipped(context: &Context, job_id: Uuid, reason: SkipReason) {
+        let mut w = context.stats.write().await;
+        w.skipped(job_id, reason);
+    }
+
+    ///
+    /// Whether the last run failed for a job that shouldn't run again after a failure
+    async fn skip_after_failure(context: &Context, job_id: Uuid) -> bool {
+        let skip = {
+            let r = context.skip_after_failure.read().await;
+            r.contains(&job_id)
+        };
+        if !skip {
+            return false;
+        }
+        let mut w = context.stats.write().await;
+        if !w.job(&job_id).map(|j| j.last_failed).unwrap_or_default() {
+            return false;
+        }
+        warn!("Skipped {:?}, its last run failed", job_id);
+        w.skipped(job_id, SkipReason::AfterFailure);
+        true
+    }
+
+    ///
+    /// Whether a run that fired now may start, otherwise it was queued or skipped
+    async fn may_start(
+        context: &Context,
+        overlaps: &Overlaps,
+        job_id: Uuid,
+        policy: OverlapPolicy,
+    ) -> bool {
+        if policy == OverlapPolicy::Allow {
+            return true;
+        }
+        let mut w = overlaps.write().await;
+        let overlap = w.entry(job_id).or_default();
+        if policy == OverlapPolicy::CancelPrevious {
+            if let Some(last_run) = overlap.last_run.take() {
+                if !last_run.is_finished() {
+                    warn!("Cancelled the previous run of {:?}", job_id);
+                    last_run.abort();
+                    overlap.cancelled += 1;
+                }
+            }
+            return true;
+        }
+        if !overlap.running {
+            overlap.running = true;
+            return true;
+        }
+        let now = context.now();
+        match policy {
+            OverlapPolicy::Queue { max_depth, .. } if overlap.queue.len() < max_depth as usize => {
+                overlap.queue.push_back(now);
+                return false;
+            }
+            OverlapPolicy::Queue {
+                drop: QueueDrop::Oldest,
+                ..
+            } if !overlap.queue.is_empty() => {
+                let dropped = overlap.queue.pop_front();
+                overlap.queue.push_back(now);
+                warn!(
+                    "Queue of {:?} is full, dropped the run that fired at {:?}",
+                    job_id, dropped
+                );
+            }
+            OverlapPolicy::Queue { .. } => {
+                warn!(
+                    "Queue of {:?} is full, dropped the run that fired at {:?}",
+                    job_id, now
+                );
+            }
+            _ => {
+                warn!("Skipped {:?}, the previous run is still going", job_id);
+            }
+        }
+        drop(w);
+        JobRunner::skipped(context, job_id, SkipReason::Overlap).await;
+        false
+    }
+
+    ///
+    /// The concurrency limits of the tags of a job, ordered by tag so that runs wait for them in
+    /// the same order
+    async fn tag_limits(context: &Context, tags: &[String]) -> Vec<Arc<ConcurrencyLimit>> {
+        let r = context.tag_limits.read().await;
+        let tags = tags.iter().collect::<BTreeSet<_>>();
+        tags.into_iter()
+            .filter_map(|tag| r.get(tag).cloned())
+            .collect()
+    }
+
+    ///
+    /// The gaps kept between the runs of the jobs with the tags of a job
+    async fn tag_gaps(context: &Context, tags: &[String]) -> Vec<Arc<RunGap>> {
+        let r = context.tag_gaps.read().await;
+        let tags = tags.iter().collect::<BTreeSet<_>>();
+        tags.into_iter()
+            .filter_map(|tag| r.get(tag).cloned())
+            .collect()
+    }
+
+    ///
+    /// The rate limits of the tags of a job, with their tags
+    async fn tag_rate_limits(
+        context: &Context,
+        tags: &[String],
+    ) -> Vec<(String, Arc<TokenBucket>)> {
+        let r = context.tag_rate_limits.read().await;
+        let tags = tags.iter().collect::<BTreeSet<_>>();
+        tags.into_iter()
+            .filter_map(|tag| r.get(tag).map(|bucket| (tag.clone(), bucket.clone())))
+            .collect()
+    }
+
+    ///
+    /// Take room for this run in the rate limits of a job that skip runs, None when one of them
+    /// has no room, giving back what the others gave. The room taken is given back with
+    /// `give_back` when the run doesn't start after all.
+    async fn reserve_rate_limits(
+        context: &Context,
+        job_id: Uuid,
+        rate_limits: &[(String, Arc<TokenBucket>)],
+    ) -> Option<Vec<Arc<TokenBucket>>> {
+        let mut reserved = Vec::new();
+        for (tag, bucket) in rate_limits {
+            if bucket.on_limited() != OnRateLimited::Skip {
+                continue;
+            }
+            if bucket.try_reserve() {
+                reserved.push(bucket.clone());
+                continue;
+            }
+            JobRunner::give_back(&reserved);
+            warn!(
+                "Skipped {:?}, the rate limit of {:?} has no room",
+                job_id, tag
+            );
+            let mut w = context.stats.write().await;
+            w.throttled(job_id, tag, true);
+            w.skipped(job_id, SkipReason::RateLimited);
+            return None;
+        }
+        Some(reserved)
+    }
+
+    fn give_back(reserved: &[Arc<TokenBucket>]) {
+        for bucket in reserved {
+            bucket.give_back();
+        }
+    }
+
+    ///
+    /// Wait for the rate limits of a job that delay runs
+    async fn wait_for_rate_limits(
+        context: &Context,
+        job_id: Uuid,
+        rate_limits: &[(String, Arc<TokenBucket>)],
+    ) {
+        for (tag, bucket) in rate_limits {
+            if bucket.on_limited() != OnRateLimited::Delay {
+                continue;
+            }
+            let wait = bucket.reserve();
+            if wait.is_zero() {
+                continue;
+            }
+            {
+                let mut w = context.stats.write().await;
+                w.throttled(job_id, tag, false);
+            }
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    ///
+    /// The queue a job's runs are sent to, None for running them right away
+    async fn queue(context: &Context, name: &str) -> Option<Arc<JobQueue>> {
+        if name.is_empty() {
+            return None;
+        }
+        let r = context.queues.read().await;
+        let queue = r.get(name).cloned();
+        if queue.is_none() {
+            warn!("There's no queue {:?}, running the job right away", name);
+        }
+        queue
+    }
+
+    ///
+    /// Whether a queued run may start now that its turn came. It's checked again, as the job
+    /// may have been stopped or removed, failed or used up its rate limits while the run waited.
+    async fn queued_may_start(context: &Context, job_id: Uuid) -> bool {
+        let tags = {
+            let mut r = context.metadata_storage.write().await;
+            match r.get(job_id).await.ok().flatten() {
+                Some(data) if !data.stopped => data.tags,
+                _ => return false,
+            }
+        };
+        if JobRunner::skip_after_failure(context, job_id).await {
+            return false;
+        }
+        let rate_limits = JobRunner::tag_rate_limits(context, &tags).await;
+        JobRunner::reserve_rate_limits(context, job_id, &rate_limits)
+            .await
+            .is_some()
+    }
+
+    ///
+    /// Whether one of the queued runs starts now that the previous one is done, passing over the
+    /// ones that may not start anymore
+    async fn next_admitted(context: &Context, overlaps: &Overlaps, job_id: Uuid) -> bool {
+        while JobRunner::next_queued(overlaps, job_id).await {
+            if JobRunner::queued_may_start(context, job_id).await {
+                return true;
+            }
+        }
+        false
+    }
+
+    ///
+    /// Whether a queued run should start now that the previous one is done
+    async fn next_queued(overlaps: &Overlaps, job_id: Uuid) -> bool {
+        let mut w = overlaps.write().await;
+        let queued = w
+            .get_mut(&job_id)
+            .and_then(|overlap| overlap.queue.pop_front())
+            .is_some();
+        if !queued {
+            w.remove(&job_id);
+        }
+        queued
+    }
+
+    ///
+    /// Forget a job that cancels its previous run once no run of it is going and none that it
+    /// cancelled is left to see it
+    async fn forget_finished(overlaps: &Overlaps, job_id: Uuid) {
+        let mut w = overlaps.write().await;
+        let finished = w.get(&job_id).is_some_and(|overlap| {
+            overlap.cancelled == 0
+                && overlap
+                    .last_run
+                    .as_ref()
+                    .is_none_or(|last_run| last_run.is_finished())
+        });
+        if finished {
+            w.remove(&job_id);
+        }
+    }
+
+    async fn checkpoint(context: &Context, job_id: Uuid, ran_at: DateTime<Utc>) {
+        let at_least_once = {
+            let r = context.at_least_once.read().await;
+            r.contains_key(&job_id)
+        };
+        if !at_least_once {
+            return;
+        }
+        let mut w = context.checkpoint_store.write().await;
+        if let Some(store) = w.as_mut() {
+            if let Err(e) = store.set(job_id, ran_at).await {
+                error!("Error saving the checkpoint of {:?} {:?}", job_id, e);
+            }
+        }
+    }
+
+    ///
+    /// Whether a run came back cancelled because a later fire cancelled it, rather than by the
+    /// runtime shutting down
+    async fn cancelled_by_policy(
+        cancellable: Option<&Overlaps>,
+        job_id: Uuid,
+        ran: &Result<(), Box<dyn Error + Send + Sync>>,
+    ) -> bool {
+        let overlaps = match (cancellable, ran) {
+            (Some(overlaps), Err(e))
+                if matches!(e.downcast_ref(), Some(JobSchedulerError::RunCancelled)) =>
+            {
+                overlaps
+            }
+            _ => return false,
+        };
+        let mut w = overlaps.write().await;
+        match w.get_mut(&job_id) {
+            Some(overlap) if overlap.cancelled > 0 => {
+                overlap.cancelled -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    ///
+    /// Keep a run that finished in the history store
+    async fn record_history(
+        context: &Context,
+        job_id: Uuid,
+        started: DateTime<Utc>,
+        duration: Duration,
+        ran: &Result<(), Box<dyn Error + Send + Sync>>,
+        cancelled: bool,
+    ) {
+        let mut w = context.history_store.write().await;
+        let store = match w.as_mut() {
+            Some(store) => store,
+            None => return,
+        };
+        let (outcome, error) = match ran {
+            Ok(()) => (JobState::Done, None),
+            Err(_) if cancelled => (JobState::Cancelled, None),
+            Err(e) => match e.downcast_ref() {
+                Some(JobSchedulerError::RunTimedOut(_)) => {
+                    (JobState::TimedOut, Some(e.to_string()))
+                }
+                _ => (JobState::Failed, Some(e.to_string())),
+            },
+        };
+        let run = RunRecord {
+            job_id,
+            started,
+            duration,
+            outcome,
+            error,
+        };
+        if let Err(e) = store.record(run).await {
+            error!(
+                "Error keeping the run of {:?} in the history {:?}",
+                job_id, e
+            );
+        }
+    }
+
+    ///
+    /// Fire the jobs set to run after this one, each once its delay is over
+    async fn fire_after(context: &Context, job_id: Uuid) {
+        let now = context.now();
+        let fires = {
+            let r = context.after_jobs.read().await;
+            r.iter()
+                .filter(|(_, after)| after.job_id == job_id)
+                .filter_map(|(id, after)| {
+                    let delay = chrono::Duration::from_std(after.delay).ok()?;
+                    Some((*id, now.checked_add_signed(delay)?))
+                })
+                .collect::<Vec<_>>()
+        };
+        if fires.is_empty() {
+            return;
+        }
+        let mut w = context.one_offs.write().await;
+        for (id, when) in fires {
+            w.entry(id).or_default().push(when);
+        }
+    }
+
+    async fn run(
+        context: &Context,
+        job_id: Uuid,
+        job_scheduler: JobsSchedulerLocked,
+        inline: bool,
+        cancellable: Option<&Overlaps>,
+    ) {
+        let ran_at = context.now();
+        if inline {
+            if let Err(e) = context.notify_tx.send((job_id, JobState::Started)) {
+                error!("Error sending error listening for activation {:?}", e);
+            }
+        } else {
+            let tx = context.notify_tx.clone();
+            tokio::spawn(async move {
+                if let Err(e) = tx.send((job_id, JobState::Started)) {
+                    error!("Error sending error listening for activation {:?}", e);
+                }
+            });
+        }
+        let v = {
+            let mut w = context.job_code.write().await;
+            match w.get(job_id).await {
                 Ok(Some(job)) => {
                     let mut job = job.write().await;
-                    let v = (job)(uuid, job_scheduler.clone());
-                    let tx = tx_notify.clone();
-                    tokio::spawn(async move {
-                        v.await;
-                        if let Err(e) = tx.send((uuid, JobState::Done)) {
-                            error!("Error sending spawned task {:?}", e);
-                        }
-                    });
+                    (job)(job_id, job_scheduler)
                 }
                 _ => {
-                    error!("Error getting {:?} from job code", uuid);
+                    error!("Error getting {:?} from job code", job_id);
+                    return;
+                }
+            }
+        };
+
+        let run_count = {
+            let mut w = context.stats.write().await;
+            w.started(job_id);
+            context.update_activity(w.running);
+            w.job(&job_id).map(|j| j.runs).unwrap_or_default()
+        };
+        {
+            let mut w = context.max_silence.write().await;
+            if let Some((_, last_run)) = w.get_mut(&job_id) {
+                *last_run = ran_at;
+            }
+        }
+        let mut running = RunningGuard {
+            context,
+            job_id,
+            started: Instant::now(),
+            done: false,
+        };
+        let data = {
+            let mut r = context.metadata_storage.write().await;
+            r.get(job_id).await.ok().flatten()
+        };
+        let timeout = data.as_ref().and_then(|j| j.timeout());
+        let span = lifecycle::run_span(job_id, data.as_ref(), run_count);
+        span.in_scope(|| lifecycle::event(job_id, JobState::Started));
+        let scheduled = {
+            let mut w = context.fire_times.write().await;
+            w.remove(&job_id).unwrap_or(ran_at)
+        };
+        let attempt = {
+            let r = context.retry_policies.read().await;
+            r.get(&job_id).map(|(_, failed)| failed + 1).unwrap_or(1)
+        };
+        let job_context = JobContext {
+            job_id,
+            name: data.as_ref().map(|j| j.name.clone()).unwrap_or_default(),
+            scheduled,
+            attempt,
+            next_run: data.as_ref().and_then(|j| j.next_tick_utc()),
+            run_count,
+            extra: data.map(|j| j.extra).unwrap_or_default(),
+        };
+        let log_level = {
+            let r = context.log_levels.read().await;
+            r.get(&job_id).copied()
+        };
+        if let Some(level) = log_level {
+            log_at!(level, "Started {:?}, run {}", job_id, run_count);
+        }
+        let started = Instant::now();
+        let run = job_context.scope(v).instrument(span.clone());
+        let ran = if inline {
+            // An inline run that timed out is dropped where it was
+            JobRunner::timed(timeout, CatchUnwind(Box::pin(run)))
+                .await
+                .and_then(|ran| ran.map_err(JobRunner::panic_error))
+                .and_then(|ran| ran.map_err(|failure| failure.error))
+        } else {
+            let mut handle = tokio::spawn(run);
+            // Kept so the next fire can cancel this run
+            if let Some(overlaps) = cancellable {
+                let mut w = overlaps.write().await;
+                w.entry(job_id).or_default().last_run = Some(handle.abort_handle());
+            }
+            let ran = JobRunner::timed(timeout, &mut handle).await;
+            if ran.is_err() {
+                handle.abort();
+            }
+            ran.and_then(|ran| ran.map_err(JobRunner::failure_error))
+                .and_then(|ran| ran.map_err(|failure| failure.error))
+        };
+        let cancelled = JobRunner::cancelled_by_policy(cancellable, job_id, &ran).await;
+        let total = {
+            let mut w = context.stats.write().await;
+            w.finished(job_id, ran.is_err() && !cancelled, started.elapsed());
+            match &ran {
+                Err(e) if !cancelled => w.failed_with(job_id, e.to_string()),
+                _ => {}
+            }
+            context.update_activity(w.running);
+            running.done = true;
+            w.job(&job_id).map(|j| j.total_duration).unwrap_or_default()
+        };
+        JobRunner::record_history(context, job_id, ran_at, started.elapsed(), &ran, cancelled)
+            .await;
+        JobRunner::check_budget(context, job_id, total).await;
+        // A run cancelled by the next fire isn't a failure, that run takes its place
+        if cancelled {
+            span.in_scope(|| {
+                if let Some(level) = log_level {
+                    log_at!(level, "Cancelled {:?} for its next run", job_id);
+                }
+                lifecycle::event(job_id, JobState::Cancelled);
+            });
+            if let Err(e) = context.notify_tx.send((job_id, JobState::Cancelled)) {
+                error!("Error sending spawned task {:?}", e);
+            }
+            return;
+        }
+        if let Err(e) = ran {
+            let timed_out = matches!(e.downcast_ref(), Some(JobSchedulerError::RunTimedOut(_)));
+            span.in_scope(|| match log_level {
+                Some(level) => log_at!(level, "Error running job {:?} {:?}", job_id, e),
+                None => error!("Error running job {:?} {:?}", job_id, e),
+            });
+            let (attempt, retry_in) = JobRunner::failed_attempt(context, job_id).await;
+            JobRunner::report_failure(&context.job_failure_handler, job_id, attempt, e).await;
+            if let Some(retry_in) = retry_in {
+                span.in_scope(|| lifecycle::event(job_id, JobState::Retrying));
+                JobRunner::retry(context, job_id, attempt, retry_in).await;
+                return;
+            }
+            if JobRunner::retries(context, job_id).await {
+                return;
+            }
+            let state = if timed_out {
+                JobState::TimedOut
+            } else {
+                JobState::Failed
+            };
+            span.in_scope(|| lifecycle::event(job_id, state));
+            if let Err(e) = context.notify_tx.send((job_id, state)) {
+                error!("Error sending spawned task {:?}", e);
+            }
+            JobRunner::until_done_over(context, job_id).await;
+            return;
+        }
+        span.in_scope(|| {
+            if let Some(level) = log_level {
+                log_at!(level, "Finished {:?} in {:?}", job_id, started.elapsed());
+            }
+            lifecycle::event(job_id, JobState::Done);
+        });
+        JobRunner::checkpoint(context, job_id, ran_at).await;
+        {
+            let mut w = context.retry_policies.write().await;
+            if let Some((_, failed)) = w.get_mut(&job_id) {
+                *failed = 0;
+            }
+        }
+        if let Err(e) = context.notify_tx.send((job_id, JobState::Done)) {
+            error!("Error sending spawned task {:?}", e);
+        }
+        JobRunner::fire_after(context, job_id).await;
+        JobRunner::until_done_over(context, job_id).await;
+    }
+
+    ///
+    /// The attempt of a job's run that failed, and how long until the next attempt when its
+    /// retry policy has attempts left. The count starts over once the attempts are used up.
+    async fn failed_attempt(context: &Context, job_id: Uuid) -> (u32, Option<Duration>) {
+        let mut w = context.retry_policies.write().await;
+        let (policy, failed) = match w.get_mut(&job_id) {
+            Some(retry) => retry,
+            None => return (1, None),
+        };
+        *failed += 1;
+        let attempt = *failed;
+        if attempt >= policy.max_attempts {
+            *failed = 0;
+            return (attempt, None);
+        }
+        (attempt, Some(policy.delay(attempt)))
+    }
+
+    ///
+    /// Fire a job again once `retry_in` is over, after failed attempt `attempt`
+    async fn retry(context: &Context, job_id: Uuid, attempt: u32, retry_in: Duration) {
+        let when = chrono::Duration::from_std(retry_in)
+            .ok()
+            .and_then(|retry_in| context.now().checked_add_signed(retry_in))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        warn!(
+            "Attempt {} of {:?} failed, trying again at {:?}",
+            attempt, job_id, when
+        );
+        {
+            let mut w = context.one_offs.write().await;
+            w.entry(job_id).or_default().push(when);
+        }
+        if let Err(e) = context.notify_tx.send((job_id, JobState::Retrying)) {
+            error!("Error sending spawned task {:?}", e);
+        }
+    }
+
+    ///
+    /// Whether a job added with `add_until_done` fires again after a failed run, that is
+    /// whether its next fire is still within its window
+    async fn retries(context: &Context, job_id: Uuid) -> bool {
+        let until = {
+            let r = context.until_done.read().await;
+            match r.get(&job_id) {
+                Some(until) => *until,
+                None => return false,
+            }
+        };
+        let next_tick = {
+            let mut r = context.metadata_storage.write().await;
+            r.get(job_id)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|j| j.next_tick_utc())
+        };
+        matches!(next_tick, Some(next_tick) if next_tick <= until)
+    }
+
+    ///
+    /// Remove a job added with `add_until_done` after its last run
+    async fn until_done_over(context: &Context, job_id: Uuid) {
+        let until_done = {
+            let r = context.until_done.read().await;
+            r.contains_key(&job_id)
+        };
+        if !until_done {
+            return;
+        }
+        let context = context.clone();
+        tokio::spawn(async move {
+            if let Err(e) = JobDeleter::remove(&context, &job_id).await {
+                error!("Error removing {:?} after its last run {:?}", job_id, e);
+            }
+        });
+    }
+
+    async fn listen_for_activations(
+        context: Context,
+        mut rx: Receiver<Uuid>,
+        job_scheduler: JobsSchedulerLocked,
+        overlaps: Overlaps,
+    ) {
+        // The inline jobs run one after another on a task of their own, so they don't hold up
+        // receiving the fires of the other jobs
+        let (inline_tx, mut inline_rx) =
+            tokio::sync::mpsc::unbounded_channel::<Pin<Box<dyn Future<Output = ()> + Send>>>();
+        tokio::spawn(async move {
+            while let Some(activated) = inline_rx.recv().await {
+                activated.await;
+            }
+        });
+        loop {
+            let uuid = match rx.recv().await {
+                Ok(uuid) => uuid,
+                Err(RecvError::Lagged(missed)) => {
+                    error!("Missed {} fires, the job runner couldn't keep up", missed);
                     continue;
                 }
+                Err(RecvError::Closed) => break,
+            };
+            #[cfg(feature = "metrics")]
+            instruments::activation_received(rx.len());
+            let inline = {
+                let r = context.inline.read().await;
+                r.contains(&uuid)
+            };
+            let activated = JobRunner::activate(
+                context.clone(),
+                uuid,
+                job_scheduler.clone(),
+                overlaps.clone(),
+                inline,
+            );
+            if inline {
+                if inline_tx.send(Box::pin(activated)).is_err() {
+                    error!("Error sending {:?} to the inline jobs", uuid);
+                }
+            } else {
+                tokio::spawn(activated);
+            }
+        }
+    }
+
+    ///
+    /// Start a run for a fire of a job, unless it's stopped or skipped
+    async fn activate(
+        context: Context,
+        uuid: Uuid,
+        job_scheduler: JobsSchedulerLocked,
+        overlaps: Overlaps,
+        inline: bool,
+    ) {
+        let (stopped, one_shot, policy, tags, priority, queue) = {
+            let mut r = context.metadata_storage.write().await;
+            let data = r.get(uuid).await.ok().flatten();
+            (
+                data.as_ref().map(|d| d.stopped).unwrap_or_default(),
+                data.as_ref()
+                    .map(|d| d.job_type == JobType::OneShot as i32)
+                    .unwrap_or_default(),
+                OverlapPolicy::from(data.as_ref().and_then(|d| d.overlap.as_ref())),
+                data.as_ref().map(|d| d.tags.clone()).unwrap_or_default(),
+                data.as_ref().map(|d| d.priority).unwrap_or_default(),
+                data.map(|d| d.queue).unwrap_or_default(),
+            )
+        };
+        // The stopped flag is stored with the job, so a job stopped before a restart
+        // stays stopped when its metadata is loaded again
+        if stopped {
+            return;
+        }
+        if JobRunner::skip_after_failure(&context, uuid).await {
+            return;
+        }
+        let rate_limits = JobRunner::tag_rate_limits(&context, &tags).await;
+        let reserved = match JobRunner::reserve_rate_limits(&context, uuid, &rate_limits).await {
+            Some(reserved) => reserved,
+            None => return,
+        };
+        // A place in the queue is taken before the overlap check, so a fire skipped for a
+        // full queue doesn't leave the job marked as running
+        let permit = match JobRunner::queue(&context, &queue).await {
+            Some(job_queue) => match job_queue.reserve() {
+                Some(permit) => Some(permit),
+                None => {
+                    JobRunner::give_back(&reserved);
+                    warn!("Skipped {:?}, its queue {:?} is full", uuid, queue);
+                    JobRunner::skipped(&context, uuid, SkipReason::QueueFull).await;
+                    return;
+                }
+            },
+            None => None,
+        };
+        // The room in the rate limits is only kept for a fire that starts now
+        if !JobRunner::may_start(&context, &overlaps, uuid, policy).await {
+            JobRunner::give_back(&reserved);
+            return;
+        }
+        let run = async move {
+            // Only skipped and queued runs wait for the run that's going
+            let waits = matches!(policy, OverlapPolicy::Skip | OverlapPolicy::Queue { .. });
+            let mut overlap = OverlapGuard {
+                overlaps: overlaps.clone(),
+                job_id: uuid,
+                done: policy == OverlapPolicy::Allow,
+            };
+            loop {
+                // Always the tags first, then the one shot limit and then the global limit,
+                // so runs can't wait on each other
+                let mut limits = JobRunner::tag_limits(&context, &tags).await;
+                if one_shot {
+                    limits.push(context.one_shot_limit.clone());
+                }
+                limits.push(context.concurrency_limit.clone());
+                let mut permits = Vec::with_capacity(limits.len());
+                for limit in limits.iter() {
+                    permits.push(limit.acquire(priority).await);
+                }
+                JobRunner::wait_for_rate_limits(&context, uuid, &rate_limits).await;
+                // The gaps are kept last, so the run starts right after them
+                for gap in JobRunner::tag_gaps(&context, &tags).await {
+                    gap.wait().await;
+                }
+                let cancellable = (policy == OverlapPolicy::CancelPrevious).then_some(&overlaps);
+                JobRunner::run(&context, uuid, job_scheduler.clone(), inline, cancellable).await;
+                while let Some(permit) = permits.pop() {
+                    drop(permit);
+                }
+                if !waits || !JobRunner::next_admitted(&context, &overlaps, uuid).await {
+                    if policy == OverlapPolicy::CancelPrevious {
+                        JobRunner::forget_finished(&overlaps, uuid).await;
+                    }
+                    overlap.done = true;
+                    break;
+                }
+            }
+        };
+        match permit {
+            Some(permit) => {
+                permit.send(Box::pin(run));
             }
+            None => run.await,
         }
     }
 
@@ -66,16 +998,16 @@ impl JobRunner {
         context: &Context,
         job_scheduler: JobsSchedulerLocked,
     ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
-        let job_code = context.job_code.clone();
-        let notify_tx = context.notify_tx.clone();
+        let context = context.clone();
         let job_activation_rx = context.job_activation_tx.subscribe();
+        let overlaps = context.overlaps.clone();
 
         Box::pin(async move {
             tokio::spawn(JobRunner::listen_for_activations(
-                job_code,
+                context,
                 job_activation_rx,
-                notify_tx,
                 job_scheduler,
+                overlaps,
             ));
             Ok(())
         })
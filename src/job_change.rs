@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+
+///
+/// What was changed about a job that is in the scheduler, as passed to the handler set with
+/// `set_on_change`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JobChange {
+    /// Its next fire was moved, by `rearm`, `reschedule`, `set_timezone` or `refresh_schedules`,
+    /// or the fires it passes over were changed with `set_calendar` or `set_global_calendar`
+    Schedule,
+    /// Its code was replaced with `update_closure` or `update_closure_async`
+    Closure,
+    /// It was paused with `pause_until` until this time
+    Paused(DateTime<Utc>),
+    /// Its pause was ended early with `resume`
+    Resumed,
+    /// It was swapped for another job with `replace`
+    Replaced,
+    /// Its stored data was changed with `update_job_data`
+    Data,
+}
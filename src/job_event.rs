@@ -0,0 +1,66 @@
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::JobState;
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::JobState;
+use chrono::{DateTime, Utc};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+use tracing::warn;
+use uuid::Uuid;
+
+///
+/// Something that happened to a job, the same states the notifications are for
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JobEvent {
+    pub job_id: Uuid,
+    /// The name of the job, empty when it has none
+    pub name: String,
+    pub state: JobState,
+    /// When it was passed on to the event streams, by the scheduler's clock
+    pub at: DateTime<Utc>,
+    /// What went wrong, for `Failed`, `TimedOut` and `Retrying`
+    pub error: Option<String>,
+}
+
+///
+/// The events of all the scheduler's jobs as they happen, see `JobScheduler::event_stream`
+///
+/// ```rust,no_run
+/// # use tokio_cron_scheduler::JobScheduler;
+/// # async fn alert(_name: &str, _error: &str) {}
+/// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+/// let mut events = sched.event_stream();
+/// tokio::spawn(async move {
+///     while let Some(event) = events.next().await {
+///         if let Some(error) = event.error {
+///             alert(&event.name, &error).await;
+///         }
+///     }
+/// });
+/// # Ok(())
+/// # }
+/// ```
+pub struct EventStream {
+    rx: Receiver<JobEvent>,
+}
+
+impl EventStream {
+    pub(crate) fn new(rx: Receiver<JobEvent>) -> Self {
+        Self { rx }
+    }
+
+    ///
+    /// The next event, None when no more can come. A stream that falls more than 200
+    /// events behind misses the oldest ones, which is logged.
+    pub async fn next(&mut self) -> Option<JobEvent> {
+        loop {
+            match self.rx.recv().await {
+                Ok(event) => return Some(event),
+                Err(RecvError::Lagged(missed)) => {
+                    warn!("The event stream missed {} events", missed)
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
@@ -1,26 +1,80 @@
+use crate::clock::Clock;
 use crate::context::Context;
 use crate::error::JobSchedulerError;
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::{JobState, JobType};
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::{JobState, JobType};
 use crate::job::to_code::{JobCode, NotificationCode};
-use crate::job::{JobCreator, JobDeleter, JobLocked, JobRunner};
+use crate::job::{
+    cron_steps, named_timezone, AfterJob, Calendar, ConcurrencyLimit, IntervalSchedule, JobCreator,
+    JobDeleter, JobError, JobInfo, JobLocked, JobQueue, JobRunner, JobStatus, JobToRunAsync,
+    QueueStats, RateLimiter, RetryPolicy, RunBudget, RunGap, TokenBucket,
+};
+use crate::job_change::JobChange;
+use crate::job_event::EventStream;
+use crate::leader_lease::LeaderLease;
+use crate::name_conflict::NameConflict;
+use crate::next_job::NextJob;
 use crate::notification::{NotificationCreator, NotificationDeleter, NotificationRunner};
-use crate::scheduler::Scheduler;
+use crate::scheduler::{RemovalsOnShutdown, Scheduler, TickReport};
+use crate::scheduler_activity::SchedulerActivity;
+use crate::scheduler_config::SchedulerConfig;
+use crate::scheduler_handle::SchedulerHandle;
+use crate::scheduler_state::SchedulerState;
 use crate::simple::{
     SimpleJobCode, SimpleMetadataStore, SimpleNotificationCode, SimpleNotificationStore,
 };
-use crate::store::{MetaDataStorage, NotificationStore};
-use chrono::{DateTime, NaiveDateTime, Utc};
+use crate::stats::{JobRunStats, SchedulerStats};
+use crate::store::{CheckpointStore, HistoryStore, MetaDataStorage, NotificationStore, RunRecord};
+use crate::JobStoredData;
+use chrono::{DateTime, FixedOffset, Offset, TimeZone, Utc};
+use cron::Schedule;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::convert::TryInto;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
 #[cfg(all(unix, feature = "signal"))]
 use tokio::signal::unix::SignalKind;
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::RwLock;
-use tracing::{error, info};
+use tracing::{error, info, warn, Level};
 use uuid::Uuid;
 
 pub type ShutdownNotification =
     dyn FnMut() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync;
 
+pub(crate) type SharedShutdownNotification = Arc<RwLock<Box<ShutdownNotification>>>;
+
+/// Called with the failed run, see `JobError`
+pub type JobFailureHandler =
+    dyn FnMut(JobError) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync;
+
+/// Called with the job id of a job that was added
+pub type JobAddedHandler = dyn Fn(Uuid) + Send + Sync;
+
+/// Called with the job id of a job that was changed and what was changed
+pub type JobChangedHandler = dyn Fn(Uuid, JobChange) + Send + Sync;
+
+/// Called with the job id of a job that fired later than it may and how late it fired
+pub type LateHandler = dyn Fn(Uuid, Duration) + Send + Sync;
+
+/// Called after every pass of the scheduler loop
+pub type TickHandler = dyn Fn(TickReport) + Send + Sync;
+
+/// Called before the scheduler loop fires jobs, whether this instance may fire them
+pub type LeadershipCheck = dyn Fn() -> bool + Send + Sync;
+
+/// Called after each fire of a cron job for the schedule its next fire is worked out with
+pub type ScheduleProvider = dyn Fn() -> Schedule + Send + Sync;
+
+/// Called with a job about to be added, an error stops the job from being added
+pub type AddInterceptor = dyn Fn(&JobInfo) -> Result<(), JobSchedulerError> + Send + Sync;
+
 /// The JobScheduler contains and executes the scheduled jobs.
 pub struct JobsSchedulerLocked {
     pub context: Arc<Context>,
@@ -32,7 +86,6 @@ pub struct JobsSchedulerLocked {
     pub notification_deleter: Arc<RwLock<NotificationDeleter>>,
     pub notification_runner: Arc<RwLock<NotificationRunner>>,
     pub scheduler: Arc<RwLock<Scheduler>>,
-    pub shutdown_notifier: Option<Arc<RwLock<Box<ShutdownNotification>>>>,
 }
 
 impl Clone for JobsSchedulerLocked {
@@ -47,7 +100,6 @@ impl Clone for JobsSchedulerLocked {
             notification_deleter: self.notification_deleter.clone(),
             notification_runner: self.notification_runner.clone(),
             scheduler: self.scheduler.clone(),
-            shutdown_notifier: self.shutdown_notifier.clone(),
         }
     }
 }
@@ -67,12 +119,29 @@ impl JobsSchedulerLocked {
             let mut notification_storage = notification_storage.write().await;
             notification_storage.init().await?;
         }
+        let jobs = {
+            let mut metadata_storage = metadata_storage.write().await;
+            metadata_storage.list_jobs().await?
+        };
         let context = Context::new(
             metadata_storage,
             notification_storage,
             job_code.clone(),
             notify_code.clone(),
         );
+        {
+            // The names of the jobs the metadata store has from before, so they're taken
+            let mut w = context.job_names.write().await;
+            w.extend(
+                jobs.iter()
+                    .filter(|data| !data.name.is_empty())
+                    .filter_map(|data| {
+                        data.id
+                            .as_ref()
+                            .map(|id| (data.name.clone(), Uuid::from(id)))
+                    }),
+            );
+        }
         {
             let mut job_code = job_code.write().await;
             job_code.init(&context).await?;
@@ -198,7 +267,6 @@ impl JobsSchedulerLocked {
             notification_deleter: Arc::new(Default::default()),
             notification_runner: Arc::new(Default::default()),
             scheduler: Arc::new(Default::default()),
-            shutdown_notifier: None,
         };
 
         Ok(val)
@@ -236,7 +304,6 @@ impl JobsSchedulerLocked {
             notification_deleter: Arc::new(Default::default()),
             notification_runner: Arc::new(Default::default()),
             scheduler: Arc::new(Default::default()),
-            shutdown_notifier: None,
         };
 
         Ok(val)
@@ -244,6 +311,13 @@ impl JobsSchedulerLocked {
 
     /// Add a job to the `JobScheduler`
     ///
+    /// Returns once the job's metadata is saved in the metadata store, so with a persistent store
+    /// like Postgres or Nats the job is registered there when this returns. An error saving it is
+    /// returned here.
+    ///
+    /// A job named with `JobBuilder::with_name` whose name another job has already is rejected
+    /// with `NameTaken`, or takes the other job's place, see `set_name_conflict`.
+    ///
     /// ```rust,ignore
     /// use tokio_cron_scheduler::{Job, JobScheduler, JobToRun};
     /// let mut sched = JobScheduler::new();
@@ -251,7 +325,7 @@ impl JobsSchedulerLocked {
     ///     println!("I get executed every 10 seconds!");
     /// })).await;
     /// ```
-    pub async fn add(&self, job: JobLocked) -> Result<Uuid, JobSchedulerError> {
+    pub async fn add(&self, mut job: JobLocked) -> Result<Uuid, JobSchedulerError> {
         let guid = job.guid();
         if !self.inited().await {
             info!("Uninited");
@@ -260,12 +334,493 @@ impl JobsSchedulerLocked {
         }
 
         let context = self.context.clone();
+        job.anchor(context.now())?;
+        let data = job.job_data()?;
+        if let Some(taken) = self.named(&data.name).await?.filter(|id| *id != guid) {
+            let name_conflict = *self.context.name_conflict.read().await;
+            match name_conflict {
+                NameConflict::Reject => return Err(JobSchedulerError::NameTaken(data.name)),
+                NameConflict::Replace => {
+                    self.clone().replace(&taken, job).await?;
+                    return Ok(taken);
+                }
+            }
+        }
+        let after = data.after.as_ref().and_then(AfterJob::from_stored);
+        if let Some(after) = after.as_ref() {
+            self.check_after(&guid, data.job_type, after).await?;
+            let r = self.context.after_jobs.read().await;
+            JobsSchedulerLocked::check_chain(&r, &guid, after)?;
+        }
+        self.check_new(&data).await?;
         JobCreator::add(&context, job).await?;
         info!("Job creator created");
+        if let Some(after) = after {
+            let mut w = self.context.after_jobs.write().await;
+            w.insert(guid, after);
+        }
+
+        let r = context
+            .job_added_handler
+            .read()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(on_add) = r.as_ref() {
+            (on_add)(guid);
+        }
 
         Ok(guid)
     }
 
+    ///
+    /// The id of the job with a name, None for an empty name. It's looked up in the names kept in
+    /// the context, and checked against the metadata store so a job that's gone isn't given.
+    async fn named(&self, name: &str) -> Result<Option<Uuid>, JobSchedulerError> {
+        if name.is_empty() {
+            return Ok(None);
+        }
+        let id = {
+            let r = self.context.job_names.read().await;
+            match r.get(name) {
+                Some(id) => *id,
+                None => return Ok(None),
+            }
+        };
+        let named = {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(id).await?.is_some_and(|data| data.name == name)
+        };
+        if !named {
+            let mut w = self.context.job_names.write().await;
+            if w.get(name) == Some(&id) {
+                w.remove(name);
+            }
+            return Ok(None);
+        }
+        Ok(Some(id))
+    }
+
+    ///
+    /// The job with a name given with `JobBuilder::with_name`, None when no job has it
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// if let Some(job) = sched.get_job_by_name("nightly-report").await? {
+    ///     println!("nightly-report is {} and fires at {:?}", job.id, job.next_tick);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_job_by_name(&self, name: &str) -> Result<Option<JobInfo>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let id = match self.named(name).await? {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+        let mut r = self.context.metadata_storage.write().await;
+        Ok(r.get(id).await?.as_ref().and_then(JobInfo::from_stored))
+    }
+
+    ///
+    /// Remove the job with a name as `remove` does. Returns whether there was a job with the name.
+    pub async fn remove_by_name(&self, name: &str) -> Result<bool, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        match self.named(name).await? {
+            Some(id) => self.remove(&id).await.map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    ///
+    /// Set what `add` does with a job whose name another job has already, it's rejected with
+    /// `NameTaken` by default
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{JobBuilder, JobScheduler, NameConflict};
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.set_name_conflict(NameConflict::Replace).await;
+    /// // Swaps the job added with this name before the restart for this one
+    /// let job = JobBuilder::new()
+    ///     .with_name("nightly-report")
+    ///     .with_cron_job_type()
+    ///     .with_schedule("0 0 3 * * *")?
+    ///     .with_run_async(Box::new(|_uuid, _l| Box::pin(async move {})))
+    ///     .build()?;
+    /// sched.add(job).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_name_conflict(&self, name_conflict: NameConflict) {
+        let mut w = self.context.name_conflict.write().await;
+        *w = name_conflict;
+    }
+
+    ///
+    /// Whether a job may be added: its schedule fires within the schedule horizon and the add
+    /// interceptor lets it in
+    async fn check_new(&self, data: &JobStoredData) -> Result<(), JobSchedulerError> {
+        if let Some(schedule) = data.schedule() {
+            let horizon = {
+                let r = self.context.schedule_horizon.read().await;
+                chrono::Duration::from_std(*r).unwrap_or(chrono::Duration::MAX)
+            };
+            let now = self.context.now();
+            let fires = data
+                .next_cron_tick(&now)
+                .map(|next| next - now <= horizon)
+                .unwrap_or_default();
+            if !fires {
+                return Err(JobSchedulerError::UnsatisfiableSchedule(
+                    schedule.to_string(),
+                ));
+            }
+        }
+        let r = self
+            .context
+            .add_interceptor
+            .read()
+            .unwrap_or_else(|e| e.into_inner());
+        if let (Some(intercept), Some(info)) = (r.as_ref(), JobInfo::from_stored(data)) {
+            (intercept)(&info)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Swap the job with id `job_id` for `job` in one go, keeping the id, the run counts and when
+    /// it last ran. The schedule, code and options all come from `job`, which is checked like a
+    /// job that is added. The job isn't removed in between, so no removal notifications are sent,
+    /// and what was set for the id in the scheduler, like a pause or `set_max_lateness`, stays.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{Job, JobScheduler};
+    /// # use uuid::Uuid;
+    /// # struct Config {
+    /// #     schedule: String,
+    /// # }
+    /// # fn run_export(_uuid: Uuid, _l: JobScheduler) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>> {
+    /// #     Box::pin(async move {})
+    /// # }
+    /// # async fn example(mut sched: JobScheduler, export_job_id: Uuid, config: Config) -> Result<(), Box<dyn std::error::Error>> {
+    /// let job = Job::new_async(config.schedule.as_str(), run_export)?;
+    /// sched.replace(&export_job_id, job).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn replace(
+        &mut self,
+        job_id: &Uuid,
+        mut job: JobLocked,
+    ) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let old = {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?
+        };
+        job.anchor(self.context.now())?;
+        let mut data = job.job_data()?;
+        data.id = Some(job_id.into());
+        data.count = old.count;
+        data.ran = old.ran;
+        data.last_tick = old.last_tick;
+        if data.after.is_none() {
+            data.after = old.after;
+        }
+        let after = data.after.as_ref().and_then(AfterJob::from_stored);
+        if let Some(after) = after.as_ref() {
+            self.check_after(job_id, data.job_type, after).await?;
+            let r = self.context.after_jobs.read().await;
+            JobsSchedulerLocked::check_chain(&r, job_id, after)?;
+        }
+        self.check_new(&data).await?;
+        job.set_job_data(data)?;
+        JobCreator::add(&self.context, job).await?;
+        if let Some(after) = after {
+            let mut w = self.context.after_jobs.write().await;
+            w.insert(*job_id, after);
+        }
+        self.changed(*job_id, JobChange::Replaced);
+        Ok(())
+    }
+
+    ///
+    /// Give a cron job another schedule in place, keeping its id, code, notifications, run counts
+    /// and everything else. Its next tick is worked out from now with the new schedule, a stopped
+    /// job stays stopped and gets the new schedule when it's started again. The schedule is
+    /// checked like the one of a job that is added. A schedule provider set for the job still
+    /// gives the schedule on later ticks. Returns `InvalidTrigger` for jobs that aren't cron jobs.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, report_job_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.reschedule(&report_job_id, "0 30 6 * * *").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn reschedule<S, E>(
+        &self,
+        job_id: &Uuid,
+        schedule: S,
+    ) -> Result<(), JobSchedulerError>
+    where
+        S: TryInto<Schedule, Error = E>,
+        E: std::error::Error + 'static,
+    {
+        let schedule: Schedule = schedule
+            .try_into()
+            .map_err(|_| JobSchedulerError::ParseSchedule)?;
+        cron_steps::validate_steps(&schedule.to_string())?;
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let mut w = self.context.metadata_storage.write().await;
+        let mut data = w
+            .get(*job_id)
+            .await?
+            .ok_or(JobSchedulerError::JobNotFound)?;
+        if data.job_type != JobType::Cron as i32 {
+            return Err(JobSchedulerError::InvalidTrigger(format!(
+                "{} isn't a cron job, it has no schedule to change",
+                job_id
+            )));
+        }
+        if !data.set_schedule(&schedule) {
+            return Ok(());
+        }
+        self.check_new(&data).await?;
+        if data.next_tick != 0 {
+            let offset = FixedOffset::east_opt(data.time_offset_seconds)
+                .unwrap_or(FixedOffset::east_opt(0).unwrap());
+            let next_tick = data
+                .next_cron_fire(&self.context.now().with_timezone(&offset))
+                .map(|t| t.with_timezone(&Utc));
+            data.set_next_tick(next_tick);
+        }
+        w.add_or_update(data).await?;
+        self.changed(*job_id, JobChange::Schedule);
+        Ok(())
+    }
+
+    ///
+    /// Give a repeated job an interval schedule in place, as `reschedule` does for cron jobs. The
+    /// schedule starts over from now, its runs so far aren't counted in its `max_runs`. Returns
+    /// `InvalidTrigger` for jobs that aren't repeated jobs and `NoNextTick` for a schedule that's
+    /// over before it fires.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::{IntervalSchedule, JobScheduler};
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, poll_job_id: Uuid, closing_time: chrono::DateTime<chrono::Utc>) -> Result<(), Box<dyn std::error::Error>> {
+    /// let schedule = IntervalSchedule::every(Duration::from_secs(90)).ending_at(closing_time);
+    /// sched.reschedule_interval(&poll_job_id, schedule).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn reschedule_interval(
+        &self,
+        job_id: &Uuid,
+        schedule: IntervalSchedule,
+    ) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let mut w = self.context.metadata_storage.write().await;
+        let mut data = w
+            .get(*job_id)
+            .await?
+            .ok_or(JobSchedulerError::JobNotFound)?;
+        if data.job_type != JobType::Repeated as i32 {
+            return Err(JobSchedulerError::InvalidTrigger(format!(
+                "{} isn't a repeated job, it has no interval to change",
+                job_id
+            )));
+        }
+        let jitter = chrono::Duration::seconds(data.jitter_seconds as i64);
+        let bounds = schedule
+            .bounds(self.context.now(), false, jitter)
+            .ok_or(JobSchedulerError::NoNextTick)?;
+        data.set_repeated_every(schedule.every_seconds());
+        if data.next_tick != 0 {
+            data.next_tick = bounds.first;
+        }
+        data.interval_bounds = Some(bounds);
+        self.check_new(&data).await?;
+        w.add_or_update(data).await?;
+        self.changed(*job_id, JobChange::Schedule);
+        Ok(())
+    }
+
+    ///
+    /// Change the stored data of a job in place with `update`, keeping its id, code and
+    /// notifications. The metadata store is held from reading the data until the changed data is
+    /// saved, so the scheduler doesn't see the job in between. The changed data is checked like
+    /// the one of a job that is added. The next tick is saved as `update` leaves it, use
+    /// `reschedule` to change a schedule and move the next tick with it.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{JobPayload, JobScheduler};
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid, settings: String) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.update_job_data(&job_id, |data| {
+    ///     data.tags.push("tenant:42".to_string());
+    ///     data.extra = settings.to_extra();
+    /// }).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn update_job_data<F>(
+        &self,
+        job_id: &Uuid,
+        update: F,
+    ) -> Result<(), JobSchedulerError>
+    where
+        F: FnOnce(&mut JobStoredData),
+    {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let mut w = self.context.metadata_storage.write().await;
+        let mut data = w
+            .get(*job_id)
+            .await?
+            .ok_or(JobSchedulerError::JobNotFound)?;
+        update(&mut data);
+        data.id = Some(job_id.into());
+        self.check_new(&data).await?;
+        let name = data.name.clone();
+        w.add_or_update(data).await?;
+        drop(w);
+        self.context.name_job(*job_id, &name).await;
+        self.changed(*job_id, JobChange::Data);
+        Ok(())
+    }
+
+    ///
+    /// Set where the runs of jobs are kept for `job_history`, by default the last 100 runs of every
+    /// job in memory. None stops keeping them.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{JobScheduler, SimpleHistoryStore};
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// // The last 1000 runs of every job, in memory
+    /// sched.set_history_store(Some(Box::new(SimpleHistoryStore::new(1000)))).await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_history_store(&self, store: Option<Box<dyn HistoryStore + Send + Sync>>) {
+        let mut w = self.context.history_store.write().await;
+        *w = store;
+    }
+
+    ///
+    /// The last `limit` runs of a job that finished, the last one first, from the history store
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{JobNotification, JobScheduler};
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// let last_success = sched
+    ///     .job_history(&job_id, 50)
+    ///     .await?
+    ///     .into_iter()
+    ///     .find(|run| run.outcome == JobNotification::Done);
+    /// if let Some(run) = last_success {
+    ///     println!("Last went well at {} in {:?}", run.started, run.duration);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn job_history(
+        &self,
+        job_id: &Uuid,
+        limit: usize,
+    ) -> Result<Vec<RunRecord>, JobSchedulerError> {
+        let mut w = self.context.history_store.write().await;
+        let store = w.as_mut().ok_or(JobSchedulerError::NoHistoryStore)?;
+        store.list(*job_id, limit).await
+    }
+
+    ///
+    /// Set where at least once jobs keep when their last successful run started
+    pub async fn set_checkpoint_store(&self, store: Box<dyn CheckpointStore + Send + Sync>) {
+        let mut w = self.context.checkpoint_store.write().await;
+        *w = Some(store);
+    }
+
+    /// Add a job that must not miss fires, even across restarts. Every successful run is
+    /// checkpointed in the checkpoint store. When the job is added again, for instance after a
+    /// restart, the fires since its checkpoint are run right away, going back at most `max_catchup`.
+    /// These runs go through the job's overlap policy, `OverlapPolicy::queue` runs them one
+    /// after another. Keep the job id the same across restarts, for instance with `JobBuilder::with_job_id`.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::{CheckpointStore, JobBuilder, JobScheduler};
+    /// # use uuid::Uuid;
+    /// # async fn invoice() {}
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid, my_store: impl CheckpointStore + Send + Sync + 'static) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.set_checkpoint_store(Box::new(my_store)).await;
+    /// let job = JobBuilder::new()
+    ///     .with_job_id(job_id.into())
+    ///     .with_cron_job_type()
+    ///     .with_schedule("0 0 * * * *")?
+    ///     .with_run_async(Box::new(|_uuid, _l| Box::pin(async move { invoice().await })))
+    ///     .build()?;
+    /// sched.add_at_least_once(job, Duration::from_secs(24 * 60 * 60)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn add_at_least_once(
+        &self,
+        job: JobLocked,
+        max_catchup: Duration,
+    ) -> Result<Uuid, JobSchedulerError> {
+        let job_id = job.guid();
+        let checkpoint = {
+            let mut w = self.context.checkpoint_store.write().await;
+            let store = w.as_mut().ok_or(JobSchedulerError::NoCheckpointStore)?;
+            store.get(job_id).await?
+        };
+        self.add(job).await?;
+        {
+            let mut w = self.context.at_least_once.write().await;
+            w.insert(job_id, max_catchup);
+        }
+
+        if let Some(checkpoint) = checkpoint {
+            let now = self.context.now();
+            let window =
+                chrono::Duration::from_std(max_catchup).unwrap_or_else(|_| now - checkpoint);
+            let from = checkpoint.max(now - window);
+            let missed = self.fires_in_range(&job_id, from, now).await?;
+            if missed > 0 {
+                info!("Running {} missed fires of {:?}", missed, job_id);
+            }
+            for _ in 0..missed {
+                if let Err(e) = self.context.job_activation_tx.send(job_id) {
+                    error!("Error sending job activation tx {:?}", e);
+                }
+            }
+        }
+        Ok(job_id)
+    }
+
     /// Remove a job from the `JobScheduler`
     ///
     /// ```rust,ignore
@@ -279,6 +834,9 @@ impl JobsSchedulerLocked {
     ///
     /// Note, the UUID of the job can be fetched calling .guid() on a Job.
     ///
+    /// Returns once the job is deleted from the metadata store and its removed notifications ran.
+    /// An error deleting it is returned here.
+    ///
     pub async fn remove(&self, to_be_removed: &Uuid) -> Result<(), JobSchedulerError> {
         if !self.inited().await {
             let mut s = self.clone();
@@ -289,8 +847,206 @@ impl JobsSchedulerLocked {
         JobDeleter::remove(&context, to_be_removed).await
     }
 
-    /// The `start` spawns a Tokio task where it loops. Every 500ms it
-    /// runs the tick method to increment any pending jobs.
+    /// Stop a job from running again, wait for the runs that are going to finish and then remove
+    /// it, which sends its removed notifications. Returns whether there were runs to wait for.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// let waited = sched.stop_graceful(&job_id).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn stop_graceful(&mut self, job_id: &Uuid) -> Result<bool, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+
+        let mut rx = self.context.notify_tx.subscribe();
+        {
+            let mut w = self.context.metadata_storage.write().await;
+            let mut data = w
+                .get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?;
+            data.stopped = true;
+            w.add_or_update(data).await?;
+        }
+
+        let mut waited = false;
+        loop {
+            let running = {
+                let r = self.context.stats.read().await;
+                r.job(job_id).map(|j| j.running > 0).unwrap_or_default()
+            };
+            if !running {
+                break;
+            }
+            waited = true;
+            // The runner updates the stats before it sends that the run is done or failed
+            match rx.recv().await {
+                Ok((
+                    id,
+                    JobState::Done | JobState::Failed | JobState::TimedOut | JobState::Cancelled,
+                )) if id == *job_id => {}
+                Err(RecvError::Closed) => break,
+                _ => {}
+            }
+        }
+
+        self.remove(job_id).await?;
+        Ok(waited)
+    }
+
+    ///
+    /// Wait until a job finished a run, returning right away if it did already. A failed, timed
+    /// out or cancelled run counts as well. Returns `WaitTimedOut` when no run finished within `timeout`.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::{Job, JobScheduler};
+    /// # async fn serve() {}
+    /// # async fn example(mut sched: JobScheduler, warm_cache: Job) -> Result<(), Box<dyn std::error::Error>> {
+    /// let job_id = sched.add(warm_cache).await?;
+    /// sched.start().await?;
+    /// sched.wait_for_first_run(&job_id, Duration::from_secs(30)).await?;
+    /// serve().await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_for_first_run(
+        &self,
+        job_id: &Uuid,
+        timeout: Duration,
+    ) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+
+        let mut rx = self.context.notify_tx.subscribe();
+        {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?;
+        }
+        let finished = {
+            let r = self.context.stats.read().await;
+            r.job(job_id)
+                .map(|j| j.runs > j.running as u64)
+                .unwrap_or_default()
+        };
+        if finished {
+            return Ok(());
+        }
+
+        let wait = async {
+            loop {
+                match rx.recv().await {
+                    Ok((
+                        id,
+                        JobState::Done
+                        | JobState::Failed
+                        | JobState::TimedOut
+                        | JobState::Cancelled,
+                    )) if id == *job_id => return Ok(()),
+                    Err(RecvError::Closed) => return Err(JobSchedulerError::WaitTimedOut),
+                    _ => {}
+                }
+            }
+        };
+        tokio::time::timeout(timeout, wait)
+            .await
+            .unwrap_or(Err(JobSchedulerError::WaitTimedOut))
+    }
+
+    /// Replace the code that is run for a job, keeping its UUID, schedule and notifications.
+    /// The new code is used from the next run onwards, runs already in flight finish with the
+    /// code they were started with. A job made with async code gets its code replaced with
+    /// `update_closure_async`, `update_closure` returns `JobSchedulerError::ClosureKindMismatch`
+    /// for it.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.update_closure(&job_id, |_uuid, _lock| {
+    ///     println!("I'm the new code");
+    /// }).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn update_closure<T>(
+        &self,
+        job_id: &Uuid,
+        mut run: T,
+    ) -> Result<(), JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) + Send + Sync,
+    {
+        let run: Box<JobToRunAsync> = Box::new(move |job_id, job_scheduler| {
+            run(job_id, job_scheduler);
+            Box::pin(std::future::ready(()))
+        });
+        self.replace_closure(job_id, false, run).await
+    }
+
+    /// Replace the code that is run for a job made with async code. See `update_closure`, which
+    /// replaces the code of a job made with sync code; `update_closure_async` returns
+    /// `JobSchedulerError::ClosureKindMismatch` for those.
+    pub async fn update_closure_async<T>(
+        &self,
+        job_id: &Uuid,
+        run: T,
+    ) -> Result<(), JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync,
+    {
+        self.replace_closure(job_id, true, Box::new(run)).await
+    }
+
+    async fn replace_closure(
+        &self,
+        job_id: &Uuid,
+        is_async: bool,
+        run: Box<JobToRunAsync>,
+    ) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+
+        let code = {
+            let mut job_code = self.context.job_code.write().await;
+            job_code.get(*job_id).await?
+        };
+        let code = code.ok_or(JobSchedulerError::JobNotFound)?;
+        let made_async = !self.context.sync_jobs.read().await.contains(job_id);
+        if made_async != is_async {
+            let (made, other) = if made_async {
+                ("async", "update_closure_async")
+            } else {
+                ("sync", "update_closure")
+            };
+            return Err(JobSchedulerError::ClosureKindMismatch(format!(
+                "job {job_id} was made with {made} code, replace it with {other}"
+            )));
+        }
+        let mut w = code.write().await;
+        *w = run;
+        self.changed(*job_id, JobChange::Closure);
+        Ok(())
+    }
+
+    /// The `start` spawns a Tokio task where it loops. It sleeps until the next job is due, at
+    /// most 500ms unless set otherwise with `set_max_sleep`, and then fires the pending jobs.
     ///
     /// ```rust,ignore
     /// if let Err(e) = sched.start().await {
@@ -316,113 +1072,3017 @@ impl JobsSchedulerLocked {
 
     /// The `time_till_next_job` method returns the duration till the next job
     /// is supposed to run. This can be used to sleep until then without waking
-    /// up at a fixed interval.AsMut
+    /// up at a fixed interval. `NextJob::None` means no job has a next tick, `NextJob::Overdue`
+    /// that a job is due already, so there's no reason to sleep.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::{JobScheduler, NextJob};
+    /// # async fn example(mut sched: JobScheduler, idle: Duration) -> Result<(), Box<dyn std::error::Error>> {
+    /// match sched.time_till_next_job().await? {
+    ///     NextJob::In(duration) => tokio::time::sleep(duration).await,
+    ///     NextJob::None => tokio::time::sleep(idle).await,
+    ///     NextJob::Overdue => {}
+    /// }
+    /// # Ok(())
+    /// # }
     /// ```
-    pub async fn time_till_next_job(
-        &mut self,
-    ) -> Result<Option<std::time::Duration>, JobSchedulerError> {
+    pub async fn time_till_next_job(&mut self) -> Result<NextJob, JobSchedulerError> {
         if !self.inited().await {
             let mut s = self.clone();
             s.init().await?;
         }
         let metadata = self.context.metadata_storage.clone();
 
+        let paused = {
+            let r = self.context.paused_until.read().await;
+            r.clone()
+        };
+        let after_jobs = {
+            let r = self.context.after_jobs.read().await;
+            r.keys().copied().collect::<HashSet<_>>()
+        };
         let mut metadata = metadata.write().await;
-        let ret = metadata.time_till_next_job().await;
+        let ret = metadata.list_next_ticks().await;
 
-        match ret {
-            Ok(ret) => Ok(ret),
+        let next_tick = match ret {
+            Ok(ret) => ret
+                .iter()
+                .filter(|n| n.next_tick != 0)
+                .filter(|n| match n.id.as_ref() {
+                    Some(id) => !after_jobs.contains(&id.into()),
+                    None => true,
+                })
+                .filter_map(|n| {
+                    let next_tick = n.next_tick_utc()?;
+                    let paused_until = n.id.as_ref().and_then(|id| paused.get(&id.into())).copied();
+                    Some(paused_until.map_or(next_tick, |until| until.max(next_tick)))
+                })
+                .min(),
             Err(e) => {
                 error!("Error getting return of time till next job {:?}", e);
-                Err(JobSchedulerError::CantGetTimeUntil)
+                return Err(JobSchedulerError::CantGetTimeUntil);
             }
+        };
+        let next_tick = match next_tick {
+            Some(next_tick) => next_tick,
+            None => return Ok(NextJob::None),
+        };
+        match (next_tick - self.context.now()).to_std() {
+            Ok(duration) if !duration.is_zero() => Ok(NextJob::In(duration)),
+            _ => Ok(NextJob::Overdue),
         }
     }
 
-    /// `next_tick_for_job` returns the date/time for when the next tick will
-    /// be for a job
-    pub async fn next_tick_for_job(
-        &mut self,
-        job_id: Uuid,
-    ) -> Result<Option<DateTime<Utc>>, JobSchedulerError> {
+    ///
+    /// The jobs with an upcoming fire and when that is, soonest first. A job paused with
+    /// `pause_until` fires at the end of its pause at the earliest. Stopped and paused jobs are
+    /// left out unless `include_paused` is true.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// for (job_id, at) in sched.jobs_by_next_fire(false).await? {
+    ///     println!("{} fires at {}", job_id, at);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn jobs_by_next_fire(
+        &self,
+        include_paused: bool,
+    ) -> Result<Vec<(Uuid, DateTime<Utc>)>, JobSchedulerError> {
         if !self.inited().await {
             let mut s = self.clone();
             s.init().await?;
         }
-        let mut r = self.context.metadata_storage.write().await;
-        r.get(job_id).await.map(|v| {
-            if let Some(vv) = v {
-                if vv.next_tick == 0 {
+        let paused = {
+            let r = self.context.paused_until.read().await;
+            r.clone()
+        };
+        let jobs = {
+            let mut r = self.context.metadata_storage.write().await;
+            r.list_jobs().await?
+        };
+        let now = self.context.now();
+        let mut fires = jobs
+            .iter()
+            .filter(|data| data.next_tick != 0)
+            .filter_map(|data| {
+                let job_id: Uuid = data.id.as_ref()?.into();
+                let next_tick = data.next_tick_utc()?;
+                let paused_until = paused.get(&job_id).filter(|until| **until > now);
+                if (data.stopped || data.paused || paused_until.is_some()) && !include_paused {
                     return None;
                 }
-                match NaiveDateTime::from_timestamp_opt(vv.next_tick as i64, 0) {
-                    None => None,
-                    Some(ts) => Some(DateTime::from_naive_utc_and_offset(ts, Utc)),
-                }
-            } else {
-                None
-            }
-        })
+                let at = paused_until.map_or(next_tick, |until| next_tick.max(*until));
+                Some(Scheduler::fire_order(
+                    at,
+                    data.priority,
+                    data.order_key,
+                    job_id,
+                ))
+            })
+            .collect::<Vec<_>>();
+        fires.sort();
+        Ok(fires
+            .into_iter()
+            .map(|(at, .., job_id)| (job_id, at))
+            .collect())
     }
 
     ///
-    /// Shut the scheduler down
-    pub async fn shutdown(&mut self) -> Result<(), JobSchedulerError> {
-        let mut notify = None;
-        std::mem::swap(&mut self.shutdown_notifier, &mut notify);
+    /// Which job fires soonest and when, None when no job has an upcoming fire. Stopped and
+    /// paused jobs are left out, as are jobs fired after other jobs with `set_after_job`.
+    /// On a tie it's the job that's fired first.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, names: std::collections::HashMap<Uuid, String>) -> Result<(), Box<dyn std::error::Error>> {
+    /// if let Some((job_id, at)) = sched.next_job().await? {
+    ///     println!("Next: {} at {}", names[&job_id], at);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn next_job(&self) -> Result<Option<(Uuid, DateTime<Utc>)>, JobSchedulerError> {
+        let after_jobs = {
+            let r = self.context.after_jobs.read().await;
+            r.keys().copied().collect::<HashSet<_>>()
+        };
+        Ok(self
+            .jobs_by_next_fire(false)
+            .await?
+            .into_iter()
+            .find(|(job_id, _)| !after_jobs.contains(job_id)))
+    }
 
-        let mut scheduler = self.scheduler.write().await;
-        scheduler.shutdown().await;
+    ///
+    /// The next `n` fires of all jobs together, soonest first and in the order they're fired when
+    /// they're at the same time. A job that fires more than once before the others is in there
+    /// more than once, one off fires are in there as well. Stopped and paused jobs are left out,
+    /// as are jobs fired after other jobs with `set_after_job`.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// for (at, job_id) in sched.upcoming_dispatches(10).await? {
+    ///     println!("{} fires at {}", job_id, at);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn upcoming_dispatches(
+        &self,
+        n: usize,
+    ) -> Result<Vec<(DateTime<Utc>, Uuid)>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let now = self.context.now();
+        let left_out = {
+            let paused = self.context.paused_until.read().await;
+            let after_jobs = self.context.after_jobs.read().await;
+            paused
+                .iter()
+                .filter(|(_, until)| **until > now)
+                .map(|(id, _)| *id)
+                .chain(after_jobs.keys().copied())
+                .collect::<HashSet<_>>()
+        };
+        let jobs = {
+            let mut r = self.context.metadata_storage.write().await;
+            r.list_jobs().await?
+        };
+        let jobs = jobs
+            .into_iter()
+            .filter(|data| data.next_tick != 0 && !data.stopped && !data.paused)
+            .filter_map(|data| {
+                let job_id: Uuid = data.id.as_ref()?.into();
+                (!left_out.contains(&job_id)).then_some((job_id, data))
+            })
+            .collect::<HashMap<_, _>>();
 
-        if let Some(notify) = notify {
-            let mut notify = notify.write().await;
-            notify().await;
+        // Merge the fires of the jobs, with the next fire of each job in the heap at most once.
+        // One off fires aren't followed by another.
+        let mut heap = BinaryHeap::new();
+        for (job_id, data) in jobs.iter() {
+            if let Some(at) = data.next_tick_utc() {
+                let order = Scheduler::fire_order(at, data.priority, data.order_key, *job_id);
+                heap.push(Reverse((order, true)));
+            }
         }
-        Ok(())
+        {
+            let r = self.context.one_offs.read().await;
+            for (job_id, whens) in r.iter().filter(|(id, _)| jobs.contains_key(*id)) {
+                let data = &jobs[job_id];
+                for at in whens.iter().filter(|at| **at > now) {
+                    let order = Scheduler::fire_order(*at, data.priority, data.order_key, *job_id);
+                    heap.push(Reverse((order, false)));
+                }
+            }
+        }
+        let mut upcoming = Vec::with_capacity(n.min(heap.len()));
+        while upcoming.len() < n {
+            let Reverse(((at, priority, order_key, job_id), scheduled)) = match heap.pop() {
+                Some(fire) => fire,
+                None => break,
+            };
+            upcoming.push((at, job_id));
+            if !scheduled {
+                continue;
+            }
+            if let Some(next) = JobsSchedulerLocked::fire_after(&jobs[&job_id], at) {
+                heap.push(Reverse(((next, priority, order_key, job_id), true)));
+            }
+        }
+        Ok(upcoming)
     }
 
     ///
-    /// Wait for a signal to shut the runtime down with
-    #[cfg(all(unix, feature = "signal"))]
-    pub fn shutdown_on_signal(&self, signal: SignalKind) {
-        let mut l = self.clone();
-        tokio::spawn(async move {
-            if let Some(_k) = tokio::signal::unix::signal(signal)
-                .expect("Can't wait for signal")
-                .recv()
-                .await
-            {
-                l.shutdown().await.expect("Problem shutting down");
+    /// The scheduled fire of a job after its fire at `at`, None for one shot jobs
+    pub(crate) fn fire_after(data: &JobStoredData, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match JobType::from_i32(data.job_type) {
+            Some(JobType::Cron) => {
+                let offset = FixedOffset::east_opt(data.time_offset_seconds)
+                    .unwrap_or(FixedOffset::east_opt(0).unwrap());
+                data.next_cron_tick(&at.with_timezone(&offset))
+                    .map(|next| next.with_timezone(&Utc))
             }
-        });
-    }
+            Some(JobType::Repeated) if data.interval_bounds.is_some() => {
+                data.next_interval_fire(at)
+            }
+            Some(JobType::Repeated) => data
+                .repeated_every()
+                .filter(|every| *every > 0)
+                .and_then(|every| at.checked_add_signed(chrono::Duration::seconds(every as i64))),
+            _ => None,
+        }
+    }
+
+    ///
+    /// The next `n` times a job fires, with what's known up front applied on top of its schedule:
+    /// the fires during its pause are left out, fires too soon after the one before are moved by
+    /// the minimum interval and its one off fires are in there. A stopped job and a job fired
+    /// after another job with `set_after_job` have none. What's only known when it fires, like
+    /// whether the scheduler is the leader or a run is still going, isn't taken into account.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// for at in sched.effective_upcoming(&job_id, 5).await? {
+    ///     println!("Fires at {}", at);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn effective_upcoming(
+        &self,
+        job_id: &Uuid,
+        n: usize,
+    ) -> Result<Vec<DateTime<Utc>>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let data = {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?
+        };
+        let fired_after = {
+            let r = self.context.after_jobs.read().await;
+            r.contains_key(job_id)
+        };
+        if data.stopped || data.paused || fired_after || n == 0 {
+            return Ok(vec![]);
+        }
+        let now = self.context.now();
+        let paused_until = {
+            let r = self.context.paused_until.read().await;
+            r.get(job_id).copied().filter(|until| *until > now)
+        };
+        let min_interval = {
+            let r = self.context.min_interval.read().await;
+            chrono::Duration::from_std(*r).unwrap_or_else(|_| chrono::Duration::zero())
+        };
+        let passed_over = |at: &DateTime<Utc>| paused_until.is_some_and(|until| *at < until);
+
+        let mut fires = vec![];
+        let mut next = if data.next_tick == 0 {
+            None
+        } else {
+            data.next_tick_utc()
+        };
+        while let Some(at) = next {
+            if fires.len() >= n {
+                break;
+            }
+            if !passed_over(&at) {
+                fires.push(at);
+            }
+            // As the scheduler loop does, the fire after is at least the minimum interval later
+            next =
+                JobsSchedulerLocked::fire_after(&data, at).map(|next| next.max(at + min_interval));
+        }
+        {
+            let r = self.context.one_offs.read().await;
+            if let Some(whens) = r.get(job_id) {
+                fires.extend(
+                    whens
+                        .iter()
+                        .filter(|at| **at > now && !passed_over(at))
+                        .copied(),
+                );
+            }
+        }
+        fires.sort();
+        fires.truncate(n);
+        Ok(fires)
+    }
+
+    ///
+    /// The next `n` times a cron expression would fire if a job were made with it now, by the
+    /// scheduler's clock and in the timezone set with `set_timezone`, UTC when none was set. It
+    /// fails as `validate_cron` does, with the horizon set with `set_schedule_horizon`, so a web
+    /// UI can check what a user entered before a job is made with it.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{JobScheduler, JobSchedulerError};
+    /// # struct Form {
+    /// #     schedule: String,
+    /// # }
+    /// # fn show(_fires: Vec<chrono::DateTime<chrono::Utc>>) {}
+    /// # fn bad_request(_why: String) {}
+    /// # async fn example(mut sched: JobScheduler, form: Form) {
+    /// match sched.preview(&form.schedule, 5).await {
+    ///     Ok(fires) => show(fires),
+    ///     Err(JobSchedulerError::InvalidCron(why)) => bad_request(why),
+    ///     Err(e) => bad_request(e.to_string()),
+    /// }
+    /// # }
+    /// ```
+    pub async fn preview(
+        &self,
+        expr: &str,
+        n: usize,
+    ) -> Result<Vec<DateTime<Utc>>, JobSchedulerError> {
+        let offset = {
+            let r = self.context.timezone.read().await;
+            r.unwrap_or(FixedOffset::east_opt(0).unwrap())
+        };
+        Ok(self
+            .preview_tz(expr, n, offset)
+            .await?
+            .into_iter()
+            .map(|at| at.with_timezone(&Utc))
+            .collect())
+    }
+
+    ///
+    /// `preview` in another timezone, as a job made with `Job::new_tz` would fire. With the
+    /// `timezone` feature a `chrono_tz::Tz` is followed through daylight saving time.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// let fires = sched.preview_tz("0 0 9 * * *", 3, chrono_tz::America::New_York).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn preview_tz<TZ: TimeZone>(
+        &self,
+        expr: &str,
+        n: usize,
+        timezone: TZ,
+    ) -> Result<Vec<DateTime<TZ>>, JobSchedulerError> {
+        let horizon = {
+            let r = self.context.schedule_horizon.read().await;
+            *r
+        };
+        let now = self.context.now().with_timezone(&timezone);
+        let schedule = cron_steps::check_cron(expr, &now, horizon)?;
+        Ok(schedule.after(&now).take(n).collect())
+    }
+
+    /// `next_tick_for_job` returns the date/time for when the next tick will
+    /// be for a job
+    pub async fn next_tick_for_job(
+        &mut self,
+        job_id: Uuid,
+    ) -> Result<Option<DateTime<Utc>>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let mut r = self.context.metadata_storage.write().await;
+        r.get(job_id)
+            .await
+            .map(|v| v.and_then(|vv| vv.next_tick_utc()))
+    }
+
+    ///
+    /// Let a one shot job fire again at `when`, keeping its UUID and notifications. A one shot
+    /// job is removed shortly after its run is done, so rearm it before that, for instance from
+    /// its own run. Returns `NotOneShot` for other jobs.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::{Job, JobSchedulerError};
+    /// # use chrono::Utc;
+    /// # async fn try_sync() -> Result<(), ()> {
+    /// #     Ok(())
+    /// # }
+    /// # fn example() -> Result<(), JobSchedulerError> {
+    /// let job = Job::new_one_shot_async(Duration::from_secs(10), |uuid, mut l| {
+    ///     Box::pin(async move {
+    ///         if try_sync().await.is_err() {
+    ///             let later = Utc::now() + chrono::Duration::minutes(5);
+    ///             l.rearm(&uuid, later).await.ok();
+    ///         }
+    ///     })
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn rearm(
+        &mut self,
+        job_id: &Uuid,
+        when: DateTime<Utc>,
+    ) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let mut w = self.context.metadata_storage.write().await;
+        let mut data = w
+            .get(*job_id)
+            .await?
+            .ok_or(JobSchedulerError::JobNotFound)?;
+        if data.job_type != JobType::OneShot as i32 {
+            return Err(JobSchedulerError::NotOneShot);
+        }
+        data.ran = false;
+        data.last_tick = None;
+        data.next_tick = when.timestamp() as u64;
+        w.add_or_update(data).await?;
+        self.changed(*job_id, JobChange::Schedule);
+        Ok(())
+    }
+
+    ///
+    /// Fire a job once more at `when`, on top of its schedule. Its schedule isn't changed, after
+    /// the extra fire the job goes on as before. The run is started like any other, so it keeps to
+    /// the job's overlap policy.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # use chrono::Utc;
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// let in_five_minutes = Utc::now() + chrono::Duration::minutes(5);
+    /// sched.schedule_one_off(&job_id, in_five_minutes).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn schedule_one_off(
+        &mut self,
+        job_id: &Uuid,
+        when: DateTime<Utc>,
+    ) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?;
+        }
+        let mut w = self.context.one_offs.write().await;
+        w.entry(*job_id).or_default().push(when);
+        Ok(())
+    }
+
+    ///
+    /// Fire a job `delay` after every run of another job that's done, None to fire it on its own
+    /// schedule again. While it's set the job's own schedule goes on without firing it, so
+    /// `time_till_next_job` leaves it out. Several jobs can be fired after the same job, and those
+    /// can have jobs fired after them in turn, as long as no job ends up fired after itself.
+    /// It's kept with the job in the metadata store, jobs made with `JobBuilder::with_after_job`
+    /// get it when they're added.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::{AfterJob, JobScheduler};
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, export_job: Uuid, report_job: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// let after = AfterJob { job_id: export_job, delay: Duration::from_secs(600) };
+    /// sched.set_after_job(&report_job, Some(after)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_after_job(
+        &self,
+        job_id: &Uuid,
+        after: Option<AfterJob>,
+    ) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let data = {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(*job_id).await?
+        };
+        let mut data = match (data, after.as_ref()) {
+            (Some(data), _) => data,
+            (None, Some(_)) => return Err(JobSchedulerError::JobNotFound),
+            (None, None) => {
+                let mut w = self.context.after_jobs.write().await;
+                w.remove(job_id);
+                return Ok(());
+            }
+        };
+        if let Some(after) = after.as_ref() {
+            self.check_after(job_id, data.job_type, after).await?;
+        }
+        {
+            let mut w = self.context.after_jobs.write().await;
+            match after {
+                Some(after) => {
+                    JobsSchedulerLocked::check_chain(&w, job_id, &after)?;
+                    w.insert(*job_id, after);
+                }
+                None => {
+                    w.remove(job_id);
+                }
+            }
+        }
+        // Kept with the job, so it's fired after the other job again when it's added again
+        data.after = after.map(Into::into);
+        let mut w = self.context.metadata_storage.write().await;
+        w.add_or_update(data).await
+    }
+
+    ///
+    /// Whether a job may be fired after `after.job_id`: it isn't a one shot and the other job is
+    /// in the scheduler
+    async fn check_after(
+        &self,
+        job_id: &Uuid,
+        job_type: i32,
+        after: &AfterJob,
+    ) -> Result<(), JobSchedulerError> {
+        // A one shot would be removed once its own fire is passed over
+        if job_type == JobType::OneShot as i32 {
+            return Err(JobSchedulerError::InvalidTrigger(format!(
+                "{} is a one shot",
+                job_id
+            )));
+        }
+        let mut r = self.context.metadata_storage.write().await;
+        r.get(after.job_id)
+            .await?
+            .ok_or(JobSchedulerError::JobNotFound)?;
+        Ok(())
+    }
+
+    ///
+    /// Whether firing a job after `after.job_id` leaves no job fired after itself
+    fn check_chain(
+        after_jobs: &HashMap<Uuid, AfterJob>,
+        job_id: &Uuid,
+        after: &AfterJob,
+    ) -> Result<(), JobSchedulerError> {
+        let mut chain = vec![*job_id];
+        let mut next = Some(after.job_id);
+        while let Some(id) = next {
+            chain.push(id);
+            if id == *job_id {
+                let chain = chain.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+                return Err(JobSchedulerError::InvalidTrigger(format!(
+                    "Fired after itself: {}",
+                    chain.join(" after ")
+                )));
+            }
+            next = after_jobs.get(&id).map(|after| after.job_id);
+        }
+        Ok(())
+    }
+
+    ///
+    /// Set the dates and times a job doesn't fire on, None to fire it whenever its schedule says
+    /// again. It's kept with the job in the metadata store, see `JobBuilder::with_calendar`.
+    /// Fires with `add_one_off` and the fires of `set_max_silence` go through whatever the
+    /// calendar says.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{Calendar, CalendarAction, JobScheduler};
+    /// # use uuid::Uuid;
+    /// # use chrono::NaiveDate;
+    /// # async fn example(mut sched: JobScheduler, billing_job: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// let calendar = Calendar::new()
+    ///     .exclude_date(NaiveDate::from_ymd_opt(2026, 12, 25).unwrap())
+    ///     .with_action(CalendarAction::Defer);
+    /// sched.set_calendar(&billing_job, Some(calendar)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_calendar(
+        &self,
+        job_id: &Uuid,
+        calendar: Option<Calendar>,
+    ) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let mut w = self.context.metadata_storage.write().await;
+        let mut data = w
+            .get(*job_id)
+            .await?
+            .ok_or(JobSchedulerError::JobNotFound)?;
+        data.exclusions = calendar.map(Into::into);
+        w.add_or_update(data).await?;
+        self.changed(*job_id, JobChange::Schedule);
+        Ok(())
+    }
+
+    ///
+    /// Set the dates and times none of the jobs fire on, on top of the jobs' own calendars, in
+    /// the time zone of each job. A fire both exclude is passed over or deferred as the job's
+    /// own calendar says. Unlike the jobs' calendars it's not kept in the metadata store.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{Calendar, JobScheduler};
+    /// # use chrono::NaiveTime;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// // Nothing runs during the nightly maintenance window
+    /// let maintenance = Calendar::new().exclude_window(
+    ///     NaiveTime::from_hms_opt(2, 0, 0).unwrap(),
+    ///     NaiveTime::from_hms_opt(4, 0, 0).unwrap(),
+    /// );
+    /// sched.set_global_calendar(Some(maintenance)).await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_global_calendar(&self, calendar: Option<Calendar>) {
+        {
+            let mut w = self.context.calendar.write().await;
+            *w = calendar;
+        }
+        let jobs = {
+            let mut r = self.context.metadata_storage.write().await;
+            r.list_jobs().await
+        };
+        match jobs {
+            Ok(jobs) => {
+                for job_id in jobs.iter().filter_map(|job| job.id.as_ref()) {
+                    self.changed(job_id.into(), JobChange::Schedule);
+                }
+            }
+            Err(e) => error!("Error listing the jobs the calendar applies to {:?}", e),
+        }
+    }
+
+    ///
+    /// Add a job that runs right away and, only when that run fails, again on its own schedule
+    /// until a run is done or `window` is over. The job is removed after the run that's done, or
+    /// after the last failed run when the next fire would be past the window. The complete
+    /// notifications only go out for that last run, with `JobState::Done` when it went well, the
+    /// failure handler still sees every failed run.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::{Job, JobScheduler};
+    /// # async fn upload() {}
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// // Try now, then every minute for up to an hour
+    /// let mut job = Job::new_async("0 * * * * *", |_uuid, _l| Box::pin(async move { upload().await }))?;
+    /// job.on_complete_notification_add(&sched, Box::new(|_job_id, _notification_id, state| {
+    ///     Box::pin(async move { println!("Upload ended with {:?}", state) })
+    /// })).await?;
+    /// sched.add_until_done(job, Duration::from_secs(3600)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn add_until_done(
+        &self,
+        mut job: JobLocked,
+        window: Duration,
+    ) -> Result<Uuid, JobSchedulerError> {
+        let job_id = job.guid();
+        if job.job_data()?.job_type == JobType::OneShot as i32 {
+            return Err(JobSchedulerError::InvalidTrigger(format!(
+                "{} is a one shot, it has no schedule to retry on",
+                job_id
+            )));
+        }
+        let now = self.context.now();
+        let until = chrono::Duration::from_std(window)
+            .ok()
+            .and_then(|window| now.checked_add_signed(window))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        {
+            let mut w = self.context.until_done.write().await;
+            w.insert(job_id, until);
+        }
+        if let Err(e) = self.add(job).await {
+            let mut w = self.context.until_done.write().await;
+            w.remove(&job_id);
+            return Err(e);
+        }
+        let mut w = self.context.one_offs.write().await;
+        w.entry(job_id).or_default().push(now);
+        Ok(job_id)
+    }
+
+    ///
+    /// Don't fire a job until `until`, after that it fires on its schedule again without having to
+    /// be resumed. The fires in between are passed over, not caught up on. Calling it again moves
+    /// the end of the pause, `resume` ends it early.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # use chrono::Utc;
+    /// # async fn example(mut sched: JobScheduler, alert_job: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// let in_two_hours = Utc::now() + chrono::Duration::hours(2);
+    /// sched.pause_until(&alert_job, in_two_hours).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn pause_until(
+        &mut self,
+        job_id: &Uuid,
+        until: DateTime<Utc>,
+    ) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?;
+        }
+        {
+            let mut w = self.context.paused_until.write().await;
+            w.insert(*job_id, until);
+        }
+        self.changed(*job_id, JobChange::Paused(until));
+        Ok(())
+    }
+
+    ///
+    /// Don't fire a job until it's resumed with `resume`, keeping it in the scheduler with its id
+    /// and notifications. The job is marked paused in the metadata store, so it stays paused
+    /// when its metadata is loaded again after a restart. Its schedule goes on, the fires in
+    /// between are passed over and not caught up on.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.pause(&job_id).await?;
+    /// // Later, maybe after a restart
+    /// sched.resume(&job_id).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn pause(&mut self, job_id: &Uuid) -> Result<(), JobSchedulerError> {
+        {
+            let mut w = self.context.metadata_storage.write().await;
+            let mut data = w
+                .get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?;
+            if data.paused {
+                return Ok(());
+            }
+            data.paused = true;
+            w.add_or_update(data).await?;
+        }
+        self.changed(*job_id, JobChange::Paused(DateTime::<Utc>::MAX_UTC));
+        Ok(())
+    }
+
+    ///
+    /// End the pause of a job set with `pause` or `pause_until`, so it fires again at its next
+    /// tick. A stopped job, like one stopped for going over its run budget, stays stopped.
+    pub async fn resume(&mut self, job_id: &Uuid) -> Result<(), JobSchedulerError> {
+        if self.end_pause(job_id).await? {
+            self.changed(*job_id, JobChange::Resumed);
+        }
+        Ok(())
+    }
+
+    ///
+    /// End the pause of a job, the one stored with it and the one set with `pause_until`.
+    /// Returns whether it was paused.
+    async fn end_pause(&self, job_id: &Uuid) -> Result<bool, JobSchedulerError> {
+        let stored = {
+            let mut w = self.context.metadata_storage.write().await;
+            let mut data = w
+                .get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?;
+            let paused = data.paused;
+            if paused {
+                data.paused = false;
+                w.add_or_update(data).await?;
+            }
+            paused
+        };
+        let until = {
+            let mut w = self.context.paused_until.write().await;
+            w.remove(job_id).is_some()
+        };
+        Ok(stored || until)
+    }
+
+    ///
+    /// The ids of the jobs with a tag
+    async fn tagged(&self, tag: &str) -> Result<Vec<Uuid>, JobSchedulerError> {
+        let jobs = {
+            let mut r = self.context.metadata_storage.write().await;
+            r.list_jobs().await?
+        };
+        Ok(jobs
+            .iter()
+            .filter(|data| data.tags.iter().any(|t| t == tag))
+            .filter_map(|data| data.id.as_ref().map(Uuid::from))
+            .collect())
+    }
+
+    ///
+    /// Pause every job with a tag until it's resumed, as `pause` does for one job. Returns the ids
+    /// of the paused jobs.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// let paused = sched.pause_by_tag("billing-api").await?;
+    /// // Once the billing API is back
+    /// sched.resume_by_tag("billing-api").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn pause_by_tag(&mut self, tag: &str) -> Result<Vec<Uuid>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let paused = self.tagged(tag).await?;
+        for job_id in paused.iter() {
+            self.pause(job_id).await?;
+        }
+        Ok(paused)
+    }
+
+    ///
+    /// End the pause of every paused job with a tag, as `resume` does for one job, whether it was
+    /// paused by `pause`, `pause_by_tag` or `pause_until`. Returns the ids of the resumed jobs.
+    pub async fn resume_by_tag(&mut self, tag: &str) -> Result<Vec<Uuid>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let mut resumed = vec![];
+        for job_id in self.tagged(tag).await? {
+            if self.end_pause(&job_id).await? {
+                self.changed(job_id, JobChange::Resumed);
+                resumed.push(job_id);
+            }
+        }
+        Ok(resumed)
+    }
+
+    ///
+    /// Remove every job with a tag, as `remove` does for one job. Returns the ids of the removed
+    /// jobs. Stops at the first job that can't be removed, the jobs before it stay removed.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// // The tenant left
+    /// let removed = sched.remove_by_tag("tenant:42").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn remove_by_tag(&mut self, tag: &str) -> Result<Vec<Uuid>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let removed = self.tagged(tag).await?;
+        for job_id in removed.iter() {
+            self.remove(job_id).await?;
+        }
+        Ok(removed)
+    }
+
+    ///
+    /// The parsed cron schedule of a job, for computing its fire times with `upcoming` or `after`.
+    /// Returns None for jobs that aren't cron jobs or don't exist.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # use chrono::Utc;
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// if let Some(schedule) = sched.schedule_of(&job_id).await? {
+    ///     let next_five: Vec<_> = schedule.upcoming(Utc).take(5).collect();
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn schedule_of(&self, job_id: &Uuid) -> Result<Option<Schedule>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let mut r = self.context.metadata_storage.write().await;
+        r.get(*job_id).await.map(|v| {
+            v.filter(|vv| vv.job_type == JobType::Cron as i32)
+                .and_then(|vv| vv.schedule())
+        })
+    }
+
+    ///
+    /// How many times a job would fire after `from` up to and including `to`. Cron jobs count the
+    /// fire times of their schedule, repeated jobs how many intervals fit in the range and one
+    /// shot jobs whether their instant is in the range.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # use chrono::Utc;
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// let from = Utc::now();
+    /// let fires = sched.fires_in_range(&job_id, from, from + chrono::Duration::days(30)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fires_in_range(
+        &self,
+        job_id: &Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<usize, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let job = {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?
+        };
+        if to < from {
+            return Ok(0);
+        }
+
+        let fires = match JobType::from_i32(job.job_type) {
+            Some(JobType::Cron) => {
+                let offset = FixedOffset::east_opt(job.time_offset_seconds)
+                    .unwrap_or(FixedOffset::east_opt(0).unwrap());
+                let jitter = chrono::Duration::seconds(job.jitter_seconds as i64);
+                job.schedule()
+                    .map(|s| {
+                        s.after(&(from.with_timezone(&offset) - jitter))
+                            .take_while(|t| *t + jitter <= to)
+                            .count()
+                    })
+                    .unwrap_or_default()
+            }
+            Some(JobType::Repeated) => match job.repeated_every() {
+                Some(every) if every > 0 => ((to - from).num_seconds() as u64 / every) as usize,
+                _ => 0,
+            },
+            Some(JobType::OneShot) => job
+                .next_tick_utc()
+                .map(|t| usize::from(from < t && t <= to))
+                .unwrap_or_default(),
+            None => 0,
+        };
+        Ok(fires)
+    }
+
+    ///
+    /// The jobs that take longer to run than the time from their next fire to the fire after, by
+    /// the average or by the last of their runs that finished, so their runs are bound to overlap
+    /// or be skipped. Jobs that haven't run yet, stopped jobs and jobs that fire only once are
+    /// left out.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// let overcommitted = sched.overcommitted_jobs().await?;
+    /// assert!(overcommitted.is_empty(), "Jobs that run longer than they're apart: {:?}", overcommitted);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn overcommitted_jobs(&self) -> Result<Vec<Uuid>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let jobs = {
+            let mut r = self.context.metadata_storage.write().await;
+            r.list_jobs().await?
+        };
+        let stats = {
+            let r = self.context.stats.read().await;
+            r.clone()
+        };
+        let mut overcommitted = jobs
+            .iter()
+            .filter(|data| data.next_tick != 0 && !data.stopped)
+            .filter_map(|data| {
+                let job_id: Uuid = data.id.as_ref()?.into();
+                let stats = stats.job(&job_id)?;
+                let took = stats.average_duration()?.max(stats.last_duration?);
+                let next = data.next_tick_utc()?;
+                let gap = (JobsSchedulerLocked::fire_after(data, next)? - next)
+                    .to_std()
+                    .ok()?;
+                (took > gap).then_some((data.order_key, job_id))
+            })
+            .collect::<Vec<_>>();
+        overcommitted.sort();
+        Ok(overcommitted
+            .into_iter()
+            .map(|(_, job_id)| job_id)
+            .collect())
+    }
+
+    ///
+    /// The longest time between two fires of a job in a row, out of the fires from now to
+    /// `horizon` from now, for deriving how long a job may go without running before it's
+    /// alerted on. The fire after the last one in the horizon counts too, so a gap that starts
+    /// in the horizon is taken whole. Repeated jobs give their interval. Jobs that fire at most
+    /// once give `JobSchedulerError::NoNextTick`.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// let max_gap = sched.max_gap(&job_id, Duration::from_secs(7 * 24 * 3600)).await?;
+    /// let alert_after = max_gap + Duration::from_secs(600);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn max_gap(
+        &self,
+        job_id: &Uuid,
+        horizon: Duration,
+    ) -> Result<Duration, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let job = {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?
+        };
+
+        let gap = match JobType::from_i32(job.job_type) {
+            Some(JobType::Cron) => {
+                let offset = FixedOffset::east_opt(job.time_offset_seconds)
+                    .unwrap_or(FixedOffset::east_opt(0).unwrap());
+                let now = self.context.now().with_timezone(&offset);
+                let to = chrono::Duration::from_std(horizon)
+                    .ok()
+                    .and_then(|horizon| now.checked_add_signed(horizon))
+                    .map_or(DateTime::<Utc>::MAX_UTC, |to| to.with_timezone(&Utc));
+                let schedule = job.schedule().ok_or(JobSchedulerError::ScheduleNotSet)?;
+                let mut fires = schedule.after(&now);
+                let mut last = fires.next();
+                let mut gap = None;
+                while let Some((previous, next)) = last.zip(fires.next()) {
+                    gap = gap.max(Some(next - previous));
+                    if next > to {
+                        break;
+                    }
+                    last = Some(next);
+                }
+                gap.and_then(|gap| gap.to_std().ok())
+            }
+            Some(JobType::Repeated) => job
+                .repeated_every()
+                .filter(|every| *every > 0)
+                .map(Duration::from_secs),
+            _ => None,
+        };
+        gap.ok_or(JobSchedulerError::NoNextTick)
+    }
+
+    ///
+    /// Call `f` with a snapshot of every job in the metadata store. The snapshots are taken in one
+    /// pass over the store, `f` is called after the store is released again. Jobs whose metadata
+    /// can't be read are skipped.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut ids = vec![];
+    /// sched.for_each_job(|info| ids.push(info.id)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn for_each_job<F: FnMut(JobInfo)>(&self, mut f: F) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+
+        let jobs = {
+            let mut r = self.context.metadata_storage.write().await;
+            r.list_jobs().await?
+        };
+        for info in jobs.iter().filter_map(JobInfo::from_stored) {
+            f(info);
+        }
+        Ok(())
+    }
+
+    ///
+    /// Every job with its next `upcoming` fires, whether it's running or paused and how its last
+    /// run went, for status pages. The fires are those of the job's schedule as described by the
+    /// metadata store, see `effective_upcoming` for pauses and one off fires taken into account.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// for job in sched.list_jobs(3).await? {
+    ///     println!("{} {:?} next {:?} last {:?}", job.description.info.id,
+    ///         job.description.info.schedule, job.description.upcoming, job.last_run);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_jobs(&self, upcoming: usize) -> Result<Vec<JobStatus>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+
+        let descriptions = {
+            let mut r = self.context.metadata_storage.write().await;
+            r.describe(upcoming).await?
+        };
+        let now = self.context.now();
+        let paused_until = self.context.paused_until.read().await;
+        let stats = self.context.stats.read().await;
+        Ok(descriptions
+            .into_iter()
+            .map(|description| {
+                let id = description.info.id;
+                let job = stats.job(&id);
+                let finished = job.filter(|j| j.runs > j.running as u64);
+                JobStatus {
+                    running: job.is_some_and(|j| j.running > 0),
+                    paused: description.paused
+                        || paused_until.get(&id).is_some_and(|until| *until > now),
+                    last_run: finished.map(|j| {
+                        if j.last_failed {
+                            JobState::Failed
+                        } else {
+                            JobState::Done
+                        }
+                    }),
+                    last_duration: finished.and_then(|j| j.last_duration),
+                    description,
+                }
+            })
+            .collect())
+    }
+
+    ///
+    /// Watch how many jobs the scheduler has, the receiver starts with the current count and sees
+    /// it change when jobs are added or removed, also when a one shot job is removed after its run.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut count = sched.job_count_watch().await;
+    /// while count.changed().await.is_ok() {
+    ///     println!("{} jobs", *count.borrow());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn job_count_watch(&self) -> tokio::sync::watch::Receiver<usize> {
+        let rx = self.context.job_count.subscribe();
+        self.context.update_job_count().await;
+        rx
+    }
+
+    ///
+    /// The schedule of every job as text, for dumping the configuration. Cron jobs give their
+    /// cron expression as it was given, repeated jobs `@every 30s` and one shot jobs `one-shot`.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// for (job_id, schedule) in sched.schedules().await? {
+    ///     println!("{} {}", job_id, schedule);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn schedules(&self) -> Result<Vec<(Uuid, String)>, JobSchedulerError> {
+        let mut schedules = vec![];
+        self.for_each_job(|info| {
+            let schedule = match info.job_type {
+                JobType::Cron => info.schedule.unwrap_or_default(),
+                JobType::Repeated => format!("@every {}s", info.repeated_every.unwrap_or_default()),
+                JobType::OneShot => "one-shot".to_string(),
+            };
+            schedules.push((info.id, schedule));
+        })
+        .await?;
+        Ok(schedules)
+    }
+
+    ///
+    /// Keep only the jobs for which `keep` returns true, like `Vec::retain`. The other jobs are
+    /// removed the same way `remove` does, once each. Jobs whose metadata can't be read are kept.
+    /// Returns how many jobs were removed.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{JobScheduler, JobType};
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.retain(|info| info.job_type == JobType::Cron).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn retain<F: Fn(&JobInfo) -> bool>(
+        &mut self,
+        keep: F,
+    ) -> Result<usize, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+
+        let jobs = {
+            let mut r = self.context.metadata_storage.write().await;
+            r.list_jobs().await?
+        };
+        let to_be_removed = jobs
+            .iter()
+            .filter_map(JobInfo::from_stored)
+            .filter(|info| !keep(info))
+            .map(|info| info.id)
+            .collect::<Vec<_>>();
+        for job_id in to_be_removed.iter() {
+            JobDeleter::remove(&self.context, job_id).await?;
+        }
+        Ok(to_be_removed.len())
+    }
+
+    ///
+    /// Move all cron jobs to another timezone. Their next ticks are computed again in the new
+    /// timezone, so a job at `0 0 12 * * *` runs at noon in that timezone. Repeated and one shot
+    /// jobs don't depend on a timezone and are left as they are. With the `timezone` feature a
+    /// `chrono_tz::Tz` is followed through daylight saving time, other timezones are kept as the
+    /// offset they have now.
+    pub async fn set_timezone<TZ: TimeZone + 'static>(
+        &self,
+        timezone: TZ,
+    ) -> Result<(), JobSchedulerError> {
+        let named = named_timezone(&timezone);
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+
+        let offset = timezone
+            .offset_from_utc_datetime(&self.context.now().naive_utc())
+            .fix();
+        {
+            let mut w = self.context.timezone.write().await;
+            *w = Some(offset);
+        }
+        let mut w = self.context.metadata_storage.write().await;
+        let jobs = w.list_jobs().await?;
+        for mut job in jobs {
+            if job.job_type != i32::from(JobType::Cron) {
+                continue;
+            }
+            if job.schedule().is_none() {
+                continue;
+            }
+            job.time_offset_seconds = offset.local_minus_utc();
+            job.timezone = named.clone();
+            let next_tick = job.next_cron_fire(&self.context.now().with_timezone(&offset));
+            job.set_next_tick(next_tick.map(|t| t.with_timezone(&Utc)));
+            let job_id = job.id.as_ref().map(Uuid::from);
+            w.add_or_update(job).await?;
+            if let Some(job_id) = job_id {
+                self.changed(job_id, JobChange::Schedule);
+            }
+        }
+        // The loop may be sleeping towards a next tick that's not there any more
+        self.context.wake.notify_one();
+        Ok(())
+    }
+
+    ///
+    /// Work out the next fire of every job again from now, for when the stored next ticks are
+    /// thought to be wrong, for instance after the clock jumped. Cron jobs get their first fire
+    /// after now, repeated jobs whose next tick has passed or is more than one interval away get
+    /// one interval after now. Fires that are due but weren't sent yet are moved on too. One shot
+    /// jobs are left as they are. It's safe to call while the scheduler runs and calling it twice
+    /// changes nothing more.
+    /// Returns how many jobs got a different next fire.
+    pub async fn refresh_schedules(&mut self) -> Result<usize, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let now = self.context.now();
+        let mut w = self.context.metadata_storage.write().await;
+        let jobs = w.list_jobs().await?;
+        let mut changed = 0;
+        for mut job in jobs {
+            if job.next_tick == 0 {
+                continue;
+            }
+            let offset = FixedOffset::east_opt(job.time_offset_seconds)
+                .unwrap_or(FixedOffset::east_opt(0).unwrap());
+            let next_tick = match JobType::from_i32(job.job_type) {
+                // A next tick already moved by the random jitter is kept
+                Some(JobType::Cron) => job
+                    .next_cron_tick(&now.with_timezone(&offset))
+                    .map(|t| t.with_timezone(&Utc))
+                    .map(|next| {
+                        let window = chrono::Duration::seconds(job.random_jitter_seconds as i64);
+                        job.next_tick_utc()
+                            .filter(|next_tick| *next_tick >= next && *next_tick < next + window)
+                            .unwrap_or_else(|| next + job.random_delay())
+                    }),
+                Some(JobType::Repeated) if job.interval_bounds.is_some() => {
+                    job.next_interval_fire(now)
+                }
+                Some(JobType::Repeated) => match job.repeated_every() {
+                    Some(every) => {
+                        let next = now + chrono::Duration::seconds(every as i64);
+                        job.next_tick_utc()
+                            .filter(|next_tick| *next_tick > now && *next_tick <= next)
+                            .or(Some(next))
+                    }
+                    None => continue,
+                },
+                _ => continue,
+            };
+            if next_tick == job.next_tick_utc() {
+                continue;
+            }
+            job.set_next_tick(next_tick);
+            let job_id = job.id.as_ref().map(Uuid::from);
+            w.add_or_update(job).await?;
+            if let Some(job_id) = job_id {
+                self.changed(job_id, JobChange::Schedule);
+            }
+            changed += 1;
+        }
+        if changed > 0 {
+            self.context.wake.notify_one();
+        }
+        Ok(changed)
+    }
+
+    ///
+    /// Shut the scheduler down. Removals of finished one-shot jobs that are still in flight are
+    /// awaited first, so their removal notifications have run by the time this returns, unless
+    /// set otherwise with `set_removals_on_shutdown`.
+    /// The shutdown handler runs on its own task, if it panics the panic is logged and the
+    /// shutdown still completes.
+    /// Jobs watching `shutdown_watch` see it go to true first. Running jobs aren't waited for,
+    /// use `shutdown_graceful` for that.
+    /// In order, a shutdown runs the pre shutdown hook set with `set_pre_shutdown_hook`, tells
+    /// the jobs through `shutdown_watch`, stops the scheduler loop and then runs the shutdown
+    /// handler. Runs still going are left to finish on their own.
+    pub async fn shutdown(&mut self) -> Result<(), JobSchedulerError> {
+        self.run_pre_shutdown_hook().await;
+        self.context.shutting_down.send_replace(true);
+        let notify = self
+            .context
+            .shutdown_handler
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+
+        let mut scheduler = self.scheduler.write().await;
+        scheduler.shutdown().await;
+
+        JobsSchedulerLocked::run_shutdown_notification(notify, "shutdown handler").await;
+        Ok(())
+    }
+
+    async fn run_pre_shutdown_hook(&mut self) {
+        let hook = self
+            .context
+            .pre_shutdown_hook
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        JobsSchedulerLocked::run_shutdown_notification(hook, "pre shutdown hook").await;
+    }
+
+    async fn run_shutdown_notification(notify: Option<SharedShutdownNotification>, what: &str) {
+        if let Some(notify) = notify {
+            let ran = tokio::spawn(async move {
+                let mut notify = notify.write().await;
+                notify().await;
+            })
+            .await;
+            if let Err(e) = ran {
+                error!("Error running the {} {:?}", what, e);
+            }
+        }
+    }
+
+    ///
+    /// Start the scheduler, let it fire jobs for `duration` by its clock and shut it down with
+    /// `shutdown_graceful`, giving the runs still going up to `grace` to finish. Returns how many
+    /// runs were still going after that.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// let still_running = sched
+    ///     .run_for(Duration::from_secs(5), Duration::from_secs(1))
+    ///     .await?;
+    /// assert_eq!(still_running, 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn run_for(
+        &mut self,
+        duration: Duration,
+        grace: Duration,
+    ) -> Result<usize, JobSchedulerError> {
+        self.start().await?;
+        self.context.clock().sleep(duration).await;
+        self.shutdown_graceful(grace).await
+    }
+
+    ///
+    /// Stop firing jobs, tell the running jobs the scheduler shuts down through `shutdown_watch`,
+    /// give them up to `timeout` to finish and shut down, running the shutdown handler last.
+    /// Returns how many runs were still going after the timeout, those aren't aborted. Fires
+    /// that come due while waiting aren't run, a persistent store keeps them for the job's
+    /// `MisfirePolicy` after a restart.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use tracing::warn;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// let still_running = sched.shutdown_graceful(Duration::from_secs(10)).await?;
+    /// if still_running > 0 {
+    ///     warn!("{} jobs didn't stop in time", still_running);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn shutdown_graceful(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<usize, JobSchedulerError> {
+        self.run_pre_shutdown_hook().await;
+        self.context.shutting_down.send_replace(true);
+        {
+            let scheduler = self.scheduler.read().await;
+            scheduler.stop_ticking().await;
+        }
+        let deadline = tokio::time::Instant::now() + timeout;
+        let running = loop {
+            let running = {
+                let r = self.context.stats.read().await;
+                r.running
+            };
+            if running == 0 || tokio::time::Instant::now() >= deadline {
+                break running;
+            }
+            tokio::time::sleep_until(
+                deadline.min(tokio::time::Instant::now() + Duration::from_millis(10)),
+            )
+            .await;
+        };
+        self.shutdown().await?;
+        Ok(running)
+    }
+
+    ///
+    /// Watch whether the scheduler shuts down, for jobs that run long and want to stop cleanly
+    /// instead of being cut off. The receiver turns to true once `shutdown` or
+    /// `shutdown_graceful` is called. Jobs get it through the scheduler they're given.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{Job, JobSchedulerError};
+    /// # fn work() -> Vec<std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>> {
+    /// #     vec![]
+    /// # }
+    /// # fn example() -> Result<(), JobSchedulerError> {
+    /// let job = Job::new_async("0 * * * * *", |_uuid, l| {
+    ///     Box::pin(async move {
+    ///         let mut stop = l.shutdown_watch();
+    ///         for chunk in work() {
+    ///             if *stop.borrow_and_update() {
+    ///                 return;
+    ///             }
+    ///             chunk.await;
+    ///         }
+    ///     })
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn shutdown_watch(&self) -> tokio::sync::watch::Receiver<bool> {
+        self.context.shutting_down.subscribe()
+    }
+
+    ///
+    /// Watch whether runs are going, it turns to `SchedulerActivity::Busy` when the first run
+    /// starts and back to `SchedulerActivity::Idle` when the last run that was going is done,
+    /// without waking the watchers for the runs in between. How long it has to stay idle before
+    /// it counts is up to the watcher.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{JobScheduler, SchedulerActivity};
+    /// # use std::time::Instant;
+    /// # struct Autoscaler;
+    /// # impl Autoscaler {
+    /// #     fn idle_since(&self, _at: Instant) {}
+    /// # }
+    /// # async fn example(mut sched: JobScheduler, autoscaler: Autoscaler) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut activity = sched.activity_watch();
+    /// while activity.changed().await.is_ok() {
+    ///     if *activity.borrow_and_update() == SchedulerActivity::Idle {
+    ///         autoscaler.idle_since(Instant::now());
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn activity_watch(&self) -> tokio::sync::watch::Receiver<SchedulerActivity> {
+        self.context.activity.subscribe()
+    }
+
+    ///
+    /// Every job's events from now on, as the notifications go out, with the name of the job and
+    /// the error of a run that failed, for handling them in one place instead of adding
+    /// notifications to every job. Jobs that are removed give a `Removed` event.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use tracing::info;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut events = sched.event_stream();
+    /// while let Some(event) = events.next().await {
+    ///     info!(job = %event.name, state = ?event.state, error = ?event.error, "job event");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn event_stream(&self) -> EventStream {
+        EventStream::new(self.context.events_tx.subscribe())
+    }
+
+    ///
+    /// Whether `shutdown` or `shutdown_graceful` was called
+    pub fn is_shutting_down(&self) -> bool {
+        *self.context.shutting_down.borrow()
+    }
+
+    ///
+    /// Set whether `shutdown` waits for, aborts or ignores the removals of finished one shot jobs
+    /// that are still in flight. It waits for them by default.
+    pub async fn set_removals_on_shutdown(&self, policy: RemovalsOnShutdown) {
+        let mut w = self.scheduler.write().await;
+        w.removals_on_shutdown = policy;
+    }
+
+    ///
+    /// Set whether the scheduler starts with a clean slate: fires that were due before `start`,
+    /// such as the ones missed while the process was down, are passed over instead of caught up
+    /// on, whatever the jobs would do otherwise. The jobs go on with their next fire after that.
+    /// One off fires are left alone. Off by default, it has to be set before `start`.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.set_start_clean(true).await;
+    /// sched.start().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_start_clean(&self, start_clean: bool) {
+        let r = self.scheduler.read().await;
+        let mut w = r.start_clean.write().await;
+        *w = start_clean;
+    }
+
+    ///
+    /// How many removals of finished one shot jobs are still in flight
+    pub async fn pending_removals(&self) -> usize {
+        let r = self.scheduler.read().await;
+        r.pending_removals().await
+    }
+
+    ///
+    /// Remove the one shot jobs that fired and whose run is done, and the jobs whose schedule
+    /// has no fire left, right away instead of on a later pass of the scheduler loop. Waits for
+    /// the removals, also the ones that were in flight already, so their removal notifications
+    /// have run when it returns. Returns the ids of the removed jobs.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.reap_finished().await?;
+    /// let state = sched.export_state().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn reap_finished(&mut self) -> Result<Vec<Uuid>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        // Listening before looking, so no removal is missed that's done in between
+        let mut deleted = self.context.job_deleted_tx.subscribe();
+        let next_ticks = {
+            let now = self.context.now();
+            let mut w = self.context.metadata_storage.write().await;
+            w.list_due_ticks(now).await?
+        };
+        let mut finished = {
+            let stats = self.context.stats.read().await;
+            next_ticks
+                .iter()
+                .filter(|n| n.next_tick == 0)
+                .filter_map(|n| n.id.as_ref().map(Uuid::from))
+                .filter(|id| !stats.job(id).map(|j| j.running > 0).unwrap_or_default())
+                .collect::<HashSet<_>>()
+        };
+        if finished.is_empty() {
+            return Ok(vec![]);
+        }
+        {
+            let pending_removals = self.scheduler.read().await.pending_removals.clone();
+            let mut pending = pending_removals.write().await;
+            for uuid in finished.iter().copied() {
+                if pending
+                    .get(&uuid)
+                    .is_some_and(|handle| !handle.is_finished())
+                {
+                    continue;
+                }
+                let context = self.context.clone();
+                let handle = tokio::spawn(async move {
+                    if let Err(e) = JobDeleter::remove(&context, &uuid).await {
+                        error!("Error removing job {:?} {:?}", uuid, e);
+                    }
+                });
+                pending.insert(uuid, handle);
+            }
+        }
+
+        let mut reaped = vec![];
+        while !finished.is_empty() {
+            match deleted.recv().await {
+                Ok(Ok(uuid)) => {
+                    if finished.remove(&uuid) {
+                        reaped.push(uuid);
+                    }
+                }
+                Ok(Err((e, Some(uuid)))) => {
+                    if finished.remove(&uuid) {
+                        error!("Error reaping {:?} {:?}", uuid, e);
+                    }
+                }
+                Ok(Err((_, None))) => {}
+                Err(RecvError::Lagged(_)) => {
+                    // Removals may have been missed, the jobs that are gone were removed
+                    let mut r = self.context.metadata_storage.write().await;
+                    let mut gone = vec![];
+                    for uuid in finished.iter().copied() {
+                        if r.get(uuid).await?.is_none() {
+                            gone.push(uuid);
+                        }
+                    }
+                    for uuid in gone {
+                        finished.remove(&uuid);
+                        reaped.push(uuid);
+                    }
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+        Ok(reaped)
+    }
+
+    ///
+    /// Shut down as `shutdown` does, or as `shutdown_graceful` does when a grace is set with
+    /// `set_shutdown_grace`, for the shutdowns on a signal or a token
+    #[cfg(any(feature = "signal", feature = "cancellation_token"))]
+    async fn shutdown_on_trigger(&mut self, trigger: &str) {
+        let grace = {
+            let r = self.context.shutdown_grace.read().await;
+            *r
+        };
+        info!("Shutting down on {}", trigger);
+        let shut_down = match grace {
+            Some(grace) => self.shutdown_graceful(grace).await.map(|still_running| {
+                if still_running > 0 {
+                    warn!("{} runs were still going at the shutdown", still_running);
+                }
+            }),
+            None => self.shutdown().await,
+        };
+        if let Err(e) = shut_down {
+            error!("Error shutting down on {} {:?}", trigger, e);
+        }
+    }
+
+    ///
+    /// How long the shutdowns on a signal or a token, like `shutdown_on_signals`, give the
+    /// running jobs to finish as `shutdown_graceful` does. None, the default, doesn't wait for
+    /// them.
+    pub async fn set_shutdown_grace(&self, grace: Option<Duration>) {
+        let mut w = self.context.shutdown_grace.write().await;
+        *w = grace;
+    }
+
+    ///
+    /// Wait for a signal to shut the runtime down with
+    #[cfg(all(unix, feature = "signal"))]
+    pub fn shutdown_on_signal(&self, signal: SignalKind) {
+        let mut l = self.clone();
+        tokio::spawn(async move {
+            if let Some(_k) = tokio::signal::unix::signal(signal)
+                .expect("Can't wait for signal")
+                .recv()
+                .await
+            {
+                l.shutdown_on_trigger(&format!("{:?}", signal)).await;
+            }
+        });
+    }
+
+    ///
+    /// Wait for a signal to shut the runtime down with
+    #[cfg(feature = "signal")]
+    pub fn shutdown_on_ctrl_c(&self) {
+        let mut l = self.clone();
+        tokio::spawn(async move {
+            tokio::signal::ctrl_c()
+                .await
+                .expect("Could not await ctrl-c");
+
+            l.shutdown_on_trigger("ctrl-c").await;
+        });
+    }
+
+    ///
+    /// Shut down on SIGINT or SIGTERM on unix and on ctrl-c elsewhere, for services stopped by
+    /// their supervisor. The shutdown handler and pre shutdown hook that are set by the time the
+    /// signal comes are run. The running jobs are waited for when a grace is set with
+    /// `set_shutdown_grace`.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn flush() {}
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.set_shutdown_handler(Box::new(|| Box::pin(async move { flush().await })));
+    /// sched.set_shutdown_grace(Some(Duration::from_secs(10))).await;
+    /// sched.shutdown_on_signals();
+    /// sched.start().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "signal")]
+    pub fn shutdown_on_signals(&self) {
+        let mut l = self.clone();
+        tokio::spawn(async move {
+            #[cfg(unix)]
+            let signal = {
+                let mut interrupt = tokio::signal::unix::signal(SignalKind::interrupt())
+                    .expect("Can't wait for SIGINT");
+                let mut terminate = tokio::signal::unix::signal(SignalKind::terminate())
+                    .expect("Can't wait for SIGTERM");
+                std::future::poll_fn(|cx| {
+                    if interrupt.poll_recv(cx).is_ready() {
+                        std::task::Poll::Ready("SIGINT")
+                    } else if terminate.poll_recv(cx).is_ready() {
+                        std::task::Poll::Ready("SIGTERM")
+                    } else {
+                        std::task::Poll::Pending
+                    }
+                })
+                .await
+            };
+            #[cfg(not(unix))]
+            let signal = {
+                tokio::signal::ctrl_c()
+                    .await
+                    .expect("Could not await ctrl-c");
+                "ctrl-c"
+            };
+            l.shutdown_on_trigger(signal).await;
+        });
+    }
+
+    ///
+    /// Shut down once `token` is cancelled, so the scheduler stops along with the rest of an
+    /// application that shuts down through a `CancellationToken`. The shutdown handler and pre
+    /// shutdown hook that are set by the time the token is cancelled are run. The running jobs
+    /// are waited for when a grace is set with `set_shutdown_grace`.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use tokio_util::sync::CancellationToken;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// let token = CancellationToken::new();
+    /// sched.shutdown_on_token(token.child_token());
+    /// // Somewhere else
+    /// token.cancel();
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "cancellation_token")]
+    pub fn shutdown_on_token(&self, token: tokio_util::sync::CancellationToken) {
+        let mut l = self.clone();
+        tokio::spawn(async move {
+            token.cancelled().await;
+            l.shutdown_on_trigger("cancellation").await;
+        });
+    }
+
+    ///
+    /// Code that is run after the shutdown was run
+    pub fn set_shutdown_handler(&mut self, job: Box<ShutdownNotification>) {
+        let mut w = self
+            .context
+            .shutdown_handler
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = Some(Arc::new(RwLock::new(job)));
+    }
+
+    ///
+    /// Remove the shutdown handler
+    pub fn remove_shutdown_handler(&mut self) {
+        let mut w = self
+            .context
+            .shutdown_handler
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = None;
+    }
+
+    ///
+    /// Code that is run when a shutdown starts, before the jobs are told about it and before the
+    /// scheduler loop stops, so the shutdown waits for it. Where the shutdown handler runs after
+    /// everything is torn down, this can hold the shutdown off, like until a job that mustn't be
+    /// cut off is done. The timeout of `shutdown_graceful` starts after it.
+    ///
+    /// ```rust,no_run
+    /// # use std::sync::Arc;
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use tokio::sync::Notify;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// let settled = Arc::new(Notify::new());
+    /// let waiting = settled.clone();
+    /// sched.set_pre_shutdown_hook(Box::new(move || {
+    ///     let waiting = waiting.clone();
+    ///     Box::pin(async move { waiting.notified().await })
+    /// }));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_pre_shutdown_hook(&mut self, hook: Box<ShutdownNotification>) {
+        let mut w = self
+            .context
+            .pre_shutdown_hook
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = Some(Arc::new(RwLock::new(hook)));
+    }
+
+    ///
+    /// Remove the pre shutdown hook
+    pub fn remove_pre_shutdown_hook(&mut self) {
+        let mut w = self
+            .context
+            .pre_shutdown_hook
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = None;
+    }
+
+    ///
+    /// Code that is run when a job fails, for instance when it panics. This is separate from the
+    /// errors of the scheduler itself, which are logged.
+    pub async fn set_job_failure_handler(&self, handler: Box<JobFailureHandler>) {
+        let mut w = self.context.job_failure_handler.write().await;
+        *w = Some(handler);
+    }
+
+    ///
+    /// Remove the job failure handler
+    pub async fn remove_job_failure_handler(&self) {
+        let mut w = self.context.job_failure_handler.write().await;
+        *w = None;
+    }
+
+    ///
+    /// Limit how long the runs of a job may take all together in wall clock time, see
+    /// `RunBudget`. Once they took longer, the budget's `on_exceeded` is applied and reported to
+    /// the job failure handler, once.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::{JobScheduler, OnBudgetExceeded, RunBudget};
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.set_run_budget(&job_id, RunBudget {
+    ///     wallclock: Duration::from_secs(60 * 60),
+    ///     on_exceeded: OnBudgetExceeded::Disable,
+    /// }).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_run_budget(
+        &self,
+        job_id: &Uuid,
+        budget: RunBudget,
+    ) -> Result<(), JobSchedulerError> {
+        {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?;
+        }
+        let mut w = self.context.budgets.write().await;
+        w.insert(*job_id, budget);
+        Ok(())
+    }
+
+    ///
+    /// Run a job again after a failed run, as `policy` says, or None to stop retrying it. A
+    /// failed attempt that's retried sends `JobState::Retrying` instead of `JobState::Failed`,
+    /// the failure handler sees every failed attempt, with its number.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::{Backoff, JobScheduler, RetryPolicy};
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.set_retry_policy(&job_id, Some(RetryPolicy {
+    ///     max_attempts: 3,
+    ///     backoff: Backoff::Fixed(Duration::from_secs(10)),
+    ///     jitter: Duration::ZERO,
+    /// })).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_retry_policy(
+        &self,
+        job_id: &Uuid,
+        policy: Option<RetryPolicy>,
+    ) -> Result<(), JobSchedulerError> {
+        {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?;
+        }
+        let mut w = self.context.retry_policies.write().await;
+        match policy {
+            Some(policy) => {
+                w.insert(*job_id, (policy, 0));
+            }
+            None => {
+                w.remove(job_id);
+            }
+        }
+        Ok(())
+    }
 
     ///
-    /// Wait for a signal to shut the runtime down with
-    #[cfg(feature = "signal")]
-    pub fn shutdown_on_ctrl_c(&self) {
-        let mut l = self.clone();
-        tokio::spawn(async move {
-            tokio::signal::ctrl_c()
-                .await
-                .expect("Could not await ctrl-c");
+    /// How late after its scheduled time a job may fire, None for no limit. A job firing later is
+    /// logged and passed to the handler set with `set_on_late`, which shows the scheduler is
+    /// overloaded or blocked. Due jobs are fired from a pass of the scheduler loop, which wakes
+    /// when the next job is due, so a little late is normal.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.set_max_lateness(&job_id, Some(Duration::from_secs(5))).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_max_lateness(
+        &self,
+        job_id: &Uuid,
+        max_lateness: Option<Duration>,
+    ) -> Result<(), JobSchedulerError> {
+        {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?;
+        }
+        let mut w = self.context.max_lateness.write().await;
+        match max_lateness {
+            Some(max_lateness) => w.insert(*job_id, max_lateness),
+            None => w.remove(job_id),
+        };
+        Ok(())
+    }
+
+    ///
+    /// How long a job may go without a run before it's fired anyway, None to only fire it on its
+    /// schedule. A safety net for a job whose fires keep being skipped or passed over, for instance
+    /// by `set_start_clean` or a schedule that rarely fires. The time is counted from the start of
+    /// its last run, or from now when it didn't run since. Paused jobs, and jobs while the
+    /// scheduler isn't the leader or is backpressured, aren't fired.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.set_max_silence(&job_id, Some(Duration::from_secs(6 * 3600))).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_max_silence(
+        &self,
+        job_id: &Uuid,
+        max_silence: Option<Duration>,
+    ) -> Result<(), JobSchedulerError> {
+        {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?;
+        }
+        let mut w = self.context.max_silence.write().await;
+        match max_silence {
+            Some(max_silence) => {
+                let now = self.context.now();
+                let last_run = w.get(job_id).map_or(now, |(_, last_run)| *last_run);
+                w.insert(*job_id, (max_silence, last_run));
+            }
+            None => {
+                w.remove(job_id);
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// How long after its scheduled time a job fired the last time, None when it didn't fire yet
+    pub async fn last_lateness(&self, job_id: &Uuid) -> Option<Duration> {
+        let r = self.context.stats.read().await;
+        r.job(job_id).and_then(|j| j.last_lateness)
+    }
+
+    ///
+    /// The level the scheduler logs the runs of a job at: when they start, finish and fail. None
+    /// for the default, where only failed runs are logged, as errors. What the job logs itself
+    /// isn't changed.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # use tracing::Level;
+    /// # async fn example(mut sched: JobScheduler, heartbeat_job: Uuid, billing_job: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.set_log_level(&heartbeat_job, Some(Level::TRACE)).await?;
+    /// sched.set_log_level(&billing_job, Some(Level::INFO)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_log_level(
+        &self,
+        job_id: &Uuid,
+        level: Option<Level>,
+    ) -> Result<(), JobSchedulerError> {
+        {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?;
+        }
+        let mut w = self.context.log_levels.write().await;
+        match level {
+            Some(level) => w.insert(*job_id, level),
+            None => w.remove(job_id),
+        };
+        Ok(())
+    }
+
+    ///
+    /// Code that is run when a job fired later than set with `set_max_lateness`, with the id of
+    /// the job and how late it fired. It's called from the scheduler loop, so it should return
+    /// quickly.
+    pub fn set_on_late(&mut self, on_late: Box<LateHandler>) {
+        let mut w = self
+            .context
+            .late_handler
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = Some(on_late);
+    }
+
+    ///
+    /// Remove the code that is run when a job fired too late
+    pub fn remove_on_late(&mut self) {
+        let mut w = self
+            .context
+            .late_handler
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = None;
+    }
+
+    ///
+    /// Skip the runs of a job after a run of it failed, until `reset_failures` is called for it.
+    /// The skipped runs are logged and counted in the job's `skipped` stats.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.set_skip_after_failure(&job_id, true).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_skip_after_failure(
+        &self,
+        job_id: &Uuid,
+        skip: bool,
+    ) -> Result<(), JobSchedulerError> {
+        {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?;
+        }
+        let mut w = self.context.skip_after_failure.write().await;
+        if skip {
+            w.insert(*job_id);
+        } else {
+            w.remove(job_id);
+        }
+        Ok(())
+    }
+
+    ///
+    /// Run a job on a task shared by the inline jobs instead of spawning a task for every fire, to
+    /// save the spawns for jobs that do very little, like bumping a counter. Meant for such jobs
+    /// only: the inline jobs run one after another, so a slow inline job holds up the fires of
+    /// all other inline jobs, and so does an inline job waiting on a queue, a concurrency limit or
+    /// a tag gap. A panic is still caught and reported as a failed run.
+    ///
+    /// ```rust,no_run
+    /// # use std::sync::Arc;
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::{Job, JobScheduler};
+    /// # use std::sync::atomic::{AtomicUsize, Ordering};
+    /// # async fn example(mut sched: JobScheduler, hits: Arc<AtomicUsize>) -> Result<(), Box<dyn std::error::Error>> {
+    /// let hits = hits.clone();
+    /// let job_id = sched.add(Job::new_repeated(Duration::from_secs(1), move |_uuid, _l| {
+    ///     hits.fetch_add(1, Ordering::Relaxed);
+    /// })?).await?;
+    /// sched.set_inline(&job_id, true).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_inline(&self, job_id: &Uuid, inline: bool) -> Result<(), JobSchedulerError> {
+        {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?;
+        }
+        let mut w = self.context.inline.write().await;
+        if inline {
+            w.insert(*job_id);
+        } else {
+            w.remove(job_id);
+        }
+        Ok(())
+    }
+
+    ///
+    /// Let a callback give the schedule of a cron job, for a cadence that's set somewhere else
+    /// and changes now and then. The callback is asked right away and then after each fire, the
+    /// schedule it gives is stored with the job, so it's what the next fire is worked out with
+    /// until the fire after. None goes back to the schedule that's stored, which is the one the
+    /// callback gave last. Only cron jobs have a schedule to give, for other jobs this is an
+    /// `InvalidTrigger`.
+    ///
+    /// ```rust,no_run
+    /// # use std::sync::Arc;
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # use cron::Schedule;
+    /// # use std::str::FromStr;
+    /// # struct Config {
+    /// #     report_cron: String,
+    /// # }
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid, config: Arc<std::sync::RwLock<Config>>) -> Result<(), Box<dyn std::error::Error>> {
+    /// let config = config.clone();
+    /// sched.set_schedule_provider(&job_id, Some(Box::new(move || {
+    ///     Schedule::from_str(&config.read().unwrap().report_cron).unwrap()
+    /// }))).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_schedule_provider(
+        &self,
+        job_id: &Uuid,
+        provider: Option<Box<ScheduleProvider>>,
+    ) -> Result<(), JobSchedulerError> {
+        let mut w = self.context.schedule_providers.write().await;
+        let provider = match provider {
+            Some(provider) => provider,
+            None => {
+                w.remove(job_id);
+                return Ok(());
+            }
+        };
+        let mut s = self.context.metadata_storage.write().await;
+        let mut job = s
+            .get(*job_id)
+            .await?
+            .ok_or(JobSchedulerError::JobNotFound)?;
+        if job.job_type != JobType::Cron as i32 {
+            return Err(JobSchedulerError::InvalidTrigger(format!(
+                "{} isn't a cron job, it has no schedule to give",
+                job_id
+            )));
+        }
+        if job.set_schedule(&(provider)()) {
+            // A stopped job stays stopped, it gets the new schedule when it's started again
+            if job.next_tick != 0 {
+                let offset = FixedOffset::east_opt(job.time_offset_seconds)
+                    .unwrap_or(FixedOffset::east_opt(0).unwrap());
+                let next_tick = job
+                    .next_cron_fire(&self.context.now().with_timezone(&offset))
+                    .map(|t| t.with_timezone(&Utc));
+                job.set_next_tick(next_tick);
+            }
+            s.add_or_update(job).await?;
+            self.changed(*job_id, JobChange::Schedule);
+        }
+        w.insert(*job_id, provider);
+        Ok(())
+    }
+
+    ///
+    /// Forget that the last run of a job failed, so a job set to skip after a failure runs again.
+    /// The job's failure count is kept.
+    pub async fn reset_failures(&self, job_id: &Uuid) {
+        let mut w = self.context.stats.write().await;
+        w.reset_failure(job_id);
+    }
+
+    ///
+    /// How far ahead a cron job must fire for `add` to take it, 5 years by default. A schedule
+    /// that doesn't fire within this long, like `0 0 0 30 2 *`, is taken for a mistake and `add`
+    /// returns `UnsatisfiableSchedule`.
+    pub async fn set_schedule_horizon(&self, horizon: Duration) {
+        let mut w = self.context.schedule_horizon.write().await;
+        *w = horizon;
+    }
+
+    ///
+    /// Limit how many runs of all jobs together may go at the same time, None for no limit, which
+    /// is the default. Runs that fire at the limit wait until a run is done, the ones with the
+    /// highest priority set with `JobBuilder::with_priority` first. The limit can be changed at
+    /// any time, lowering it doesn't stop runs that are going.
+    pub fn set_max_concurrency(&mut self, max: Option<usize>) {
+        self.context.concurrency_limit.set_max(max);
+    }
+
+    ///
+    /// Limit how many runs of one shot jobs may go at the same time, on top of the limit set with
+    /// `set_max_concurrency`, so a burst of one shot jobs leaves room for the other jobs. None for
+    /// no limit, which is the default. Like the global limit, this can be changed at any time.
+    pub fn set_max_one_shot_concurrency(&mut self, max: Option<usize>) {
+        self.context.one_shot_limit.set_max(max);
+    }
+
+    ///
+    /// Limit how many runs of the jobs with a tag may go at the same time, on top of the limit
+    /// set with `set_max_concurrency`. A job with several limited tags waits for all of them.
+    /// Like the global limit, this can be changed at any time.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.set_tag_concurrency("db-heavy", 3).await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_tag_concurrency(&mut self, tag: &str, limit: usize) {
+        let mut w = self.context.tag_limits.write().await;
+        w.entry(tag.to_string())
+            .or_insert_with(|| Arc::new(ConcurrencyLimit::default()))
+            .set_max(Some(limit));
+    }
+
+    ///
+    /// Keep the starts of the runs of the jobs with a tag at least `gap` apart, for jobs that share
+    /// something that can't take runs in quick succession, None to stop keeping a gap. A fired job
+    /// waits for the gap after the start of the last run with the tag, holding on to its place in
+    /// the concurrency limits while it waits.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.set_tag_gap("legacy-api", Some(Duration::from_secs(5))).await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_tag_gap(&mut self, tag: &str, gap: Option<Duration>) {
+        let mut w = self.context.tag_gaps.write().await;
+        match gap {
+            Some(gap) => match w.get(tag) {
+                Some(run_gap) => run_gap.set_gap(gap),
+                None => {
+                    w.insert(tag.to_string(), Arc::new(RunGap::new(gap)));
+                }
+            },
+            None => {
+                w.remove(tag);
+            }
+        }
+    }
 
-            if let Err(err) = l.shutdown().await {
-                error!("{:?}", err);
+    ///
+    /// Keep the runs of all the jobs with a tag together to the rate of `limiter`, for jobs that
+    /// share a rate limited downstream API, None to stop limiting them. A run that fires when
+    /// there's no room waits for it, or is skipped with `OnRateLimited::Skip`. A job with several
+    /// limited tags keeps to all of them. The runs the limits delayed or skipped are counted in
+    /// the job's `throttled`. Runs that fire faster than the rate pile up waiting, unless they're
+    /// skipped or the jobs' overlap policy keeps them from it.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::{JobScheduler, RateLimiter};
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// // At most one call every 10 seconds
+    /// let limiter = RateLimiter::new(1, Duration::from_secs(10));
+    /// sched.set_tag_rate_limit("billing-api", Some(limiter)).await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_tag_rate_limit(&mut self, tag: &str, limiter: Option<RateLimiter>) {
+        let mut w = self.context.tag_rate_limits.write().await;
+        match limiter {
+            Some(limiter) => match w.get(tag) {
+                Some(bucket) => bucket.set_limiter(limiter),
+                None => {
+                    w.insert(tag.to_string(), Arc::new(TokenBucket::new(limiter)));
+                }
+            },
+            None => {
+                w.remove(tag);
             }
+        }
+    }
+
+    ///
+    /// Add a queue with its own `workers` for the jobs sent to it with `JobBuilder::with_queue`,
+    /// so kinds of jobs can get their own pools, like 20 workers for "io" and 4 for "cpu". A fired
+    /// job waits in the queue until a worker is free, when `depth` runs are waiting already the
+    /// fire is skipped. Workers still keep to the tag and global concurrency limits. Adding a queue
+    /// with the name of an existing one replaces it, the runs waiting in the old one still run.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{JobBuilder, JobScheduler};
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.add_queue("cpu", 4, 100).await;
+    /// let job = JobBuilder::new()
+    ///     .with_cron_job_type()
+    ///     .with_schedule("0 * * * * *")?
+    ///     .with_queue("cpu")
+    ///     .with_run_async(Box::new(|_uuid, _l| Box::pin(async move {})))
+    ///     .build()?;
+    /// sched.add(job).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn add_queue(&mut self, name: &str, workers: usize, depth: usize) {
+        let mut w = self.context.queues.write().await;
+        w.insert(name.to_string(), Arc::new(JobQueue::new(workers, depth)));
+    }
+
+    ///
+    /// Remove a queue added with `add_queue`, the runs waiting in it still run. The jobs sent to it
+    /// run right away again. Returns whether there was such a queue.
+    pub async fn remove_queue(&mut self, name: &str) -> bool {
+        let mut w = self.context.queues.write().await;
+        w.remove(name).is_some()
+    }
+
+    ///
+    /// How many runs are waiting in and being run by every queue added with `add_queue`
+    pub async fn queue_stats(&self) -> HashMap<String, QueueStats> {
+        let r = self.context.queues.read().await;
+        r.iter()
+            .map(|(name, queue)| (name.clone(), queue.stats()))
+            .collect()
+    }
+
+    ///
+    /// The shortest time between two runs of the same job, 1ms by default. A run that would
+    /// follow the previous one sooner, like a repeated job of 0 seconds, is moved to this long
+    /// after the previous run and a warning is logged.
+    pub async fn set_min_interval(&self, min_interval: Duration) {
+        let mut w = self.context.min_interval.write().await;
+        *w = min_interval;
+    }
+
+    ///
+    /// The longest the scheduler loop sleeps between passes, 500ms by default. The loop sleeps
+    /// until the next tick in the metadata store or the next one off fire when that's sooner.
+    /// Only `set_timezone` and `refresh_schedules` wake the loop early, so jobs that are added or
+    /// changed otherwise, retries and one off fires are picked up at the next pass, up to this
+    /// late, and so are the jobs added by another process sharing the store. A longer sleep means
+    /// fewer passes over the store, a shorter one picks up changes sooner.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.set_max_sleep(Duration::from_secs(60)).await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_max_sleep(&mut self, max_sleep: Duration) {
+        let mut w = self.context.max_sleep.write().await;
+        *w = max_sleep;
+    }
+
+    ///
+    /// Go by `clock` instead of the system clock, for instance a `MockClock` in tests. Jobs work
+    /// out their first fire by this clock when they're added, set it before adding them. The
+    /// jobs that were added before can have their fires worked out again by this clock with
+    /// `refresh_schedules`.
+    ///
+    /// ```rust,no_run
+    /// # use std::sync::Arc;
+    /// # #[cfg(feature = "test-util")]
+    /// # use tokio_cron_scheduler::MockClock;
+    /// # use tokio_cron_scheduler::{Job, JobScheduler};
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, start: chrono::DateTime<chrono::Utc>, run: fn(Uuid, JobScheduler) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>) -> Result<(), Box<dyn std::error::Error>> {
+    /// # #[cfg(feature = "test-util")]
+    /// sched.set_clock(Arc::new(MockClock::starting_at(start)));
+    /// sched.add(Job::new_async("0 * * * * *", run)?).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        let mut w = self
+            .context
+            .clock
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = clock;
+    }
+
+    ///
+    /// Code that is run every time `add` added a job, with the id of the job. It's called once
+    /// the job is stored, so looking the job up from it works.
+    pub fn set_on_add(&mut self, on_add: Box<JobAddedHandler>) {
+        let mut w = self
+            .context
+            .job_added_handler
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = Some(on_add);
+    }
+
+    ///
+    /// Remove the code that is run when a job was added
+    pub fn remove_on_add(&mut self) {
+        let mut w = self
+            .context
+            .job_added_handler
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = None;
+    }
+
+    ///
+    /// Code that is run every time a job in the scheduler is changed, with the id of the job and
+    /// what was changed, for keeping a record of changes. Adding and removing jobs is reported by
+    /// `set_on_add` and the job notifications. Runs where the change is made, so it should return
+    /// quickly.
+    ///
+    /// ```rust,no_run
+    /// # use std::sync::Arc;
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # struct AuditLog;
+    /// # impl AuditLog {
+    /// #     fn record(&self, _job_id: Uuid, _change: String) {}
+    /// # }
+    /// # async fn example(mut sched: JobScheduler, audit_log: Arc<AuditLog>) -> Result<(), Box<dyn std::error::Error>> {
+    /// let audit_log = audit_log.clone();
+    /// sched.set_on_change(Box::new(move |job_id, change| {
+    ///     audit_log.record(job_id, format!("{:?}", change));
+    /// }));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_on_change(&mut self, on_change: Box<JobChangedHandler>) {
+        let mut w = self
+            .context
+            .job_changed_handler
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = Some(on_change);
+    }
+
+    ///
+    /// Remove the code that is run when a job was changed
+    pub fn remove_on_change(&mut self) {
+        let mut w = self
+            .context
+            .job_changed_handler
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = None;
+    }
+
+    fn changed(&self, job_id: Uuid, change: JobChange) {
+        let r = self
+            .context
+            .job_changed_handler
+            .read()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(on_change) = r.as_ref() {
+            (on_change)(job_id, change);
+        }
+    }
+
+    ///
+    /// Code that is run after every pass of the scheduler loop, with what the pass did and how
+    /// long it took. It's called from the loop, so it should return quickly.
+    pub fn set_on_tick(&mut self, on_tick: Box<TickHandler>) {
+        let mut w = self
+            .context
+            .tick_handler
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = Some(on_tick);
+    }
+
+    ///
+    /// Remove the code that is run after every pass of the scheduler loop
+    pub fn remove_on_tick(&mut self) {
+        let mut w = self
+            .context
+            .tick_handler
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = None;
+    }
+
+    ///
+    /// Only fire jobs from passes of the scheduler loop for which `check` returns true, for running
+    /// replicas that share a metadata store with an external lease deciding the leader. The other
+    /// passes still move the ticks of due jobs on, but don't start them. `check` is called from the
+    /// loop, so it should return quickly.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{JobScheduler, LeaderLease};
+    /// # async fn example(mut sched: JobScheduler, lease: LeaderLease) -> Result<(), Box<dyn std::error::Error>> {
+    /// let lease = lease.clone();
+    /// sched.set_leadership_check(Box::new(move || lease.is_held()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_leadership_check(&mut self, check: Box<LeadershipCheck>) {
+        let mut w = self
+            .context
+            .leadership_check
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = Some(check);
+    }
+
+    ///
+    /// Only fire jobs while `lease` is held, so that of the instances sharing the metadata store
+    /// the leader runs the jobs. Replaces the leadership check.
+    pub fn set_leader_lease(&mut self, lease: &LeaderLease) {
+        let lease = lease.clone();
+        self.set_leadership_check(Box::new(move || lease.is_held()));
+    }
+
+    ///
+    /// Fire jobs from every pass of the scheduler loop again
+    pub fn remove_leadership_check(&mut self) {
+        let mut w = self
+            .context
+            .leadership_check
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = None;
+    }
+
+    ///
+    /// Hold off firing jobs while `rx` is true, like while the queue the jobs feed is overloaded.
+    /// The schedules go on meanwhile, fires are passed over and not caught up on once it's false
+    /// again.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler, queue_depth: usize) -> Result<(), Box<dyn std::error::Error>> {
+    /// let (overloaded_tx, overloaded_rx) = tokio::sync::watch::channel(false);
+    /// sched.set_backpressure(overloaded_rx);
+    /// overloaded_tx.send(queue_depth > 10_000)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_backpressure(&mut self, rx: tokio::sync::watch::Receiver<bool>) {
+        let mut w = self
+            .context
+            .backpressure
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = Some(rx);
+    }
+
+    ///
+    /// Fire jobs regardless of backpressure again
+    pub fn remove_backpressure(&mut self) {
+        let mut w = self
+            .context
+            .backpressure
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = None;
+    }
+
+    ///
+    /// Whether firing jobs is held off by the signal set with `set_backpressure`
+    pub fn is_backpressured(&self) -> bool {
+        self.context.backpressured()
+    }
+
+    ///
+    /// Don't fire any job until `ready` is done, like until a database pool the jobs use is
+    /// connected. The jobs can be added and listed meanwhile and their schedules go on, but the
+    /// fires until it's done are passed over, not caught up on, as with `set_start_clean`. Setting
+    /// another readiness future replaces the one before.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{Job, JobScheduler};
+    /// # async fn example(mut sched: JobScheduler, job: Job) -> Result<(), Box<dyn std::error::Error>> {
+    /// let (pool_tx, pool_rx) = tokio::sync::oneshot::channel::<()>();
+    /// sched.set_ready_signal(async move {
+    ///     let _ = pool_rx.await;
+    /// });
+    /// sched.add(job).await?;
+    /// sched.start().await?;
+    /// pool_tx.send(()).ok();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_ready_signal<F>(&mut self, ready: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let (tx, rx) = tokio::sync::watch::channel(false);
+        {
+            let mut w = self
+                .context
+                .ready
+                .write()
+                .unwrap_or_else(|e| e.into_inner());
+            *w = Some(rx);
+        }
+        tokio::spawn(async move {
+            ready.await;
+            let _ = tx.send(true);
         });
     }
 
     ///
-    /// Code that is run after the shutdown was run
-    pub fn set_shutdown_handler(&mut self, job: Box<ShutdownNotification>) {
-        self.shutdown_notifier = Some(Arc::new(RwLock::new(job)));
+    /// Whether the readiness future set with `set_ready_signal` is done, true when none was set
+    pub fn is_ready(&self) -> bool {
+        self.context.ready()
     }
 
     ///
-    /// Remove the shutdown handler
-    pub fn remove_shutdown_handler(&mut self) {
-        self.shutdown_notifier = None;
+    /// Check every job before it's added, `add` returns the error of the interceptor without
+    /// adding the job. The interceptor is called with the job as it would be found in the metadata
+    /// store, before the job is saved there.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::{JobInfo, JobScheduler, JobSchedulerError};
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// sched.set_add_interceptor(Box::new(|job: &JobInfo| match job.repeated_every {
+    ///     Some(secs) if secs < 60 => Err(JobSchedulerError::JobRejected(format!(
+    ///         "Runs every {} seconds, at least 60 are needed",
+    ///         secs
+    ///     ))),
+    ///     _ => Ok(()),
+    /// }));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_add_interceptor(&mut self, interceptor: Box<AddInterceptor>) {
+        let mut w = self
+            .context
+            .add_interceptor
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = Some(interceptor);
+    }
+
+    ///
+    /// Add jobs without checking them first
+    pub fn remove_add_interceptor(&mut self) {
+        let mut w = self
+            .context
+            .add_interceptor
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *w = None;
+    }
+
+    ///
+    /// Whether the scheduler loop made a pass within `max_staleness`. The loop passes at least as
+    /// often as the max sleep set with `set_max_sleep`, 500ms by default, once the scheduler is
+    /// started, before that the scheduler is not healthy.
+    pub fn is_healthy(&self, max_staleness: Duration) -> bool {
+        let last = self.context.heartbeat.load(Ordering::Relaxed);
+        if last == 0 {
+            return false;
+        }
+        let now = self.context.now().timestamp_millis() as u64;
+        now.saturating_sub(last) <= max_staleness.as_millis() as u64
+    }
+
+    ///
+    /// When the scheduler loop wakes for its next pass, the end of the sleep it's in, for telling
+    /// a coordinator of several instances how soon this one picks up due jobs. The loop sleeps
+    /// until the next tick, at most the max sleep set with `set_max_sleep`, so this is when the
+    /// next job is due or sooner. `None` while the loop isn't running.
+    pub fn next_wake(&self) -> Option<DateTime<Utc>> {
+        match self.context.next_wake.load(Ordering::Relaxed) {
+            0 => None,
+            millis => DateTime::from_timestamp_millis(millis as i64),
+        }
+    }
+
+    ///
+    /// Get a snapshot of the settings in effect
+    pub async fn config(&self) -> SchedulerConfig {
+        let on_add = self
+            .context
+            .job_added_handler
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some();
+        SchedulerConfig {
+            max_sleep: *self.context.max_sleep.read().await,
+            removals_on_shutdown: self.scheduler.read().await.removals_on_shutdown,
+            start_clean: *self.scheduler.read().await.start_clean.read().await,
+            min_interval: *self.context.min_interval.read().await,
+            max_concurrency: self.context.concurrency_limit.max(),
+            max_one_shot_concurrency: self.context.one_shot_limit.max(),
+            schedule_horizon: *self.context.schedule_horizon.read().await,
+            name_conflict: *self.context.name_conflict.read().await,
+            timezone: *self.context.timezone.read().await,
+            has_checkpoint_store: self.context.checkpoint_store.read().await.is_some(),
+            has_job_failure_handler: self.context.job_failure_handler.read().await.is_some(),
+            has_shutdown_handler: self
+                .context
+                .shutdown_handler
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .is_some(),
+            has_pre_shutdown_hook: self
+                .context
+                .pre_shutdown_hook
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .is_some(),
+            has_on_add: on_add,
+        }
+    }
+
+    ///
+    /// Take a snapshot of the whole scheduler: the metadata of every job, their run counters,
+    /// their pauses and the settings. Load it into another scheduler with `import_state`. With the
+    /// `serde` feature the snapshot can be serialized, whatever the metadata store.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # async fn example(mut sched: JobScheduler) -> Result<(), Box<dyn std::error::Error>> {
+    /// # #[cfg(feature = "serde")]
+    /// let json = serde_json::to_string(&sched.export_state().await?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn export_state(&self) -> Result<SchedulerState, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+
+        let jobs = {
+            let mut r = self.context.metadata_storage.write().await;
+            r.list_jobs().await?
+        };
+        let now = self.context.now();
+        let paused_until = {
+            let r = self.context.paused_until.read().await;
+            r.iter()
+                .filter(|(_, until)| **until > now)
+                .map(|(id, until)| (*id, *until))
+                .collect()
+        };
+        Ok(SchedulerState {
+            jobs,
+            stats: self.stats().await,
+            paused_until,
+            config: self.config().await,
+        })
+    }
+
+    ///
+    /// Load a snapshot taken with `export_state`. The jobs are added as they were, with their
+    /// ticks, flags, tags and run counters. As code can't be part of a snapshot, `registry` gives
+    /// the code for each job, for instance by looking up its id, name, tags or extra data. Jobs it has no
+    /// code for are left out. The jobs that were paused stay paused. The settings of the snapshot
+    /// are applied, handlers and stores aren't part of it. Returns the ids of the jobs that were
+    /// added.
+    ///
+    /// ```rust,no_run
+    /// # use std::sync::Arc;
+    /// # use tokio_cron_scheduler::{JobContext, JobScheduler, JobToRunWithContext, SchedulerState};
+    /// # async fn example(old: JobScheduler, mut new: JobScheduler, handlers: std::collections::HashMap<String, Arc<JobToRunWithContext>>, json: String) -> Result<(), Box<dyn std::error::Error>> {
+    /// let state = old.export_state().await?;
+    /// let ids = new
+    ///     .import_state(state, |data| handlers.get(&data.name).cloned().map(JobContext::handler))
+    ///     .await?;
+    ///
+    /// // Or from a snapshot serialized with the `serde` feature
+    /// # #[cfg(feature = "serde")]
+    /// let state: SchedulerState = serde_json::from_str(&json)?;
+    /// # #[cfg(feature = "serde")]
+    /// new.import_state(state, |data| handlers.get(&data.name).cloned().map(JobContext::handler))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn import_state<F>(
+        &mut self,
+        state: SchedulerState,
+        registry: F,
+    ) -> Result<Vec<Uuid>, JobSchedulerError>
+    where
+        F: Fn(&JobStoredData) -> Option<Box<JobToRunAsync>>,
+    {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+
+        self.set_min_interval(state.config.min_interval).await;
+        self.set_schedule_horizon(state.config.schedule_horizon)
+            .await;
+        self.set_name_conflict(state.config.name_conflict).await;
+        self.set_max_concurrency(state.config.max_concurrency);
+        self.set_max_one_shot_concurrency(state.config.max_one_shot_concurrency);
+        self.set_removals_on_shutdown(state.config.removals_on_shutdown)
+            .await;
+        self.set_start_clean(state.config.start_clean).await;
+
+        let mut ids = vec![];
+        for data in state.jobs {
+            let job_id: Uuid = match data.id.as_ref() {
+                Some(id) => id.into(),
+                None => continue,
+            };
+            let run_async = match registry(&data) {
+                Some(run_async) => run_async,
+                None => {
+                    warn!("No code for {:?}, it's not imported", job_id);
+                    continue;
+                }
+            };
+            self.add(JobLocked::from_stored_data(data, run_async))
+                .await?;
+            if let Some(stats) = state.stats.job(&job_id) {
+                let mut w = self.context.stats.write().await;
+                w.jobs.insert(
+                    job_id,
+                    JobRunStats {
+                        running: 0,
+                        ..stats.clone()
+                    },
+                );
+            }
+            if let Some(until) = state.paused_until.get(&job_id) {
+                self.pause_until(&job_id, *until).await?;
+            }
+            ids.push(job_id);
+        }
+        Ok(ids)
+    }
+
+    ///
+    /// Get a snapshot of the run counters of the scheduler
+    pub async fn stats(&self) -> SchedulerStats {
+        let r = self.context.stats.read().await;
+        r.clone()
+    }
+
+    ///
+    /// Start the run counters of a job over, for instance for a new reporting window. Whether a
+    /// run is going and whether the last run failed are kept. A run budget and the run count in
+    /// the `JobContext` count from the reset too.
+    pub async fn reset_stats(&self, job_id: &Uuid) {
+        let mut w = self.context.stats.write().await;
+        w.reset(job_id);
+    }
+
+    ///
+    /// Get the run counters of the scheduler in the Prometheus text exposition format, to be
+    /// appended to an existing `/metrics` endpoint
+    #[cfg(feature = "metrics")]
+    pub async fn metrics_text(&self) -> String {
+        let r = self.context.stats.read().await;
+        r.to_prometheus()
+    }
+
+    ///
+    /// How long the runs of a job took at the given percentiles from 0 to 100, in the same order,
+    /// from a histogram of all its runs since the job was added or its stats were reset. None
+    /// when the job didn't run yet.
+    ///
+    /// ```rust,no_run
+    /// # use tokio_cron_scheduler::JobScheduler;
+    /// # use uuid::Uuid;
+    /// # async fn example(mut sched: JobScheduler, job_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+    /// if let Some(p) = sched.latency_percentiles(&job_id, &[50.0, 95.0, 99.0]).await {
+    ///     println!("p50 {:?} p95 {:?} p99 {:?}", p[0], p[1], p[2]);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub async fn latency_percentiles(
+        &self,
+        job_id: &Uuid,
+        percentiles: &[f64],
+    ) -> Option<Vec<Duration>> {
+        let r = self.context.stats.read().await;
+        let latency = &r.job(job_id)?.latency;
+        percentiles
+            .iter()
+            .map(|percentile| latency.percentile(*percentile))
+            .collect()
+    }
+
+    ///
+    /// The scheduler with the id of one of its jobs, for a job to control itself from its run
+    pub fn handle(&self, job_id: Uuid) -> SchedulerHandle {
+        SchedulerHandle::new(job_id, self.clone())
     }
 
     ///
@@ -0,0 +1,86 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Buckets per power of two, so a bucket is at most an eighth of its lower bound wide
+const SUB_BUCKETS: usize = 8;
+/// Enough powers of two in microseconds for runs of up to about 200 days, longer runs are
+/// counted in the last bucket
+const BUCKETS: usize = SUB_BUCKETS * 42;
+
+///
+/// How long the runs of a job took, counted in buckets of microseconds that grow with the
+/// duration instead of keeping every run, so it takes the same memory however often the job runs.
+/// Percentiles are accurate to within an eighth.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LatencyHistogram {
+    counts: Vec<u64>,
+    count: u64,
+    max: Duration,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            counts: vec![0; BUCKETS],
+            count: 0,
+            max: Duration::ZERO,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    fn bucket(micros: u64) -> usize {
+        if micros < SUB_BUCKETS as u64 {
+            return micros as usize;
+        }
+        let power = 63 - micros.leading_zeros() as usize;
+        let shift = power - SUB_BUCKETS.trailing_zeros() as usize;
+        let sub = (micros >> shift) as usize - SUB_BUCKETS;
+        ((shift + 1) * SUB_BUCKETS + sub).min(BUCKETS - 1)
+    }
+
+    /// The longest duration counted in a bucket
+    fn upper_bound(bucket: usize) -> u64 {
+        if bucket < SUB_BUCKETS {
+            return bucket as u64;
+        }
+        let shift = bucket / SUB_BUCKETS - 1;
+        let sub = (bucket % SUB_BUCKETS) as u64;
+        ((SUB_BUCKETS as u64 + sub + 1) << shift) - 1
+    }
+
+    pub(crate) fn record(&mut self, duration: Duration) {
+        let micros = duration.as_micros().min(u64::MAX as u128) as u64;
+        self.counts[LatencyHistogram::bucket(micros)] += 1;
+        self.count += 1;
+        self.max = self.max.max(duration);
+    }
+
+    ///
+    /// How many runs were counted
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    ///
+    /// How long the runs took at most at a percentile from 0 to 100, like 99.0 for the time 99%
+    /// of the runs stayed under. None when no run was counted.
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = ((percentile.clamp(0.0, 100.0) / 100.0) * self.count as f64).ceil() as u64;
+        let rank = rank.max(1);
+        let mut seen = 0;
+        for (bucket, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                let upper = Duration::from_micros(LatencyHistogram::upper_bound(bucket));
+                return Some(upper.min(self.max));
+            }
+        }
+        Some(self.max)
+    }
+}
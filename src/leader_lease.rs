@@ -0,0 +1,108 @@
+use crate::store::LeaseStore;
+use chrono::Utc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, Notify};
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+///
+/// Keeps a lease up from the background, so that of the scheduler instances sharing a metadata
+/// store only the one holding it fires jobs. The lease is renewed every third of its ttl. Should
+/// the holder die or lose its connection, the lease runs out and another instance takes it over.
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use tokio_cron_scheduler::{JobScheduler, LeaderLease, LeaseStore};
+/// # async fn example(mut sched: JobScheduler, lease_store: Box<dyn LeaseStore + Send + Sync>) -> Result<(), Box<dyn std::error::Error>> {
+/// // A PostgresLeaderLease or NatsLeaderLease, or a LeaseStore of your own
+/// let lease = LeaderLease::start(lease_store, Duration::from_secs(15));
+/// sched.set_leader_lease(&lease);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct LeaderLease {
+    held_until: Arc<AtomicU64>,
+    stop: Arc<Notify>,
+    task: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl LeaderLease {
+    ///
+    /// Start taking and renewing the lease of `store`, held for `ttl` after every renewal
+    pub fn start(mut store: Box<dyn LeaseStore + Send + Sync>, ttl: Duration) -> Self {
+        let held_until = Arc::new(AtomicU64::new(0));
+        let stop = Arc::new(Notify::new());
+        let renew_every = ttl / 3;
+        let task = {
+            let held_until = held_until.clone();
+            let stop = stop.clone();
+            tokio::spawn(async move {
+                loop {
+                    // Held until a ttl after the renewal started, the store keeps it at least as
+                    // long
+                    let started = Utc::now().timestamp_millis() as u64;
+                    let held = match store.acquire(ttl).await {
+                        Ok(held) => held,
+                        Err(e) => {
+                            error!("Error renewing the leader lease {:?}", e);
+                            false
+                        }
+                    };
+                    let until = if held {
+                        started + ttl.as_millis() as u64
+                    } else {
+                        0
+                    };
+                    let was_held = held_until.swap(until, Ordering::Relaxed) > 0;
+                    if held && !was_held {
+                        info!("Took the leader lease");
+                    } else if !held && was_held {
+                        info!("Lost the leader lease");
+                    }
+                    if tokio::time::timeout(renew_every, stop.notified())
+                        .await
+                        .is_ok()
+                    {
+                        break;
+                    }
+                }
+                held_until.store(0, Ordering::Relaxed);
+                if let Err(e) = store.release().await {
+                    error!("Error releasing the leader lease {:?}", e);
+                }
+            })
+        };
+        Self {
+            held_until,
+            stop,
+            task: Arc::new(Mutex::new(Some(task))),
+        }
+    }
+
+    ///
+    /// Whether this instance holds the lease. It's let go of once a renewal is due and hasn't
+    /// gone through.
+    pub fn is_held(&self) -> bool {
+        let now = Utc::now().timestamp_millis() as u64;
+        now < self.held_until.load(Ordering::Relaxed)
+    }
+
+    ///
+    /// Stop renewing the lease and give it up, for another instance to take over without waiting
+    /// for it to run out. Runs that were started while it was held aren't stopped.
+    pub async fn release(&self) {
+        self.stop.notify_one();
+        let task = {
+            let mut w = self.task.lock().await;
+            w.take()
+        };
+        if let Some(task) = task {
+            if let Err(e) = task.await {
+                error!("Error stopping the leader lease {:?}", e);
+            }
+        }
+    }
+}
@@ -3,18 +3,39 @@
 extern crate num_derive;
 extern crate core;
 
+mod clock;
 mod context;
+mod crontab_loader;
 mod error;
+#[cfg(feature = "metrics")]
+mod instruments;
 mod job;
+mod job_change;
+mod job_event;
 mod job_scheduler;
+#[cfg(feature = "metrics")]
+mod latency_histogram;
+mod leader_lease;
+mod name_conflict;
 #[cfg(feature = "nats_storage")]
 mod nats;
+mod next_job;
 mod notification;
 #[cfg(feature = "postgres_storage")]
 mod postgres;
+#[cfg(feature = "redis_storage")]
+mod redis;
 mod scheduler;
+mod scheduler_activity;
+mod scheduler_config;
+mod scheduler_handle;
+mod scheduler_state;
 mod simple;
+#[cfg(feature = "sqlite_storage")]
+mod sqlite;
+mod stats;
 mod store;
+mod test_scheduler;
 
 use std::ops::Add;
 use std::str::FromStr;
@@ -24,7 +45,7 @@ use std::time::{Duration, SystemTime};
 use crate::job::job_data::ListOfUuids;
 #[cfg(feature = "has_bytes")]
 use crate::job::job_data_prost::ListOfUuids;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeZone, Utc};
 use cron::Schedule;
 #[cfg(not(feature = "has_bytes"))]
 use job::job_data::{JobAndNextTick, JobStoredData, Uuid as JobUuid};
@@ -33,26 +54,72 @@ use job::job_data_prost::{JobAndNextTick, JobStoredData, Uuid as JobUuid};
 use uuid::Uuid;
 
 #[cfg(feature = "nats_storage")]
-pub use crate::nats::{NatsMetadataStore, NatsNotificationStore, NatsStore, NatsStoreBuilder};
+pub use crate::nats::{
+    NatsLeaderLease, NatsMetadataStore, NatsNotificationStore, NatsStore, NatsStoreBuilder,
+};
 
 #[cfg(feature = "postgres_storage")]
-pub use crate::postgres::{PostgresMetadataStore, PostgresNotificationStore, PostgresStore};
+pub use crate::postgres::{
+    PostgresHistoryStore, PostgresLeaderLease, PostgresMetadataStore, PostgresNotificationStore,
+    PostgresStore,
+};
+
+#[cfg(feature = "redis_storage")]
+pub use crate::redis::{
+    RedisHistoryStore, RedisJobChange, RedisMetadataStore, RedisNotificationStore, RedisStore,
+};
+
+#[cfg(feature = "sqlite_storage")]
+pub use crate::sqlite::{SqliteMetadataStore, SqliteNotificationStore, SqliteStore};
 
+#[cfg(feature = "test-util")]
+pub use clock::MockClock;
+pub use clock::{Clock, SystemClock};
 pub use context::Context;
+pub use crontab_loader::{CrontabChanges, CrontabLoader};
 pub use error::JobSchedulerError;
+#[cfg(feature = "metrics")]
+pub use instruments::describe_metrics;
 #[cfg(not(feature = "has_bytes"))]
 pub use job::job_data::JobState as JobNotification;
+#[cfg(not(feature = "has_bytes"))]
+pub use job::job_data::JobType;
 #[cfg(feature = "has_bytes")]
 pub use job::job_data_prost::JobState as JobNotification;
+#[cfg(feature = "has_bytes")]
+pub use job::job_data_prost::JobType;
 pub use job::to_code::{JobCode, NotificationCode, PinnedGetFuture, ToCode};
 pub use job::JobLocked as Job;
-pub use job::OnJobNotification;
-pub use job::{JobBuilder, JobToRun, JobToRunAsync};
+pub use job::{
+    parse_cron, preview_cron, validate_cron, AfterJob, Backoff, Calendar, CalendarAction,
+    IntervalSchedule, JobBuilder, JobContext, JobDescription, JobError, JobInfo, JobPayload,
+    JobSpec, JobStatus, JobToRun, JobToRunAsync, MisfirePolicy, OnBudgetExceeded, OnRateLimited,
+    OverlapPolicy, QueueDrop, QueueStats, RateLimiter, RetryPolicy, RunBudget,
+};
+pub use job::{JobToRunWithContext, OnJobNotification, SharedJobToRunAsync};
+pub use job_change::JobChange;
+pub use job_event::{EventStream, JobEvent};
 pub use job_scheduler::JobsSchedulerLocked as JobScheduler;
-pub use store::{MetaDataStorage, NotificationStore};
+#[cfg(feature = "metrics")]
+pub use latency_histogram::LatencyHistogram;
+pub use leader_lease::LeaderLease;
+pub use name_conflict::NameConflict;
+pub use next_job::NextJob;
+pub use scheduler::{RemovalsOnShutdown, TickReport};
+pub use scheduler_activity::SchedulerActivity;
+pub use scheduler_config::SchedulerConfig;
+pub use scheduler_handle::SchedulerHandle;
+pub use scheduler_state::SchedulerState;
+pub use stats::{JobRunStats, SchedulerStats, SkipReason};
+pub use store::{
+    CheckpointStore, HistoryStore, LeaseStore, MetaDataStorage, NotificationStore,
+    PinnedCheckpointFuture, RunRecord,
+};
+pub use test_scheduler::{TestRun, TestScheduler};
 
 pub use simple::{
-    SimpleJobCode, SimpleMetadataStore, SimpleNotificationCode, SimpleNotificationStore,
+    SimpleHistoryStore, SimpleJobCode, SimpleMetadataStore, SimpleNotificationCode,
+    SimpleNotificationStore,
 };
 
 impl JobUuid {
@@ -124,6 +191,39 @@ impl JobStoredData {
             .and_then(|s| Schedule::from_str(s).ok())
     }
 
+    ///
+    /// The first fire of the cron schedule after `after`, moved by the job's stable jitter
+    pub fn next_cron_tick<TZ: TimeZone>(&self, after: &DateTime<TZ>) -> Option<DateTime<TZ>> {
+        let jitter = chrono::Duration::seconds(self.jitter_seconds as i64);
+        let schedule = self.schedule()?;
+        // A named timezone has its own offset at every fire, so it follows daylight saving time
+        #[cfg(feature = "timezone")]
+        if let Ok(timezone) = self.timezone.parse::<chrono_tz::Tz>() {
+            return schedule
+                .after(&(after.with_timezone(&timezone) - jitter))
+                .next()
+                .map(|t| (t + jitter).with_timezone(&after.timezone()));
+        }
+        schedule
+            .after(&(after.clone() - jitter))
+            .next()
+            .map(|t| t + jitter)
+    }
+
+    ///
+    /// The first fire of the cron schedule after `after` as `next_cron_tick` gives it, moved on
+    /// by a new random delay within the job's random jitter window, see `JobBuilder::with_jitter`
+    pub fn next_cron_fire<TZ: TimeZone>(&self, after: &DateTime<TZ>) -> Option<DateTime<TZ>> {
+        self.next_cron_tick(after).map(|t| t + self.random_delay())
+    }
+
+    ///
+    /// A random delay of whole seconds within the job's random jitter window, zero without one
+    pub fn random_delay(&self) -> chrono::Duration {
+        let delay = job::random::below(self.random_jitter_seconds as u64);
+        chrono::Duration::seconds(delay as i64)
+    }
+
     pub fn next_tick_utc(&self) -> Option<DateTime<Utc>> {
         match self.next_tick {
             0 => None,
@@ -135,6 +235,29 @@ impl JobStoredData {
         self.last_tick.map(JobAndNextTick::utc)
     }
 
+    ///
+    /// The first fire of an interval schedule after `after`, on the schedule's grid, see
+    /// `JobBuilder::with_interval`. None once the schedule is over, and for jobs without one.
+    pub fn next_interval_fire(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let bounds = self.interval_bounds.as_ref()?;
+        let every = self.repeated_every().filter(|every| *every > 0)? as i64;
+        let first = bounds.first as i64;
+        let after = after.timestamp();
+        let fires = if after < first {
+            0
+        } else {
+            (after - first) / every + 1
+        };
+        if bounds.max_runs > 0 && fires >= bounds.max_runs as i64 {
+            return None;
+        }
+        let at = first.checked_add(fires.checked_mul(every)?)?;
+        if bounds.end > 0 && at >= bounds.end as i64 {
+            return None;
+        }
+        Utc.timestamp_opt(at, 0).single()
+    }
+
     pub fn repeated_every(&self) -> Option<u64> {
         self.job.as_ref().and_then(|jt| match jt {
             #[cfg(feature = "has_bytes")]
@@ -148,6 +271,65 @@ impl JobStoredData {
         })
     }
 
+    ///
+    /// How long a run of the job may take before it's cancelled, None for no limit
+    pub fn timeout(&self) -> Option<Duration> {
+        match self.timeout_ms {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        }
+    }
+
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        // Kept as at least a millisecond, 0 is no timeout
+        self.timeout_ms = timeout
+            .map(|t| (t.as_millis() as u64).max(1))
+            .unwrap_or_default();
+    }
+
+    ///
+    /// Change the cron schedule of a cron job, other jobs are left as they are. Returns whether
+    /// the schedule changed.
+    pub fn set_schedule(&mut self, schedule: &Schedule) -> bool {
+        let schedule = schedule.to_string();
+        match self.job.as_mut() {
+            #[cfg(feature = "has_bytes")]
+            Some(job::job_data_prost::job_stored_data::Job::CronJob(cj)) => {
+                let changed = cj.schedule != schedule;
+                cj.schedule = schedule;
+                changed
+            }
+            #[cfg(not(feature = "has_bytes"))]
+            Some(job::job_data::job_stored_data::Job::CronJob(cj)) => {
+                let changed = cj.schedule != schedule;
+                cj.schedule = schedule;
+                changed
+            }
+            _ => false,
+        }
+    }
+
+    ///
+    /// Change the interval of a repeated job, other jobs are left as they are. Returns whether the
+    /// interval changed.
+    pub fn set_repeated_every(&mut self, every: u64) -> bool {
+        match self.job.as_mut() {
+            #[cfg(feature = "has_bytes")]
+            Some(job::job_data_prost::job_stored_data::Job::NonCronJob(ncj)) => {
+                let changed = ncj.repeated_every != every;
+                ncj.repeated_every = every;
+                changed
+            }
+            #[cfg(not(feature = "has_bytes"))]
+            Some(job::job_data::job_stored_data::Job::NonCronJob(ncj)) => {
+                let changed = ncj.repeated_every != every;
+                ncj.repeated_every = every;
+                changed
+            }
+            _ => false,
+        }
+    }
+
     pub fn set_next_tick(&mut self, tick: Option<DateTime<Utc>>) {
         self.next_tick = match tick {
             Some(t) => t.timestamp() as u64,
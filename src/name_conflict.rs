@@ -0,0 +1,15 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+///
+/// What `add` does with a job whose name another job in the scheduler has already, see
+/// `JobBuilder::with_name`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NameConflict {
+    /// Return `JobSchedulerError::NameTaken` and leave the job that has the name as it is
+    #[default]
+    Reject,
+    /// Swap the job that has the name for the new one as `replace` does, keeping its id
+    Replace,
+}
@@ -0,0 +1,115 @@
+use crate::nats::{sanitize_nats_key, NatsStore};
+use crate::store::LeaseStore;
+use crate::JobSchedulerError;
+use chrono::Utc;
+use nats::kv::Operation;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+const LEASE_KEY: &str = "TCS_LEADER";
+
+///
+/// A lease kept as a key in the Nats KV bucket, with the instance holding it and until when. It's
+/// taken and renewed with writes on the revision that was read, so only one instance gets it,
+/// and taken over by another instance once it has run out. The clocks of the instances are
+/// expected to be in sync.
+#[derive(Clone)]
+pub struct NatsLeaderLease {
+    pub store: NatsStore,
+    pub key: String,
+    holder: Uuid,
+}
+
+impl NatsLeaderLease {
+    pub fn new(store: NatsStore, key: String) -> Self {
+        Self {
+            store,
+            key: sanitize_nats_key(&key),
+            holder: Uuid::new_v4(),
+        }
+    }
+}
+
+impl Default for NatsLeaderLease {
+    fn default() -> Self {
+        let key = std::env::var("NATS_LEADER_KEY").unwrap_or_else(|_| LEASE_KEY.to_string());
+        Self::new(NatsStore::default(), key)
+    }
+}
+
+/// The holder and until when in milliseconds, as kept in the bucket
+fn lease_value(holder: Uuid, until: i64) -> String {
+    format!("{} {}", holder, until)
+}
+
+fn parse_lease_value(value: &[u8]) -> Option<(Uuid, i64)> {
+    let value = std::str::from_utf8(value).ok()?;
+    let (holder, until) = value.split_once(' ')?;
+    Some((Uuid::parse_str(holder).ok()?, until.parse().ok()?))
+}
+
+impl LeaseStore for NatsLeaderLease {
+    fn acquire(
+        &mut self,
+        ttl: Duration,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>> {
+        let bucket = self.store.bucket.clone();
+        let key = self.key.clone();
+        let holder = self.holder;
+        Box::pin(async move {
+            let bucket = bucket.read().await;
+            let now = Utc::now().timestamp_millis();
+            let value = lease_value(holder, now + ttl.as_millis() as i64);
+            let entry = bucket.entry(&key).map_err(|e| {
+                error!("Error getting the leader lease {:?}", e);
+                JobSchedulerError::LeaseFailed(e.to_string())
+            })?;
+            let entry = entry.filter(|entry| entry.operation == Operation::Put);
+            let written = match entry {
+                Some(entry) => match parse_lease_value(&entry.value) {
+                    Some((current, until)) if current != holder && until > now => return Ok(false),
+                    _ => bucket.update(&key, value, entry.revision),
+                },
+                None => bucket.create(&key, value),
+            };
+            match written {
+                Ok(_) => Ok(true),
+                Err(e) => {
+                    // Another instance wrote it since it was read
+                    debug!("Leader lease not taken {:?}", e);
+                    Ok(false)
+                }
+            }
+        })
+    }
+
+    fn release(&mut self) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let bucket = self.store.bucket.clone();
+        let key = self.key.clone();
+        let holder = self.holder;
+        Box::pin(async move {
+            let bucket = bucket.read().await;
+            let entry = bucket.entry(&key).map_err(|e| {
+                error!("Error getting the leader lease {:?}", e);
+                JobSchedulerError::LeaseFailed(e.to_string())
+            })?;
+            let entry = match entry.filter(|entry| entry.operation == Operation::Put) {
+                Some(entry) => entry,
+                None => return Ok(()),
+            };
+            match parse_lease_value(&entry.value) {
+                Some((current, _)) if current == holder => bucket
+                    .update(&key, lease_value(holder, 0), entry.revision)
+                    .map(|_| ())
+                    .map_err(|e| {
+                        error!("Error releasing the leader lease {:?}", e);
+                        JobSchedulerError::LeaseFailed(e.to_string())
+                    }),
+                _ => Ok(()),
+            }
+        })
+    }
+}
@@ -140,6 +140,8 @@ impl MetaDataStorage for NatsMetadataStore {
                     job_type: jd.job_type,
                     next_tick: jd.next_tick,
                     last_tick: jd.last_tick,
+                    order_key: jd.order_key,
+                    priority: jd.priority,
                 })
                 .collect::<Vec<_>>();
             Ok(list)
@@ -187,6 +189,14 @@ impl MetaDataStorage for NatsMetadataStore {
 
     fn time_till_next_job(
         &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<std::time::Duration>, JobSchedulerError>> + Send>>
+    {
+        self.time_till_next_job_at(Utc::now())
+    }
+
+    fn time_till_next_job_at(
+        &mut self,
+        now: DateTime<Utc>,
     ) -> Pin<Box<dyn Future<Output = Result<Option<Duration>, JobSchedulerError>> + Send>> {
         let list = self.list_guids();
         let bucket = self.store.bucket.clone();
@@ -198,7 +208,6 @@ impl MetaDataStorage for NatsMetadataStore {
             }
             let list = list.unwrap();
             let bucket = bucket.read().await;
-            let now = Utc::now();
             let now = now.timestamp() as u64;
             let ret = list
                 .uuids
@@ -226,6 +235,34 @@ impl MetaDataStorage for NatsMetadataStore {
             Ok(ret)
         })
     }
+
+    fn list_jobs(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobStoredData>, JobSchedulerError>> + Send>> {
+        let list_guids = self.list_guids();
+        let bucket = self.store.bucket.clone();
+        Box::pin(async move {
+            let list = list_guids.await;
+            if let Err(e) = list {
+                error!("Error getting list of guids {:?}", e);
+                return Err(e);
+            }
+            let list = list.unwrap();
+            let bucket = bucket.read().await;
+            let list = list
+                .uuids
+                .iter()
+                .map(|uuid| {
+                    let uuid: Uuid = uuid.into();
+                    uuid
+                })
+                .flat_map(|uuid| bucket.get(&*uuid_to_nats_id(uuid)))
+                .flatten()
+                .flat_map(|buf| JobStoredData::decode(buf.as_slice()))
+                .collect::<Vec<_>>();
+            Ok(list)
+        })
+    }
 }
 
 impl NatsMetadataStore {
@@ -1,3 +1,4 @@
+mod leader_lease;
 mod metadata_store;
 mod notification_store;
 
@@ -8,6 +9,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use crate::JobSchedulerError;
+pub use leader_lease::NatsLeaderLease;
 pub use metadata_store::NatsMetadataStore;
 pub use notification_store::NatsNotificationStore;
 
@@ -0,0 +1,13 @@
+use std::time::Duration;
+
+///
+/// When the next job is due, as returned by `time_till_next_job`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NextJob {
+    /// The next job is due in this long
+    In(Duration),
+    /// No job has a next tick
+    None,
+    /// A job is due already and hasn't been fired yet
+    Overdue,
+}
@@ -125,21 +125,17 @@ impl NotificationCreator {
                 let created = created_rx.recv().await;
                 match created {
                     Ok(e) => match e {
-                        Ok(uuid) => {
-                            if uuid == notification_id {
-                                if let Err(e) = tx.send(Ok(uuid)) {
-                                    error!("Error sending notification addition success {:?}", e);
-                                }
-                                break 'receiving_additions;
+                        Ok(uuid) if uuid == notification_id => {
+                            if let Err(e) = tx.send(Ok(uuid)) {
+                                error!("Error sending notification addition success {:?}", e);
                             }
+                            break 'receiving_additions;
                         }
-                        Err((e, Some(uuid))) => {
-                            if uuid == notification_id {
-                                if let Err(e) = tx.send(Err(e)) {
-                                    error!("Error sending notification addition failure {:?}", e);
-                                }
-                                break 'receiving_additions;
+                        Err((e, Some(uuid))) if uuid == notification_id => {
+                            if let Err(e) = tx.send(Err(e)) {
+                                error!("Error sending notification addition failure {:?}", e);
                             }
+                            break 'receiving_additions;
                         }
                         _ => {}
                     },
@@ -19,16 +19,20 @@ pub struct NotificationDeleter {}
 impl NotificationDeleter {
     async fn listen_to_job_removals(
         storage: Arc<RwLock<Box<dyn NotificationStore + Send + Sync>>>,
-        mut rx_job_delete: Receiver<JobId>,
+        mut rx_job_deleted: Receiver<Result<JobId, (JobSchedulerError, Option<JobId>)>>,
         tx_notification_deleted: Sender<NotificationDeletedResult>,
     ) {
         loop {
-            let val = rx_job_delete.recv().await;
+            let val = rx_job_deleted.recv().await;
             if let Err(e) = val {
-                error!("Error receiving delete jobs {:?}", e);
+                error!("Error receiving deleted jobs {:?}", e);
                 break;
             }
-            let job_id = val.unwrap();
+            // Only clean up once the job is gone, its removal notifications have run by then
+            let job_id = match val.unwrap() {
+                Ok(job_id) => job_id,
+                Err(_) => continue,
+            };
             let mut storage = storage.write().await;
             let guids = storage.list_notification_guids_for_job_id(job_id).await;
             if let Err(e) = guids {
@@ -36,7 +40,6 @@ impl NotificationDeleter {
                 continue;
             }
             let guids = guids.unwrap();
-            // TODO first check for removal callback
             for notification_id in guids {
                 if let Err(e) = storage.delete(notification_id).await {
                     error!("Error deleting notification {:?}", e);
@@ -94,7 +97,7 @@ impl NotificationDeleter {
         &mut self,
         context: &Context,
     ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
-        let rx_job_delete = context.job_delete_tx.subscribe();
+        let rx_job_deleted = context.job_deleted_tx.subscribe();
         let rx_notification_delete = context.notify_delete_tx.subscribe();
         let tx_notification_deleted = context.notify_deleted_tx.clone();
         let storage = context.notification_storage.clone();
@@ -102,7 +105,7 @@ impl NotificationDeleter {
         Box::pin(async move {
             tokio::spawn(NotificationDeleter::listen_to_job_removals(
                 storage.clone(),
-                rx_job_delete,
+                rx_job_deleted,
                 tx_notification_deleted.clone(),
             ));
             tokio::spawn(NotificationDeleter::listen_for_notification_removals(
@@ -132,21 +135,17 @@ impl NotificationDeleter {
             });
             while let Ok(val) = deleted_rx.recv().await {
                 match val {
-                    Ok((uuid, deleted, _)) => {
-                        if uuid == notification_id {
-                            if let Err(e) = tx.send(Ok((uuid, deleted))) {
-                                error!("Error sending notification removal success {:?}", e);
-                            }
-                            break;
+                    Ok((uuid, deleted, _)) if uuid == notification_id => {
+                        if let Err(e) = tx.send(Ok((uuid, deleted))) {
+                            error!("Error sending notification removal success {:?}", e);
                         }
+                        break;
                     }
-                    Err((e, Some(uuid))) => {
-                        if uuid == notification_id {
-                            if let Err(e) = tx.send(Err(e)) {
-                                error!("Error sending removal error {:?}", e);
-                            }
-                            break;
+                    Err((e, Some(uuid))) if uuid == notification_id => {
+                        if let Err(e) = tx.send(Err(e)) {
+                            error!("Error sending removal error {:?}", e);
                         }
+                        break;
                     }
                     _ => {}
                 }
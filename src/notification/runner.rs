@@ -1,4 +1,6 @@
 use crate::context::Context;
+#[cfg(feature = "metrics")]
+use crate::instruments;
 #[cfg(not(feature = "has_bytes"))]
 use crate::job::job_data::JobState;
 #[cfg(feature = "has_bytes")]
@@ -9,6 +11,7 @@ use crate::JobSchedulerError;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::broadcast::Receiver;
 use tokio::sync::RwLock;
 use tracing::error;
@@ -42,6 +45,8 @@ impl NotificationRunner {
                 continue;
             }
             let notifications = notifications.unwrap();
+            #[cfg(feature = "metrics")]
+            instruments::notification(state, notifications.len(), rx.len());
             let mut code = code.write().await;
             for notification_id in notifications {
                 let code = code.get(notification_id).await;
@@ -65,6 +70,40 @@ impl NotificationRunner {
         }
     }
 
+    ///
+    /// Pass the notifications on to the event streams, with the name of the job and, for the
+    /// runs that failed, the error
+    async fn listen_for_events(context: Context, mut rx: Receiver<(Uuid, JobState)>) {
+        loop {
+            let (job_id, state) = match rx.recv().await {
+                Ok(val) => val,
+                Err(RecvError::Lagged(missed)) => {
+                    error!("Missed {} notifications for the event streams", missed);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            if context.events_tx.receiver_count() == 0 {
+                continue;
+            }
+            let name = {
+                let mut w = context.metadata_storage.write().await;
+                match w.get(job_id).await {
+                    Ok(Some(data)) => data.name,
+                    _ => String::new(),
+                }
+            };
+            let error = match state {
+                JobState::Failed | JobState::TimedOut | JobState::Retrying => {
+                    let r = context.stats.read().await;
+                    r.job(&job_id).and_then(|j| j.last_error.clone())
+                }
+                _ => None,
+            };
+            context.send_event(job_id, name, state, error);
+        }
+    }
+
     pub fn init(
         &mut self,
         context: &Context,
@@ -72,11 +111,14 @@ impl NotificationRunner {
         let code = context.notification_code.clone();
         let rx = context.notify_tx.subscribe();
         let storage = context.notification_storage.clone();
+        let events_rx = context.notify_tx.subscribe();
+        let context = context.clone();
 
         Box::pin(async move {
             tokio::spawn(NotificationRunner::listen_for_activations(
                 code, rx, storage,
             ));
+            tokio::spawn(NotificationRunner::listen_for_events(context, events_rx));
             Ok(())
         })
     }
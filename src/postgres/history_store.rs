@@ -0,0 +1,206 @@
+use crate::job::job_data_prost::JobState;
+use crate::postgres::PostgresStore;
+use crate::store::{HistoryStore, RunRecord};
+use crate::JobSchedulerError;
+use chrono::{TimeZone, Utc};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio_postgres::{Client, Row};
+use tracing::error;
+use uuid::Uuid;
+
+const TABLE: &str = "job_run";
+
+///
+/// Keeps every run of a job as a row in Postgres, until the job is removed. The connection is
+/// made on the first run that's kept, and again on the next one after an error.
+#[derive(Clone)]
+pub struct PostgresHistoryStore {
+    pub url: String,
+    pub init_tables: bool,
+    pub table: String,
+    store: Arc<Mutex<PostgresStore>>,
+}
+
+impl PostgresHistoryStore {
+    pub fn new(url: String, init_tables: bool, table: String) -> Self {
+        Self {
+            store: Arc::new(Mutex::new(PostgresStore::Created(url.clone()))),
+            url,
+            init_tables,
+            table,
+        }
+    }
+
+    async fn client(
+        store: &Arc<Mutex<PostgresStore>>,
+        url: &str,
+        init_tables: bool,
+        table: &str,
+    ) -> Result<Arc<tokio::sync::RwLock<Client>>, JobSchedulerError> {
+        let mut w = store.lock().await;
+        if !w.inited() {
+            let inited = PostgresStore::Created(url.to_string()).init().await?;
+            if let (true, PostgresStore::Inited(client)) = (init_tables, &inited) {
+                let client = client.read().await;
+                let sql = "CREATE TABLE IF NOT EXISTS ".to_string()
+                    + table
+                    + " (\
+                            job_id UUID NOT NULL, \
+                            started BIGINT NOT NULL, \
+                            duration_ms BIGINT NOT NULL, \
+                            outcome INTEGER NOT NULL, \
+                            error TEXT \
+                        )";
+                if let Err(e) = client.execute(&*sql, &[]).await {
+                    error!("Error on init Postgres history store {:?}", e);
+                    return Err(JobSchedulerError::CantInit);
+                }
+                let sql = "CREATE INDEX IF NOT EXISTS ".to_string()
+                    + table
+                    + "_job_id_started ON "
+                    + table
+                    + " (job_id, started DESC)";
+                if let Err(e) = client.execute(&*sql, &[]).await {
+                    error!("Error on init Postgres history store {:?}", e);
+                    return Err(JobSchedulerError::CantInit);
+                }
+            }
+            *w = inited;
+        }
+        match &*w {
+            PostgresStore::Created(_) => Err(JobSchedulerError::CantInit),
+            PostgresStore::Inited(client) => Ok(client.clone()),
+        }
+    }
+
+    async fn reset(store: &Arc<Mutex<PostgresStore>>, url: &str) {
+        let mut w = store.lock().await;
+        *w = PostgresStore::Created(url.to_string());
+    }
+
+    fn from_row(row: &Row) -> Option<RunRecord> {
+        let job_id: Uuid = row.get(0);
+        let started: i64 = row.get(1);
+        let duration_ms: i64 = row.get(2);
+        let outcome: i32 = row.get(3);
+        let error: Option<String> = row.get(4);
+        Some(RunRecord {
+            job_id,
+            started: Utc.timestamp_millis_opt(started).single()?,
+            duration: Duration::from_millis(duration_ms.max(0) as u64),
+            outcome: JobState::from_i32(outcome)?,
+            error,
+        })
+    }
+}
+
+impl Default for PostgresHistoryStore {
+    fn default() -> Self {
+        let url = match PostgresStore::default() {
+            PostgresStore::Created(url) => url,
+            PostgresStore::Inited(_) => unreachable!(),
+        };
+        let init_tables = std::env::var("POSTGRES_INIT_HISTORY")
+            .map(|s| s.to_lowercase() == "true")
+            .unwrap_or_default();
+        let table = std::env::var("POSTGRES_HISTORY_TABLE").unwrap_or_else(|_| TABLE.to_string());
+        Self::new(url, init_tables, table)
+    }
+}
+
+impl HistoryStore for PostgresHistoryStore {
+    fn record(
+        &mut self,
+        run: RunRecord,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let url = self.url.clone();
+        let init_tables = self.init_tables;
+        let table = self.table.clone();
+        Box::pin(async move {
+            let client = PostgresHistoryStore::client(&store, &url, init_tables, &table).await?;
+            let sql = "INSERT INTO ".to_string()
+                + &*table
+                + " (job_id, started, duration_ms, outcome, error) VALUES ($1, $2, $3, $4, $5)";
+            let started = run.started.timestamp_millis();
+            let duration_ms = run.duration.as_millis().min(i64::MAX as u128) as i64;
+            let outcome = run.outcome as i32;
+            let inserted = {
+                let client = client.read().await;
+                client
+                    .execute(
+                        &*sql,
+                        &[&run.job_id, &started, &duration_ms, &outcome, &run.error],
+                    )
+                    .await
+            };
+            if let Err(e) = inserted {
+                error!("Error keeping the run of {:?} {:?}", run.job_id, e);
+                PostgresHistoryStore::reset(&store, &url).await;
+                return Err(JobSchedulerError::CantAdd);
+            }
+            Ok(())
+        })
+    }
+
+    fn list(
+        &mut self,
+        job_id: Uuid,
+        limit: usize,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<RunRecord>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let url = self.url.clone();
+        let init_tables = self.init_tables;
+        let table = self.table.clone();
+        Box::pin(async move {
+            let client = PostgresHistoryStore::client(&store, &url, init_tables, &table).await?;
+            let sql = "SELECT job_id, started, duration_ms, outcome, error FROM ".to_string()
+                + &*table
+                + " WHERE job_id = $1 ORDER BY started DESC LIMIT $2";
+            let limit = limit.min(i64::MAX as usize) as i64;
+            let rows = {
+                let client = client.read().await;
+                client.query(&*sql, &[&job_id, &limit]).await
+            };
+            match rows {
+                Ok(rows) => Ok(rows
+                    .iter()
+                    .filter_map(PostgresHistoryStore::from_row)
+                    .collect()),
+                Err(e) => {
+                    error!("Error getting the runs of {:?} {:?}", job_id, e);
+                    PostgresHistoryStore::reset(&store, &url).await;
+                    Err(JobSchedulerError::FetchJob)
+                }
+            }
+        })
+    }
+
+    fn delete(
+        &mut self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let url = self.url.clone();
+        let init_tables = self.init_tables;
+        let table = self.table.clone();
+        Box::pin(async move {
+            let client = PostgresHistoryStore::client(&store, &url, init_tables, &table).await?;
+            let sql = "DELETE FROM ".to_string() + &*table + " WHERE job_id = $1";
+            let deleted = {
+                let client = client.read().await;
+                client.execute(&*sql, &[&job_id]).await
+            };
+            if let Err(e) = deleted {
+                error!("Error removing the runs of {:?} {:?}", job_id, e);
+                PostgresHistoryStore::reset(&store, &url).await;
+                return Err(JobSchedulerError::CantRemove);
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,122 @@
+use crate::postgres::PostgresStore;
+use crate::store::LeaseStore;
+use crate::JobSchedulerError;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::error;
+
+/// "tcs_lead"
+const LOCK_KEY: i64 = 0x7463_735f_6c65_6164;
+
+///
+/// A lease on a Postgres session level advisory lock. The lock is held by the connection of the
+/// lease, so it's let go of by the database as soon as the holder's connection is gone. The
+/// connection is made again on the next renewal after an error.
+#[derive(Clone)]
+pub struct PostgresLeaderLease {
+    pub url: String,
+    pub key: i64,
+    store: Arc<Mutex<PostgresStore>>,
+    held: Arc<AtomicBool>,
+}
+
+impl PostgresLeaderLease {
+    pub fn new(url: String, key: i64) -> Self {
+        Self {
+            store: Arc::new(Mutex::new(PostgresStore::Created(url.clone()))),
+            url,
+            key,
+            held: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+impl Default for PostgresLeaderLease {
+    fn default() -> Self {
+        let url = match PostgresStore::default() {
+            PostgresStore::Created(url) => url,
+            PostgresStore::Inited(_) => unreachable!(),
+        };
+        let key = std::env::var("POSTGRES_LEADER_LOCK_KEY")
+            .ok()
+            .and_then(|key| key.parse().ok())
+            .unwrap_or(LOCK_KEY);
+        Self::new(url, key)
+    }
+}
+
+impl LeaseStore for PostgresLeaderLease {
+    fn acquire(
+        &mut self,
+        _ttl: Duration,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let url = self.url.clone();
+        let key = self.key;
+        let held = self.held.clone();
+        Box::pin(async move {
+            let mut w = store.lock().await;
+            if !w.inited() {
+                *w = PostgresStore::Created(url.clone()).init().await?;
+            }
+            let client = match &*w {
+                PostgresStore::Created(_) => return Err(JobSchedulerError::CantInit),
+                PostgresStore::Inited(client) => client.clone(),
+            };
+            // The lock is the session's until it's unlocked, while it's held the connection only
+            // has to be up still
+            let row = {
+                let client = client.read().await;
+                if held.load(Ordering::Relaxed) {
+                    client.query_one("SELECT true", &[]).await
+                } else {
+                    client
+                        .query_one("SELECT pg_try_advisory_lock($1)", &[&key])
+                        .await
+                }
+            };
+            match row {
+                Ok(row) => {
+                    let locked: bool = row.get(0);
+                    held.store(locked, Ordering::Relaxed);
+                    Ok(locked)
+                }
+                Err(e) => {
+                    error!("Error with the advisory lock {:?}", e);
+                    held.store(false, Ordering::Relaxed);
+                    *w = PostgresStore::Created(url);
+                    Err(JobSchedulerError::LeaseFailed(e.to_string()))
+                }
+            }
+        })
+    }
+
+    fn release(&mut self) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let key = self.key;
+        let held = self.held.clone();
+        Box::pin(async move {
+            if !held.swap(false, Ordering::Relaxed) {
+                return Ok(());
+            }
+            let w = store.lock().await;
+            let client = match &*w {
+                PostgresStore::Created(_) => return Ok(()),
+                PostgresStore::Inited(client) => client.clone(),
+            };
+            let client = client.read().await;
+            client
+                .query_one("SELECT pg_advisory_unlock($1)", &[&key])
+                .await
+                .map(|_| ())
+                .map_err(|e| {
+                    error!("Error unlocking the advisory lock {:?}", e);
+                    JobSchedulerError::LeaseFailed(e.to_string())
+                })
+        })
+    }
+}
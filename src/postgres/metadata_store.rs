@@ -1,8 +1,11 @@
-use crate::job::job_data_prost::{CronJob, JobType, NonCronJob};
+use crate::job::job_data_prost::{
+    After, CronJob, Exclusions, IntervalBounds, JobType, NonCronJob, Overlap,
+};
 use crate::postgres::PostgresStore;
 use crate::store::{DataStore, InitStore, MetaDataStorage};
 use crate::{JobAndNextTick, JobSchedulerError, JobStoredData, JobUuid};
 use chrono::{DateTime, Utc};
+use prost::Message;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -54,7 +57,9 @@ impl DataStore<JobStoredData> for PostgresMetadataStore {
                     let sql = "select \
                         id, last_updated, next_tick, last_tick, job_type, count, \
                         ran, stopped, schedule, repeating, repeated_every, \
-                        extra, time_offset_seconds \
+                        extra, time_offset_seconds, \
+                        overlap_kind, overlap_max_depth, overlap_drop_oldest, jitter_seconds, tags, queue, order_key, timezone, misfire, timeout_ms, name, priority, random_jitter_seconds, \
+                        after_job_id, after_delay_ms, exclusions, interval_bounds, paused \
                      from "
                         .to_string()
                         + &*table
@@ -92,12 +97,15 @@ impl DataStore<JobStoredData> for PostgresMetadataStore {
                         + " (\
                         id, last_updated, next_tick, job_type, count, \
                         ran, stopped, schedule, repeating, repeated_every, \
-                        extra, last_tick, time_offset_seconds \
+                        extra, last_tick, time_offset_seconds, \
+                        overlap_kind, overlap_max_depth, overlap_drop_oldest, jitter_seconds, tags, queue, order_key, timezone, misfire, timeout_ms, name, priority, random_jitter_seconds, \
+                        after_job_id, after_delay_ms, exclusions, interval_bounds, paused \
                     )\
                     VALUES (\
                         $1, $2, $3, $4, $5, \
                         $6, $7, $8, $9, $10,\
-                        $11, $12, $13 \
+                        $11, $12, $13, $14, $15, \
+                        $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31 \
                     )\
                     ON CONFLICT (id) \
                     DO \
@@ -105,7 +113,12 @@ impl DataStore<JobStoredData> for PostgresMetadataStore {
                         SET \
                             last_updated=$2, next_tick=$3, job_type=$4, count=$5, \
                             ran=$6, stopped=$7, schedule=$8, repeating=$9, repeated_every=$10, \
-                            extra=$11, last_tick=$12, time_offset_seconds=$13
+                            extra=$11, last_tick=$12, time_offset_seconds=$13, \
+                            overlap_kind=$14, overlap_max_depth=$15, overlap_drop_oldest=$16, \
+                            jitter_seconds=$17, tags=$18, queue=$19, order_key=$20, timezone=$21, misfire=$22, \
+                            timeout_ms=$23, name=$24, priority=$25, \
+                            random_jitter_seconds=$26, after_job_id=$27, after_delay_ms=$28, \
+                            exclusions=$29, interval_bounds=$30, paused=$31
                     ";
                     let last_updated = data.last_updated.as_ref().map(|i| *i as i64);
                     let next_tick = data.next_tick as i64;
@@ -128,6 +141,27 @@ impl DataStore<JobStoredData> for PostgresMetadataStore {
                     let extra = data.extra;
                     let last_tick = data.last_tick.as_ref().map(|i| *i as i64);
                     let time_offset_seconds = data.time_offset_seconds;
+                    let overlap_kind = data.overlap.as_ref().map(|o| o.kind);
+                    let overlap_max_depth = data.overlap.as_ref().map(|o| o.max_depth as i32);
+                    let overlap_drop_oldest = data.overlap.as_ref().map(|o| o.drop_oldest);
+                    let jitter_seconds = data.jitter_seconds as i32;
+                    let tags = data.tags;
+                    let queue = data.queue;
+                    let order_key = data.order_key;
+                    let timezone = data.timezone;
+                    let misfire = data.misfire;
+                    let timeout_ms = data.timeout_ms as i64;
+                    let name = data.name;
+                    let priority = data.priority;
+                    let random_jitter_seconds = data.random_jitter_seconds as i32;
+                    let after_job_id = data
+                        .after
+                        .as_ref()
+                        .and_then(|a| a.job_id.as_ref())
+                        .map(Uuid::from);
+                    let after_delay_ms = data.after.as_ref().map(|a| a.delay_ms as i64);
+                    let exclusions = data.exclusions.as_ref().map(|e| e.encode_to_vec());
+                    let interval_bounds = data.interval_bounds.as_ref().map(|b| b.encode_to_vec());
 
                     let val = store
                         .query(
@@ -146,6 +180,24 @@ impl DataStore<JobStoredData> for PostgresMetadataStore {
                                 &extra,
                                 &last_tick,
                                 &time_offset_seconds,
+                                &overlap_kind,
+                                &overlap_max_depth,
+                                &overlap_drop_oldest,
+                                &jitter_seconds,
+                                &tags,
+                                &queue,
+                                &order_key,
+                                &timezone,
+                                &misfire,
+                                &timeout_ms,
+                                &name,
+                                &priority,
+                                &random_jitter_seconds,
+                                &after_job_id,
+                                &after_delay_ms,
+                                &exclusions,
+                                &interval_bounds,
+                                &data.paused,
                             ],
                         )
                         .await;
@@ -193,7 +245,9 @@ impl From<Row> for JobStoredData {
         /*
         id, last_updated, next_tick, last_tick, job_type, count, \
                         ran, stopped, schedule, repeating, repeated_every, \
-                        extra, time_offset_seconds
+                        extra, time_offset_seconds, \
+                        overlap_kind, overlap_max_depth, overlap_drop_oldest, jitter_seconds, tags, queue, order_key, timezone, misfire, timeout_ms, name, priority, random_jitter_seconds,
+                        after_job_id, after_delay_ms, exclusions, interval_bounds, paused
          */
         let id: Uuid = row.get(0);
         let last_updated = row.try_get(1).ok().map(|i: i64| i as u64);
@@ -235,6 +289,54 @@ impl From<Row> for JobStoredData {
         };
         let extra = row.try_get(11).unwrap_or_default();
         let time_offset_seconds = row.try_get(12).unwrap_or_default();
+        let overlap = row.try_get(13).ok().map(|kind: i32| Overlap {
+            kind,
+            max_depth: row
+                .try_get(14)
+                .ok()
+                .map(|i: i32| i as u32)
+                .unwrap_or_default(),
+            drop_oldest: row.try_get(15).unwrap_or_default(),
+        });
+        let jitter_seconds = row
+            .try_get(16)
+            .ok()
+            .map(|i: i32| i as u32)
+            .unwrap_or_default();
+        let tags = row.try_get(17).unwrap_or_default();
+        let queue = row.try_get(18).unwrap_or_default();
+        let order_key = row.try_get(19).unwrap_or_default();
+        let timezone = row.try_get(20).unwrap_or_default();
+        let misfire = row.try_get(21).unwrap_or_default();
+        let timeout_ms = row
+            .try_get(22)
+            .ok()
+            .map(|i: i64| i as u64)
+            .unwrap_or_default();
+        let name = row.try_get(23).unwrap_or_default();
+        let priority = row.try_get(24).unwrap_or_default();
+        let random_jitter_seconds = row
+            .try_get(25)
+            .ok()
+            .map(|i: i32| i as u32)
+            .unwrap_or_default();
+        let after = row.try_get(26).ok().map(|job_id: Uuid| After {
+            job_id: Some(job_id.into()),
+            delay_ms: row
+                .try_get(27)
+                .ok()
+                .map(|i: i64| i as u64)
+                .unwrap_or_default(),
+        });
+        let exclusions = row
+            .try_get(28)
+            .ok()
+            .and_then(|bytes: Vec<u8>| Exclusions::decode(&*bytes).ok());
+        let interval_bounds = row
+            .try_get(29)
+            .ok()
+            .and_then(|bytes: Vec<u8>| IntervalBounds::decode(&*bytes).ok());
+        let paused = row.try_get(30).unwrap_or_default();
 
         Self {
             id: Some(id.into()),
@@ -248,6 +350,21 @@ impl From<Row> for JobStoredData {
             stopped,
             job,
             time_offset_seconds,
+            overlap,
+            jitter_seconds,
+            tags,
+            queue,
+            order_key,
+            timezone,
+            misfire,
+            timeout_ms,
+            name,
+            priority,
+            random_jitter_seconds,
+            after,
+            exclusions,
+            interval_bounds,
+            paused,
         }
     }
 }
@@ -285,6 +402,24 @@ impl InitStore for PostgresMetadataStore {
                                             repeated_every BIGINT,\
                                             time_offset_seconds INTEGER, \
                                             extra BYTEA, \
+                                            overlap_kind INTEGER, \
+                                            overlap_max_depth INTEGER, \
+                                            overlap_drop_oldest BOOL, \
+                                            jitter_seconds INTEGER, \
+                                            tags TEXT[], \
+                                            queue TEXT, \
+                                            order_key BIGINT, \
+                                            timezone TEXT, \
+                                            misfire INTEGER, \
+                                            timeout_ms BIGINT, \
+                                            name TEXT, \
+                                            priority INTEGER, \
+                                            random_jitter_seconds INTEGER, \
+                                            after_job_id UUID, \
+                                            after_delay_ms BIGINT, \
+                                            exclusions BYTEA, \
+                                            interval_bounds BYTEA, \
+                                            paused BOOL, \
                                             CONSTRAINT pk_metadata PRIMARY KEY (id) \
                                         )";
                                 let create = v.execute(&*sql, &[]).await;
@@ -292,6 +427,33 @@ impl InitStore for PostgresMetadataStore {
                                     error!("Error on init Postgres Metadata store {:?}", e);
                                     return Err(JobSchedulerError::CantInit);
                                 }
+                                // Tables created by earlier versions miss the newer columns
+                                let sql = "ALTER TABLE ".to_string()
+                                    + &*table
+                                    + " \
+                                        ADD COLUMN IF NOT EXISTS overlap_kind INTEGER, \
+                                        ADD COLUMN IF NOT EXISTS overlap_max_depth INTEGER, \
+                                        ADD COLUMN IF NOT EXISTS overlap_drop_oldest BOOL, \
+                                        ADD COLUMN IF NOT EXISTS jitter_seconds INTEGER, \
+                                        ADD COLUMN IF NOT EXISTS tags TEXT[], \
+                                        ADD COLUMN IF NOT EXISTS queue TEXT, \
+                                        ADD COLUMN IF NOT EXISTS order_key BIGINT, \
+                                        ADD COLUMN IF NOT EXISTS timezone TEXT, \
+                                        ADD COLUMN IF NOT EXISTS misfire INTEGER, \
+                                        ADD COLUMN IF NOT EXISTS timeout_ms BIGINT, \
+                                        ADD COLUMN IF NOT EXISTS name TEXT, \
+                                        ADD COLUMN IF NOT EXISTS priority INTEGER, \
+                                        ADD COLUMN IF NOT EXISTS random_jitter_seconds INTEGER, \
+                                        ADD COLUMN IF NOT EXISTS after_job_id UUID, \
+                                        ADD COLUMN IF NOT EXISTS after_delay_ms BIGINT, \
+                                        ADD COLUMN IF NOT EXISTS exclusions BYTEA, \
+                                        ADD COLUMN IF NOT EXISTS interval_bounds BYTEA, \
+                                        ADD COLUMN IF NOT EXISTS paused BOOL";
+                                let alter = v.execute(&*sql, &[]).await;
+                                if let Err(e) = alter {
+                                    error!("Error on updating Postgres Metadata store {:?}", e);
+                                    return Err(JobSchedulerError::CantInit);
+                                }
                             }
                         }
                         *w = v;
@@ -332,7 +494,7 @@ impl MetaDataStorage for PostgresMetadataStore {
                     let store = store.read().await;
                     let now = Utc::now().timestamp();
                     let sql = "SELECT \
-                            id, job_type, next_tick, last_tick \
+                            id, job_type, next_tick, last_tick, order_key, priority \
                         FROM "
                         .to_string()
                         + &*table
@@ -354,12 +516,16 @@ impl MetaDataStorage for PostgresMetadataStore {
                                     .map(|i: i64| i as u64)
                                     .unwrap_or_default();
                                 let last_tick = row.try_get(3).ok().map(|i: i64| i as u64);
+                                let order_key = row.try_get(4).unwrap_or_default();
+                                let priority = row.try_get(5).unwrap_or_default();
 
                                 JobAndNextTick {
                                     id: Some(id),
                                     job_type,
                                     next_tick,
                                     last_tick,
+                                    order_key,
+                                    priority,
                                 }
                             })
                             .collect::<Vec<_>>()),
@@ -411,6 +577,14 @@ impl MetaDataStorage for PostgresMetadataStore {
 
     fn time_till_next_job(
         &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<std::time::Duration>, JobSchedulerError>> + Send>>
+    {
+        self.time_till_next_job_at(Utc::now())
+    }
+
+    fn time_till_next_job_at(
+        &mut self,
+        now: DateTime<Utc>,
     ) -> Pin<Box<dyn Future<Output = Result<Option<Duration>, JobSchedulerError>> + Send>> {
         let store = self.store.clone();
         let table = self.table.clone();
@@ -420,7 +594,7 @@ impl MetaDataStorage for PostgresMetadataStore {
                 PostgresStore::Created(_) => Err(JobSchedulerError::CouldNotGetTimeUntilNextTick),
                 PostgresStore::Inited(store) => {
                     let store = store.read().await;
-                    let now = Utc::now().timestamp();
+                    let now = now.timestamp();
                     let sql = "SELECT \
                             next_tick \
                         FROM "
@@ -449,4 +623,37 @@ impl MetaDataStorage for PostgresMetadataStore {
             }
         })
     }
+
+    fn list_jobs(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobStoredData>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                PostgresStore::Created(_) => Err(JobSchedulerError::ErrorLoadingJob),
+                PostgresStore::Inited(store) => {
+                    let store = store.read().await;
+                    let sql = "select \
+                        id, last_updated, next_tick, last_tick, job_type, count, \
+                        ran, stopped, schedule, repeating, repeated_every, \
+                        extra, time_offset_seconds, \
+                        overlap_kind, overlap_max_depth, overlap_drop_oldest, jitter_seconds, tags, queue, order_key, timezone, misfire, timeout_ms, name, priority, random_jitter_seconds, \
+                        after_job_id, after_delay_ms, exclusions, interval_bounds, paused \
+                     from "
+                        .to_string()
+                        + &*table;
+                    let rows = store.query(&*sql, &[]).await;
+                    match rows {
+                        Ok(rows) => Ok(rows.into_iter().map(|row| row.into()).collect()),
+                        Err(e) => {
+                            error!("Error listing jobs {:?}", e);
+                            Err(JobSchedulerError::ErrorLoadingJob)
+                        }
+                    }
+                }
+            }
+        })
+    }
 }
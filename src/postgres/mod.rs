@@ -1,3 +1,5 @@
+mod history_store;
+mod leader_lease;
 mod metadata_store;
 mod notification_store;
 
@@ -9,6 +11,8 @@ use tokio::sync::RwLock;
 use tokio_postgres::{Client, NoTls};
 use tracing::error;
 
+pub use history_store::PostgresHistoryStore;
+pub use leader_lease::PostgresLeaderLease;
 pub use metadata_store::PostgresMetadataStore;
 pub use notification_store::PostgresNotificationStore;
 
@@ -0,0 +1,160 @@
+use crate::job::job_data_prost::JobState;
+use crate::redis::{key_prefix, RedisStore};
+use crate::store::{HistoryStore, RunRecord};
+use crate::JobSchedulerError;
+use chrono::{TimeZone, Utc};
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::error;
+use uuid::Uuid;
+
+const RUNS_PRE: &str = "runs";
+const KEEP: usize = 100;
+
+///
+/// Keeps the last `keep` runs of every job in a Redis list per job, until the job is removed.
+/// The connection is made on the first run that's kept.
+#[derive(Clone)]
+pub struct RedisHistoryStore {
+    pub store: Arc<RwLock<RedisStore>>,
+    pub prefix: String,
+    pub keep: usize,
+}
+
+impl Default for RedisHistoryStore {
+    fn default() -> Self {
+        let keep = std::env::var("REDIS_HISTORY_KEEP")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(KEEP);
+        Self {
+            store: Arc::new(RwLock::new(RedisStore::default())),
+            prefix: key_prefix(),
+            keep,
+        }
+    }
+}
+
+impl RedisHistoryStore {
+    fn key(&self, id: Uuid) -> String {
+        format!("{}:{}:{}", self.prefix, RUNS_PRE, id)
+    }
+
+    async fn connection(
+        store: &Arc<RwLock<RedisStore>>,
+    ) -> Result<ConnectionManager, JobSchedulerError> {
+        let mut w = store.write().await;
+        if !w.inited() {
+            *w = w.clone().init().await?;
+        }
+        match &*w {
+            RedisStore::Created(_) => Err(JobSchedulerError::CantInit),
+            RedisStore::Inited(_, connection) => Ok(connection.clone()),
+        }
+    }
+
+    ///
+    /// `started:duration_ms:outcome`, with `:error` after it when there is one
+    fn encode(run: &RunRecord) -> String {
+        let mut value = format!(
+            "{}:{}:{}",
+            run.started.timestamp_millis(),
+            run.duration.as_millis(),
+            run.outcome as i32
+        );
+        if let Some(error) = &run.error {
+            value.push(':');
+            value.push_str(error);
+        }
+        value
+    }
+
+    fn decode(job_id: Uuid, value: &str) -> Option<RunRecord> {
+        let mut fields = value.splitn(4, ':');
+        let started = fields.next()?.parse().ok()?;
+        let duration_ms = fields.next()?.parse().ok()?;
+        let outcome = fields.next()?.parse().ok()?;
+        Some(RunRecord {
+            job_id,
+            started: Utc.timestamp_millis_opt(started).single()?,
+            duration: Duration::from_millis(duration_ms),
+            outcome: JobState::from_i32(outcome)?,
+            error: fields.next().map(|error| error.to_string()),
+        })
+    }
+}
+
+impl HistoryStore for RedisHistoryStore {
+    fn record(
+        &mut self,
+        run: RunRecord,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let key = self.key(run.job_id);
+        let keep = self.keep;
+        Box::pin(async move {
+            if keep == 0 {
+                return Ok(());
+            }
+            let mut connection = RedisHistoryStore::connection(&store).await?;
+            redis::pipe()
+                .atomic()
+                .lpush(&key, RedisHistoryStore::encode(&run))
+                .ignore()
+                .ltrim(&key, 0, keep as isize - 1)
+                .ignore()
+                .query_async::<_, ()>(&mut connection)
+                .await
+                .map_err(|e| {
+                    error!("Error keeping the run of {:?} {:?}", run.job_id, e);
+                    JobSchedulerError::CantAdd
+                })
+        })
+    }
+
+    fn list(
+        &mut self,
+        job_id: Uuid,
+        limit: usize,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<RunRecord>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let key = self.key(job_id);
+        Box::pin(async move {
+            if limit == 0 {
+                return Ok(vec![]);
+            }
+            let mut connection = RedisHistoryStore::connection(&store).await?;
+            let values: Vec<String> = connection
+                .lrange(&key, 0, limit.min(isize::MAX as usize) as isize - 1)
+                .await
+                .map_err(|e| {
+                    error!("Error getting the runs of {:?} {:?}", job_id, e);
+                    JobSchedulerError::FetchJob
+                })?;
+            Ok(values
+                .iter()
+                .filter_map(|value| RedisHistoryStore::decode(job_id, value))
+                .collect())
+        })
+    }
+
+    fn delete(
+        &mut self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let key = self.key(job_id);
+        Box::pin(async move {
+            let mut connection = RedisHistoryStore::connection(&store).await?;
+            connection.del::<_, ()>(&key).await.map_err(|e| {
+                error!("Error removing the runs of {:?} {:?}", job_id, e);
+                JobSchedulerError::CantRemove
+            })
+        })
+    }
+}
@@ -0,0 +1,418 @@
+use crate::job::job_data_prost::JobType;
+use crate::redis::{key_prefix, RedisStore};
+use crate::store::{DataStore, InitStore, MetaDataStorage};
+use crate::{JobAndNextTick, JobSchedulerError, JobStoredData};
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use prost::Message;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use tokio::sync::RwLock;
+use tracing::error;
+use uuid::Uuid;
+
+const LIST_NAME: &str = "jobs";
+const METADATA_PRE: &str = "job";
+const CHANNEL: &str = "job_changes";
+
+///
+/// A change to a job in the store, published to the other scheduler instances sharing it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RedisJobChange {
+    Updated(Uuid),
+    Removed(Uuid),
+}
+
+impl RedisJobChange {
+    fn message(&self) -> String {
+        match self {
+            RedisJobChange::Updated(id) => format!("updated:{}", id),
+            RedisJobChange::Removed(id) => format!("removed:{}", id),
+        }
+    }
+
+    fn parse(message: &str) -> Option<Self> {
+        let (change, id) = message.split_once(':')?;
+        let id = Uuid::parse_str(id).ok()?;
+        match change {
+            "updated" => Some(RedisJobChange::Updated(id)),
+            "removed" => Some(RedisJobChange::Removed(id)),
+            _ => None,
+        }
+    }
+}
+
+///
+/// A Redis backed metadata store. Every job is kept as its own key, with a set of the ids of all
+/// jobs next to it. One shot jobs expire `one_shot_grace` after their fire, so they don't pile up
+/// when the scheduler that would remove them after their run is gone. Every change is published
+/// on a channel, see `changes`.
+#[derive(Clone)]
+pub struct RedisMetadataStore {
+    pub store: Arc<RwLock<RedisStore>>,
+    pub prefix: String,
+    pub one_shot_grace: Duration,
+}
+
+impl Default for RedisMetadataStore {
+    fn default() -> Self {
+        let one_shot_grace = std::env::var("REDIS_ONE_SHOT_GRACE_SECONDS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(60 * 60));
+        Self {
+            store: Arc::new(RwLock::new(RedisStore::default())),
+            prefix: key_prefix(),
+            one_shot_grace,
+        }
+    }
+}
+
+impl RedisMetadataStore {
+    fn key(&self, id: Uuid) -> String {
+        format!("{}:{}:{}", self.prefix, METADATA_PRE, id)
+    }
+
+    fn list_key(&self) -> String {
+        format!("{}:{}", self.prefix, LIST_NAME)
+    }
+
+    fn channel(&self) -> String {
+        format!("{}:{}", self.prefix, CHANNEL)
+    }
+
+    async fn connection(
+        store: &Arc<RwLock<RedisStore>>,
+        error: JobSchedulerError,
+    ) -> Result<ConnectionManager, JobSchedulerError> {
+        let r = store.read().await;
+        match &*r {
+            RedisStore::Created(_) => Err(error),
+            RedisStore::Inited(_, connection) => Ok(connection.clone()),
+        }
+    }
+
+    ///
+    /// When the key of a job expires, for one shot jobs
+    fn expires_at(data: &JobStoredData, grace: Duration) -> Option<i64> {
+        if data.job_type != JobType::OneShot as i32 {
+            return None;
+        }
+        let fires_at = match data.next_tick {
+            0 => Utc::now().timestamp(),
+            next_tick => next_tick as i64,
+        };
+        Some(fires_at.saturating_add(grace.as_secs() as i64))
+    }
+
+    fn put(
+        &self,
+        data: JobStoredData,
+        error: JobSchedulerError,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let uuid: Uuid = data.id.as_ref().unwrap().into();
+        let key = self.key(uuid);
+        let list_key = self.list_key();
+        let channel = self.channel();
+        let expires_at = RedisMetadataStore::expires_at(&data, self.one_shot_grace);
+        Box::pin(async move {
+            let mut connection = RedisMetadataStore::connection(&store, error.clone()).await?;
+            let mut pipe = redis::pipe();
+            pipe.atomic()
+                .set(&key, data.encode_to_vec())
+                .ignore()
+                .sadd(&list_key, uuid.to_string())
+                .ignore();
+            if let Some(expires_at) = expires_at {
+                pipe.expire_at(&key, expires_at.max(0) as usize).ignore();
+            }
+            pipe.publish(&channel, RedisJobChange::Updated(uuid).message())
+                .ignore();
+            pipe.query_async::<_, ()>(&mut connection)
+                .await
+                .map_err(|e| {
+                    error!("Error saving {:?} {:?}", uuid, e);
+                    error
+                })
+        })
+    }
+
+    ///
+    /// Every job in the store. The ids of jobs whose key expired are taken out of the list.
+    fn all(
+        &self,
+        error: JobSchedulerError,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobStoredData>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let list_key = self.list_key();
+        let this = self.clone();
+        Box::pin(async move {
+            let mut connection = RedisMetadataStore::connection(&store, error.clone()).await?;
+            let ids: Vec<String> = connection.smembers(&list_key).await.map_err(|e| {
+                error!("Error getting list of guids {:?}", e);
+                JobSchedulerError::CantListGuids
+            })?;
+            let ids = ids
+                .iter()
+                .filter_map(|id| Uuid::parse_str(id).ok())
+                .collect::<Vec<_>>();
+            if ids.is_empty() {
+                return Ok(vec![]);
+            }
+            let keys = ids.iter().map(|id| this.key(*id)).collect::<Vec<_>>();
+            let values: Vec<Option<Vec<u8>>> = redis::cmd("MGET")
+                .arg(&keys)
+                .query_async(&mut connection)
+                .await
+                .map_err(|e| {
+                    error!("Error getting jobs {:?}", e);
+                    error
+                })?;
+            let expired = ids
+                .iter()
+                .zip(values.iter())
+                .filter(|(_, value)| value.is_none())
+                .map(|(id, _)| id.to_string())
+                .collect::<Vec<_>>();
+            if !expired.is_empty() {
+                if let Err(e) = connection.srem::<_, _, ()>(&list_key, &expired).await {
+                    error!("Error removing expired jobs from the list {:?}", e);
+                }
+            }
+            Ok(values
+                .into_iter()
+                .flatten()
+                .filter_map(|value| JobStoredData::decode(value.as_slice()).ok())
+                .collect())
+        })
+    }
+
+    ///
+    /// The changes to the jobs in the store made by any scheduler sharing it, this one included,
+    /// for instance to refresh caches or to pick up jobs added elsewhere. The channel closes when
+    /// the subscription to Redis is lost.
+    pub fn changes(
+        &self,
+    ) -> Pin<
+        Box<
+            dyn Future<Output = Result<UnboundedReceiver<RedisJobChange>, JobSchedulerError>>
+                + Send,
+        >,
+    > {
+        let store = self.store.clone();
+        let channel = self.channel();
+        Box::pin(async move {
+            let client = {
+                let r = store.read().await;
+                match &*r {
+                    RedisStore::Created(_) => return Err(JobSchedulerError::CantInit),
+                    RedisStore::Inited(client, _) => client.as_ref().clone(),
+                }
+            };
+            let connection = client.get_async_connection().await.map_err(|e| {
+                error!("Error connecting to Redis for the job changes {:?}", e);
+                JobSchedulerError::CantInit
+            })?;
+            let mut pubsub = connection.into_pubsub();
+            pubsub.subscribe(&channel).await.map_err(|e| {
+                error!("Error subscribing to the job changes {:?}", e);
+                JobSchedulerError::CantInit
+            })?;
+            let (tx, rx) = unbounded_channel();
+            tokio::spawn(async move {
+                let mut messages = pubsub.into_on_message();
+                while let Some(message) = messages.next().await {
+                    let payload: String = match message.get_payload() {
+                        Ok(payload) => payload,
+                        Err(e) => {
+                            error!("Error reading a job change {:?}", e);
+                            continue;
+                        }
+                    };
+                    match RedisJobChange::parse(&payload) {
+                        Some(change) => {
+                            if tx.send(change).is_err() {
+                                break;
+                            }
+                        }
+                        None => error!("Unknown job change {:?}", payload),
+                    }
+                }
+            });
+            Ok(rx)
+        })
+    }
+}
+
+impl DataStore<JobStoredData> for RedisMetadataStore {
+    fn get(
+        &mut self,
+        id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<JobStoredData>, JobSchedulerError>> + Send>>
+    {
+        let store = self.store.clone();
+        let key = self.key(id);
+        Box::pin(async move {
+            let mut connection =
+                RedisMetadataStore::connection(&store, JobSchedulerError::GetJobData).await?;
+            let value: Option<Vec<u8>> = connection.get(&key).await.map_err(|e| {
+                error!("Error getting data {:?}", e);
+                JobSchedulerError::GetJobData
+            })?;
+            Ok(value.and_then(|v| JobStoredData::decode(v.as_slice()).ok()))
+        })
+    }
+
+    fn add_or_update(
+        &mut self,
+        data: JobStoredData,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        self.put(data, JobSchedulerError::CantAdd)
+    }
+
+    fn delete(
+        &mut self,
+        guid: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let key = self.key(guid);
+        let list_key = self.list_key();
+        let channel = self.channel();
+        Box::pin(async move {
+            let mut connection =
+                RedisMetadataStore::connection(&store, JobSchedulerError::CantRemove).await?;
+            redis::pipe()
+                .atomic()
+                .del(&key)
+                .ignore()
+                .srem(&list_key, guid.to_string())
+                .ignore()
+                .publish(&channel, RedisJobChange::Removed(guid).message())
+                .ignore()
+                .query_async::<_, ()>(&mut connection)
+                .await
+                .map_err(|e| {
+                    error!("Error removing {:?} {:?}", guid, e);
+                    JobSchedulerError::CantRemove
+                })
+        })
+    }
+}
+
+impl InitStore for RedisMetadataStore {
+    fn init(&mut self) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let mut w = store.write().await;
+            if w.inited() {
+                return Ok(());
+            }
+            let inited = w.clone().init().await?;
+            *w = inited;
+            Ok(())
+        })
+    }
+
+    fn inited(&mut self) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let r = store.read().await;
+            Ok(r.inited())
+        })
+    }
+}
+
+impl MetaDataStorage for RedisMetadataStore {
+    fn list_next_ticks(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>> {
+        let all = self.all(JobSchedulerError::CantListNextTicks);
+        Box::pin(async move {
+            let list = all
+                .await?
+                .into_iter()
+                .map(|jd| JobAndNextTick {
+                    id: jd.id,
+                    job_type: jd.job_type,
+                    next_tick: jd.next_tick,
+                    last_tick: jd.last_tick,
+                    order_key: jd.order_key,
+                    priority: jd.priority,
+                })
+                .collect::<Vec<_>>();
+            Ok(list)
+        })
+    }
+
+    fn set_next_and_last_tick(
+        &mut self,
+        guid: Uuid,
+        next_tick: Option<DateTime<Utc>>,
+        last_tick: Option<DateTime<Utc>>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let get = self.get(guid);
+        let this = self.clone();
+        Box::pin(async move {
+            match get.await {
+                Ok(Some(mut val)) => {
+                    val.next_tick = match next_tick {
+                        Some(next_tick) => next_tick.timestamp(),
+                        None => 0,
+                    } as u64;
+                    val.last_tick = last_tick.map(|lt| lt.timestamp() as u64);
+                    this.put(val, JobSchedulerError::UpdateJobData).await
+                }
+                Ok(None) => {
+                    error!("Could not get value to update");
+                    Err(JobSchedulerError::UpdateJobData)
+                }
+                Err(e) => {
+                    error!("Could not get value to update {:?}", e);
+                    Err(JobSchedulerError::UpdateJobData)
+                }
+            }
+        })
+    }
+
+    fn time_till_next_job(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<std::time::Duration>, JobSchedulerError>> + Send>>
+    {
+        self.time_till_next_job_at(Utc::now())
+    }
+
+    fn time_till_next_job_at(
+        &mut self,
+        now: DateTime<Utc>,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Duration>, JobSchedulerError>> + Send>> {
+        let all = self.all(JobSchedulerError::CantGetTimeUntil);
+        Box::pin(async move {
+            let now = now.timestamp() as u64;
+            let ret = all
+                .await?
+                .into_iter()
+                .filter_map(|jd| match jd.next_tick {
+                    0 => None,
+                    i if i > now => Some(i),
+                    _ => None,
+                })
+                .min()
+                .map(|t| t - now)
+                .map(Duration::from_secs);
+            Ok(ret)
+        })
+    }
+
+    fn list_jobs(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobStoredData>, JobSchedulerError>> + Send>> {
+        self.all(JobSchedulerError::CantListGuids)
+    }
+}
@@ -0,0 +1,71 @@
+mod history_store;
+mod metadata_store;
+mod notification_store;
+
+use crate::JobSchedulerError;
+use redis::aio::ConnectionManager;
+use redis::Client;
+use std::future::Future;
+use std::pin::Pin;
+use tracing::error;
+
+pub use history_store::RedisHistoryStore;
+pub use metadata_store::{RedisJobChange, RedisMetadataStore};
+pub use notification_store::RedisNotificationStore;
+
+///
+/// A connection to Redis, made when the store is inited. The `ConnectionManager` reconnects on
+/// its own when the connection is lost.
+#[derive(Clone)]
+pub enum RedisStore {
+    Created(String),
+    Inited(Box<Client>, ConnectionManager),
+}
+
+impl RedisStore {
+    pub fn inited(&self) -> bool {
+        matches!(self, RedisStore::Inited(..))
+    }
+}
+
+impl Default for RedisStore {
+    fn default() -> Self {
+        let url = std::env::var("REDIS_URL").unwrap_or_else(|_| {
+            let host = std::env::var("REDIS_HOST").unwrap_or_else(|_| "localhost".to_string());
+            let port = std::env::var("REDIS_PORT").unwrap_or_else(|_| "6379".to_string());
+            "redis://".to_string() + &*host + ":" + &*port
+        });
+        Self::Created(url)
+    }
+}
+
+impl RedisStore {
+    pub fn init(
+        self,
+    ) -> Pin<Box<dyn Future<Output = Result<RedisStore, JobSchedulerError>> + Send>> {
+        Box::pin(async move {
+            match self {
+                RedisStore::Created(url) => {
+                    let client = Client::open(&*url).map_err(|e| {
+                        error!("Error with the Redis url {:?}", e);
+                        JobSchedulerError::CantInit
+                    })?;
+                    let connection = ConnectionManager::new(client.clone()).await.map_err(|e| {
+                        error!("Error connecting to Redis {:?}", e);
+                        JobSchedulerError::CantInit
+                    })?;
+                    Ok(RedisStore::Inited(Box::new(client), connection))
+                }
+                RedisStore::Inited(client, connection) => {
+                    Ok(RedisStore::Inited(client, connection))
+                }
+            }
+        })
+    }
+}
+
+///
+/// The prefix of the keys of the stores, so several schedulers can share a Redis database
+pub(crate) fn key_prefix() -> String {
+    std::env::var("REDIS_KEY_PREFIX").unwrap_or_else(|_| "tcs".to_string())
+}
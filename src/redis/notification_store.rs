@@ -0,0 +1,284 @@
+use crate::job::job_data_prost::{JobState, NotificationData};
+use crate::job::{JobId, NotificationId};
+use crate::redis::{key_prefix, RedisStore};
+use crate::store::{DataStore, InitStore, NotificationStore};
+use crate::JobSchedulerError;
+use prost::Message;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::error;
+use uuid::Uuid;
+
+const NOTIFICATION_PRE: &str = "notification";
+const JOB_NOTIFICATIONS_PRE: &str = "job_notifications";
+
+///
+/// A Redis backed notification store. Every notification is kept as its own key, with a set of
+/// the ids of the notifications of each job.
+#[derive(Clone)]
+pub struct RedisNotificationStore {
+    pub store: Arc<RwLock<RedisStore>>,
+    pub prefix: String,
+}
+
+impl Default for RedisNotificationStore {
+    fn default() -> Self {
+        Self {
+            store: Arc::new(RwLock::new(RedisStore::default())),
+            prefix: key_prefix(),
+        }
+    }
+}
+
+impl RedisNotificationStore {
+    fn key(&self, id: Uuid) -> String {
+        format!("{}:{}:{}", self.prefix, NOTIFICATION_PRE, id)
+    }
+
+    fn job_key(&self, job_id: Uuid) -> String {
+        format!("{}:{}:{}", self.prefix, JOB_NOTIFICATIONS_PRE, job_id)
+    }
+
+    async fn connection(
+        store: &Arc<RwLock<RedisStore>>,
+        error: JobSchedulerError,
+    ) -> Result<ConnectionManager, JobSchedulerError> {
+        let r = store.read().await;
+        match &*r {
+            RedisStore::Created(_) => Err(error),
+            RedisStore::Inited(_, connection) => Ok(connection.clone()),
+        }
+    }
+
+    fn job_id(data: &NotificationData) -> Option<Uuid> {
+        data.job_id
+            .as_ref()
+            .and_then(|j| j.job_id.as_ref())
+            .map(|id| id.into())
+    }
+}
+
+impl DataStore<NotificationData> for RedisNotificationStore {
+    fn get(
+        &mut self,
+        id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<NotificationData>, JobSchedulerError>> + Send>>
+    {
+        let store = self.store.clone();
+        let key = self.key(id);
+        Box::pin(async move {
+            let mut connection =
+                RedisNotificationStore::connection(&store, JobSchedulerError::GetJobData).await?;
+            let value: Option<Vec<u8>> = connection.get(&key).await.map_err(|e| {
+                error!("Error getting data {:?}", e);
+                JobSchedulerError::GetJobData
+            })?;
+            Ok(value.and_then(|v| NotificationData::decode(v.as_slice()).ok()))
+        })
+    }
+
+    fn add_or_update(
+        &mut self,
+        data: NotificationData,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let notification_id: Uuid = data
+            .job_id
+            .as_ref()
+            .and_then(|j| j.notification_id.as_ref())
+            .unwrap()
+            .into();
+        let job_id = RedisNotificationStore::job_id(&data).unwrap();
+        let key = self.key(notification_id);
+        let job_key = self.job_key(job_id);
+        Box::pin(async move {
+            let mut connection =
+                RedisNotificationStore::connection(&store, JobSchedulerError::CantAdd).await?;
+            redis::pipe()
+                .atomic()
+                .set(&key, data.encode_to_vec())
+                .ignore()
+                .sadd(&job_key, notification_id.to_string())
+                .ignore()
+                .query_async::<_, ()>(&mut connection)
+                .await
+                .map_err(|e| {
+                    error!("Error saving notification {:?} {:?}", notification_id, e);
+                    JobSchedulerError::CantAdd
+                })
+        })
+    }
+
+    fn delete(
+        &mut self,
+        guid: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let get = self.get(guid);
+        let store = self.store.clone();
+        let key = self.key(guid);
+        let this = self.clone();
+        Box::pin(async move {
+            // The notification's job is needed to take it out of the job's set
+            let job_id = get.await?.as_ref().and_then(RedisNotificationStore::job_id);
+            let mut connection =
+                RedisNotificationStore::connection(&store, JobSchedulerError::CantRemove).await?;
+            let mut pipe = redis::pipe();
+            pipe.atomic().del(&key).ignore();
+            if let Some(job_id) = job_id {
+                pipe.srem(this.job_key(job_id), guid.to_string()).ignore();
+            }
+            pipe.query_async::<_, ()>(&mut connection)
+                .await
+                .map_err(|e| {
+                    error!("Error removing notification {:?} {:?}", guid, e);
+                    JobSchedulerError::CantRemove
+                })
+        })
+    }
+}
+
+impl InitStore for RedisNotificationStore {
+    fn init(&mut self) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let mut w = store.write().await;
+            if w.inited() {
+                return Ok(());
+            }
+            let inited = w.clone().init().await?;
+            *w = inited;
+            Ok(())
+        })
+    }
+
+    fn inited(&mut self) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let r = store.read().await;
+            Ok(r.inited())
+        })
+    }
+}
+
+impl NotificationStore for RedisNotificationStore {
+    fn list_notification_guids_for_job_and_state(
+        &mut self,
+        job: JobId,
+        state: JobState,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<NotificationId>, JobSchedulerError>> + Send>> {
+        let list = self.list_notification_guids_for_job_id(job);
+        let store = self.store.clone();
+        let this = self.clone();
+        let state = state as i32;
+        Box::pin(async move {
+            let ids = list.await?;
+            if ids.is_empty() {
+                return Ok(vec![]);
+            }
+            let mut connection =
+                RedisNotificationStore::connection(&store, JobSchedulerError::CantListGuids)
+                    .await?;
+            let keys = ids.iter().map(|id| this.key(*id)).collect::<Vec<_>>();
+            let values: Vec<Option<Vec<u8>>> = redis::cmd("MGET")
+                .arg(&keys)
+                .query_async(&mut connection)
+                .await
+                .map_err(|e| {
+                    error!("Error getting notifications {:?}", e);
+                    JobSchedulerError::CantListGuids
+                })?;
+            let notification_ids = ids
+                .into_iter()
+                .zip(values)
+                .filter_map(|(id, value)| {
+                    value
+                        .and_then(|v| NotificationData::decode(v.as_slice()).ok())
+                        .filter(|nd| nd.job_states.contains(&state))
+                        .map(|_| id)
+                })
+                .collect::<Vec<_>>();
+            Ok(notification_ids)
+        })
+    }
+
+    fn list_notification_guids_for_job_id(
+        &mut self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Uuid>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let job_key = self.job_key(job_id);
+        Box::pin(async move {
+            let mut connection =
+                RedisNotificationStore::connection(&store, JobSchedulerError::CantListGuids)
+                    .await?;
+            let ids: Vec<String> = connection.smembers(&job_key).await.map_err(|e| {
+                error!("Error getting list of guids {:?}", e);
+                JobSchedulerError::CantListGuids
+            })?;
+            Ok(ids
+                .iter()
+                .filter_map(|id| Uuid::parse_str(id).ok())
+                .collect())
+        })
+    }
+
+    fn delete_notification_for_state(
+        &mut self,
+        notification_id: Uuid,
+        state: JobState,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>> {
+        let get = self.get(notification_id);
+        let mut this = self.clone();
+        Box::pin(async move {
+            let mut data = match get.await? {
+                Some(data) => data,
+                None => {
+                    error!("Notification not found {:?}", notification_id);
+                    return Err(JobSchedulerError::CantRemove);
+                }
+            };
+            let state = state as i32;
+            let mut deleted = false;
+            data.job_states.retain(|s| {
+                let ret = *s != state;
+                deleted |= !ret;
+                ret
+            });
+            if data.job_states.is_empty() {
+                this.delete(notification_id).await.map(|_| true)
+            } else {
+                this.add_or_update(data).await.map(|_| deleted)
+            }
+        })
+    }
+
+    fn delete_for_job(
+        &mut self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let list = self.list_notification_guids_for_job_id(job_id);
+        let store = self.store.clone();
+        let this = self.clone();
+        Box::pin(async move {
+            let ids = list.await?;
+            let mut connection =
+                RedisNotificationStore::connection(&store, JobSchedulerError::CantRemove).await?;
+            let mut pipe = redis::pipe();
+            pipe.atomic();
+            for id in ids {
+                pipe.del(this.key(id)).ignore();
+            }
+            pipe.del(this.job_key(job_id)).ignore();
+            pipe.query_async::<_, ()>(&mut connection)
+                .await
+                .map_err(|e| {
+                    error!("Error removing the notifications of {:?} {:?}", job_id, e);
+                    JobSchedulerError::CantRemove
+                })
+        })
+    }
+}
@@ -1,23 +1,75 @@
 use crate::context::Context;
+#[cfg(feature = "metrics")]
+use crate::instruments;
 #[cfg(not(feature = "has_bytes"))]
 use crate::job::job_data::{JobState, JobType};
 #[cfg(feature = "has_bytes")]
 use crate::job::job_data_prost::{JobState, JobType};
-use crate::JobSchedulerError;
-use chrono::{FixedOffset, Utc};
+use crate::job::{lifecycle, Calendar, CalendarAction, JobDeleter, MisfirePolicy};
+use crate::stats::SkipReason;
+use crate::{JobSchedulerError, JobStoredData};
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::oneshot::{Receiver, Sender};
 use tokio::sync::RwLock;
-use tracing::error;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
+///
+/// The longest the scheduler loop sleeps between passes unless set otherwise with
+/// `set_max_sleep`. Every pass reads the next ticks from the metadata store again, so jobs added
+/// by any means, also by another process sharing the store, are seen within this long.
+pub(crate) const TICK_INTERVAL: Duration = Duration::from_millis(500);
+
+///
+/// What a pass of the scheduler loop did, for profiling the loop
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TickReport {
+    /// The number of the pass, counting from 1
+    pub tick: u64,
+    pub now: DateTime<Utc>,
+    /// How many jobs that were due, or done and to be removed, were looked at
+    pub evaluated: usize,
+    /// How many fires were sent to the runner
+    pub fired: usize,
+    /// How long the pass took, not counting the sleep before it
+    pub took: Duration,
+    /// How long the loop sleeps before the next pass, until the next tick or one off fire and at
+    /// most the max sleep set with `set_max_sleep`
+    pub sleep: Duration,
+}
+
+///
+/// What `shutdown` does with the removals of finished one shot jobs that are still in flight
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RemovalsOnShutdown {
+    /// Wait for them, so their removal notifications have run when `shutdown` returns
+    #[default]
+    Wait,
+    /// Abort them, the jobs may be left in the metadata store
+    Abort,
+    /// Leave them running in the background
+    Ignore,
+}
+
 pub struct Scheduler {
     pub shutdown: Arc<RwLock<bool>>,
     pub start_tx: Arc<RwLock<Option<Sender<bool>>>>,
     pub start_rx: Arc<RwLock<Option<Receiver<bool>>>>,
     pub ticking: Arc<RwLock<bool>>,
     pub inited: bool,
+    pub pending_removals: Arc<RwLock<HashMap<Uuid, JoinHandle<()>>>>,
+    pub removals_on_shutdown: RemovalsOnShutdown,
+    /// Whether the fires that were due before the loop started are passed over
+    pub start_clean: Arc<RwLock<bool>>,
 }
 
 impl Default for Scheduler {
@@ -29,11 +81,127 @@ impl Default for Scheduler {
             start_tx: Arc::new(RwLock::new(Some(ticker_tx))),
             start_rx: Arc::new(RwLock::new(Some(ticker_rx))),
             ticking: Arc::new(RwLock::new(false)),
+            pending_removals: Arc::new(RwLock::new(HashMap::new())),
+            removals_on_shutdown: RemovalsOnShutdown::default(),
+            start_clean: Arc::new(RwLock::new(false)),
         }
     }
 }
 
 impl Scheduler {
+    ///
+    /// The key jobs are fired in when sorted by it: most overdue first, the jobs due at the same
+    /// time by priority, the highest first, then by order key and then by id
+    pub(crate) fn fire_order<T: Ord>(
+        at: T,
+        priority: i32,
+        order_key: i64,
+        job_id: Uuid,
+    ) -> (T, Reverse<i32>, i64, Uuid) {
+        (at, Reverse(priority), order_key, job_id)
+    }
+
+    ///
+    /// Ask the schedule provider of a job for its schedule, after a fire and before its next fire
+    /// is worked out. Returns whether the schedule changed.
+    async fn provide_schedule(context: &Context, job_id: Uuid, job: &mut JobStoredData) -> bool {
+        let r = context.schedule_providers.read().await;
+        match r.get(&job_id) {
+            Some(provider) => job.set_schedule(&(provider)()),
+            None => false,
+        }
+    }
+
+    ///
+    /// How many times a job was due from `scheduled` up to `now`, `scheduled` included
+    fn fires_since(job: &JobStoredData, scheduled: DateTime<Utc>, now: DateTime<Utc>) -> usize {
+        match JobType::from_i32(job.job_type) {
+            Some(JobType::Cron) => {
+                let fixed_offset = FixedOffset::east_opt(job.time_offset_seconds)
+                    .unwrap_or(FixedOffset::east_opt(0).unwrap());
+                let mut fires = 1;
+                let mut fire = scheduled.with_timezone(&fixed_offset);
+                while let Some(next) = job.next_cron_tick(&fire).filter(|next| *next <= now) {
+                    fires += 1;
+                    fire = next;
+                }
+                fires
+            }
+            Some(JobType::Repeated) => match job.repeated_every() {
+                Some(every) if every > 0 => {
+                    (now - scheduled).num_seconds().max(0) as usize / every as usize + 1
+                }
+                _ => 1,
+            },
+            _ => 1,
+        }
+    }
+
+    ///
+    /// Whether a fire of a job falls in the job's calendar or the global one, with the action of
+    /// the calendar it falls in, the job's own first, and when neither excludes it any more
+    async fn excluded(
+        context: &Context,
+        job: &JobStoredData,
+        scheduled: DateTime<Utc>,
+    ) -> Option<(CalendarAction, Option<DateTime<Utc>>)> {
+        let global = {
+            let r = context.calendar.read().await;
+            r.clone()
+        };
+        let own = job.exclusions.as_ref().map(Calendar::from_stored);
+        let calendars = own.iter().chain(global.iter()).collect::<Vec<_>>();
+        let fixed_offset = FixedOffset::east_opt(job.time_offset_seconds)
+            .unwrap_or(FixedOffset::east_opt(0).unwrap());
+        let local = scheduled.with_timezone(&fixed_offset).naive_local();
+        let action = calendars.iter().find(|c| c.excludes(local))?.action;
+        let mut allowed = Some(local);
+        // Each calendar in turn until neither moves it on
+        for _ in 0..100 {
+            let before = allowed;
+            for calendar in calendars.iter() {
+                allowed = allowed.and_then(|at| calendar.next_allowed(at));
+            }
+            if allowed == before {
+                break;
+            }
+        }
+        let allowed = allowed
+            .filter(|at| calendars.iter().all(|c| !c.excludes(*at)))
+            .and_then(|at| fixed_offset.from_local_datetime(&at).single())
+            .map(|at| at.with_timezone(&Utc));
+        Some((action, allowed))
+    }
+
+    ///
+    /// Keep how late a job fired and report it when that's later than the job may fire
+    async fn fired_late(context: &Context, job_id: Uuid, lateness: chrono::Duration) {
+        let lateness = lateness.to_std().unwrap_or_default();
+        {
+            let mut w = context.stats.write().await;
+            w.fired(job_id, lateness);
+        }
+        let max_lateness = {
+            let r = context.max_lateness.read().await;
+            r.get(&job_id).copied()
+        };
+        match max_lateness {
+            Some(max_lateness) if lateness > max_lateness => {}
+            _ => return,
+        }
+        warn!(
+            "{:?} fired {:?} late, more than the {:?} it may",
+            job_id, lateness, max_lateness
+        );
+        let r = context
+            .late_handler
+            .read()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(on_late) = r.as_ref() {
+            (on_late)(job_id, lateness);
+        }
+    }
+
     pub async fn init(&mut self, context: &Context) {
         if self.inited {
             return;
@@ -41,8 +209,11 @@ impl Scheduler {
 
         let job_activation_tx = context.job_activation_tx.clone();
         let notify_tx = context.notify_tx.clone();
-        let job_delete_tx = context.job_delete_tx.clone();
         let shutdown = self.shutdown.clone();
+        let pending_removals = self.pending_removals.clone();
+        let heartbeat = context.heartbeat.clone();
+        let next_wake = context.next_wake.clone();
+        let context = context.clone();
         let metadata_storage = context.metadata_storage.clone();
 
         self.inited = true;
@@ -56,6 +227,7 @@ impl Scheduler {
         };
 
         let ticking = self.ticking.clone();
+        let start_clean = self.start_clean.clone();
         tokio::spawn(async move {
             let is_ticking = {
                 let ticking = ticking.read().await;
@@ -85,19 +257,43 @@ impl Scheduler {
                     }
                 }
             }
+            // Starting clean, what was due before now isn't fired
+            let started_at = {
+                let r = start_clean.read().await;
+                r.then(|| context.now().timestamp() as u64)
+            };
+            let mut tick = 0;
+            let mut previous_pass = context.now();
+            let mut sleep = Scheduler::next_sleep(&context, context.now()).await;
             'next_tick: loop {
                 let shutdown = {
                     let r = shutdown.read().await;
                     *r
                 };
                 if shutdown {
+                    next_wake.store(0, Ordering::Relaxed);
                     break 'next_tick;
                 }
-                tokio::time::sleep(Duration::from_millis(500)).await;
-                let now = Utc::now();
+                let clock = context.clock();
+                let wake = clock.now() + sleep;
+                next_wake.store(wake.timestamp_millis() as u64, Ordering::Relaxed);
+                // Woken early when the next ticks were worked out again, like by `set_timezone`
+                tokio::select! {
+                    _ = clock.sleep(sleep) => {}
+                    _ = context.wake.notified() => {}
+                }
+                tick += 1;
+                let started = Instant::now();
+                let now = clock.now();
+                // Fires that were due a second before the previous pass, the ticks are in whole
+                // seconds, were missed
+                let missed_before =
+                    std::mem::replace(&mut previous_pass, now) - chrono::Duration::seconds(1);
+                heartbeat.store(now.timestamp_millis() as u64, Ordering::Relaxed);
+                // Only the jobs that are due or done, the store keeps them ordered when it can
                 let next_ticks = {
                     let mut w = metadata_storage.write().await;
-                    w.list_next_ticks().await
+                    w.list_due_ticks(now).await
                 };
                 if let Err(e) = next_ticks {
                     error!("Error with listing next ticks {:?}", e);
@@ -113,133 +309,475 @@ impl Scheduler {
                         None
                     }
                 });
-                for uuid in to_be_deleted {
-                    let tx = job_delete_tx.clone();
-                    tokio::spawn(async move {
-                        if let Err(e) = tx.send(uuid) {
-                            error!("Error sending deletion {:?}", e);
+                {
+                    let stats = context.stats.read().await;
+                    let mut pending = pending_removals.write().await;
+                    pending.retain(|_, handle| !handle.is_finished());
+                    for uuid in to_be_deleted {
+                        if pending.contains_key(&uuid) {
+                            continue;
                         }
-                    });
+                        // A one shot is removed once its run is done, after its done or failed
+                        // notification went out
+                        if stats.job(&uuid).map(|j| j.running > 0).unwrap_or_default() {
+                            continue;
+                        }
+                        let context = context.clone();
+                        let handle = tokio::spawn(async move {
+                            if let Err(e) = JobDeleter::remove(&context, &uuid).await {
+                                error!("Error removing job {:?} {:?}", uuid, e);
+                            }
+                        });
+                        pending.insert(uuid, handle);
+                    }
                 }
 
                 next_ticks.retain(|n| n.next_tick != 0);
+                let min_interval = {
+                    let r = context.min_interval.read().await;
+                    chrono::Duration::from_std(*r).unwrap_or_else(|_| chrono::Duration::zero())
+                };
 
-                let must_runs = next_ticks.iter().filter_map(|n| {
-                    let next_tick = n.next_tick_utc();
-                    let last_tick = n.last_tick_utc();
-                    let job_type: JobType = JobType::from_i32(n.job_type).unwrap();
-
-                    let must_run = match (last_tick.as_ref(), next_tick.as_ref(), job_type) {
-                        (None, Some(next_tick), JobType::OneShot) => {
-                            let now_to_next = now.cmp(next_tick);
-                            matches!(now_to_next, std::cmp::Ordering::Greater)
-                                || matches!(now_to_next, std::cmp::Ordering::Equal)
-                        }
-                        (None, Some(next_tick), JobType::Repeated) => {
-                            let now_to_next = now.cmp(next_tick);
-                            matches!(now_to_next, std::cmp::Ordering::Greater)
-                                || matches!(now_to_next, std::cmp::Ordering::Equal)
-                        }
-                        (None, Some(next_tick), JobType::Cron) => {
-                            let now_to_next = now.cmp(next_tick);
-                            matches!(now_to_next, std::cmp::Ordering::Greater)
-                                || matches!(now_to_next, std::cmp::Ordering::Equal)
-                        }
-                        (Some(last_tick), Some(next_tick), _) => {
-                            let now_to_next = now.cmp(next_tick);
-                            let last_to_next = last_tick.cmp(next_tick);
-
-                            (matches!(now_to_next, std::cmp::Ordering::Greater)
-                                || matches!(now_to_next, std::cmp::Ordering::Equal))
-                                && (matches!(last_to_next, std::cmp::Ordering::Less)
-                                    || matches!(last_to_next, std::cmp::Ordering::Equal))
-                        }
-                        _ => false,
-                    };
-                    if must_run {
-                        let id: Uuid = n.id.as_ref().map(|f| f.into()).unwrap();
-                        Some(id)
-                    } else {
-                        None
-                    }
-                });
+                let mut must_runs = next_ticks
+                    .iter()
+                    .filter_map(|n| {
+                        let id: Uuid = n.id.as_ref()?.into();
+                        let next_tick = n.next_tick_utc();
+                        let last_tick = n.last_tick_utc();
+                        let job_type: JobType = JobType::from_i32(n.job_type)?;
 
-                for uuid in must_runs {
-                    {
-                        let tx = notify_tx.clone();
-                        tokio::spawn(async move {
-                            if let Err(e) = tx.send((uuid, JobState::Scheduled)) {
-                                error!("Error sending notification activation {:?}", e);
+                        let must_run = match (last_tick.as_ref(), next_tick.as_ref(), job_type) {
+                            (None, Some(next_tick), JobType::OneShot) => {
+                                let now_to_next = now.cmp(next_tick);
+                                matches!(now_to_next, std::cmp::Ordering::Greater)
+                                    || matches!(now_to_next, std::cmp::Ordering::Equal)
                             }
-                        });
-                    }
-                    {
-                        let tx = job_activation_tx.clone();
-                        tokio::spawn(async move {
-                            if let Err(e) = tx.send(uuid) {
-                                error!("Error sending job activation tx {:?}", e);
+                            (None, Some(next_tick), JobType::Repeated) => {
+                                let now_to_next = now.cmp(next_tick);
+                                matches!(now_to_next, std::cmp::Ordering::Greater)
+                                    || matches!(now_to_next, std::cmp::Ordering::Equal)
                             }
-                        });
-                    }
+                            (None, Some(next_tick), JobType::Cron) => {
+                                let now_to_next = now.cmp(next_tick);
+                                matches!(now_to_next, std::cmp::Ordering::Greater)
+                                    || matches!(now_to_next, std::cmp::Ordering::Equal)
+                            }
+                            (Some(last_tick), Some(next_tick), _) => {
+                                let now_to_next = now.cmp(next_tick);
+                                let last_to_next = last_tick.cmp(next_tick);
 
+                                (matches!(now_to_next, std::cmp::Ordering::Greater)
+                                    || matches!(now_to_next, std::cmp::Ordering::Equal))
+                                    && (matches!(last_to_next, std::cmp::Ordering::Less)
+                                        || matches!(last_to_next, std::cmp::Ordering::Equal))
+                            }
+                            _ => false,
+                        };
+                        if must_run {
+                            Some(Scheduler::fire_order(
+                                n.next_tick,
+                                n.priority,
+                                n.order_key,
+                                id,
+                            ))
+                        } else {
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let before_start = must_runs
+                    .iter()
+                    .filter(|(next_tick, ..)| started_at.is_some_and(|at| *next_tick < at))
+                    .map(|(.., id)| *id)
+                    .collect::<HashSet<_>>();
+                must_runs.sort();
+                let must_runs = must_runs.into_iter().map(|(.., id)| id).collect::<Vec<_>>();
+
+                // Paused jobs keep to their schedule, their fires are passed over until the pause
+                // is over
+                let paused = {
+                    let mut w = context.paused_until.write().await;
+                    w.retain(|_, until| *until > now);
+                    w.keys().copied().collect::<HashSet<_>>()
+                };
+                // Not the leader, the schedules go on but nothing is fired
+                let leader = context
+                    .leadership_check
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .as_ref()
+                    .map(|check| (check)())
+                    .unwrap_or(true);
+                // Backpressure, the schedules go on but nothing is fired until it's over
+                let backpressured = context.backpressured();
+                // Not ready yet, the same until the readiness future is done
+                let ready = context.ready();
+                let passed_over =
+                    |uuid: &Uuid| !leader || backpressured || !ready || paused.contains(uuid);
+                // Fired after other jobs, their own schedule goes on without firing them
+                let after_jobs = {
+                    let r = context.after_jobs.read().await;
+                    r.keys().copied().collect::<HashSet<_>>()
+                };
+                let fires_own = |uuid: &Uuid| {
+                    !passed_over(uuid) && !after_jobs.contains(uuid) && !before_start.contains(uuid)
+                };
+
+                // One after another so the runner gets them in that order
+                let dispatching = {
+                    let tx = job_activation_tx.clone();
+                    let notify_tx = notify_tx.clone();
                     let storage = metadata_storage.clone();
+                    let context = context.clone();
+                    let must_runs = must_runs.clone();
+                    let passed_over = must_runs
+                        .iter()
+                        .filter(|uuid| !fires_own(uuid))
+                        .copied()
+                        .collect::<HashSet<_>>();
                     tokio::spawn(async move {
-                        let mut w = storage.write().await;
-                        let job = w.get(uuid).await;
-
-                        let next_and_last_tick = match job {
-                            Ok(Some(job)) => {
-                                let job_type: JobType = JobType::from_i32(job.job_type).unwrap();
-                                let schedule = job.schedule();
-                                // TODO continue from here
-                                let fixed_offset = FixedOffset::east_opt(job.time_offset_seconds)
-                                    .unwrap_or(FixedOffset::east_opt(0).unwrap());
-                                let now = now.with_timezone(&fixed_offset);
-                                let repeated_every = job.repeated_every();
-                                let next_tick = job
-                                    .next_tick_utc()
-                                    .map(|nt| nt.with_timezone(&fixed_offset));
-                                let next_tick = match job_type {
-                                    JobType::Cron => schedule.and_then(|s| s.after(&now).next()),
-                                    JobType::OneShot => None,
-                                    JobType::Repeated => repeated_every.and_then(|r| {
-                                        next_tick.and_then(|nt| {
+                        let mut sent = 0;
+                        for uuid in must_runs {
+                            // Activate after the ticks are updated, so the run sees its next fire time
+                            let mut w = storage.write().await;
+                            let job = w.get(uuid).await;
+                            let scheduled = match job.as_ref() {
+                                Ok(Some(job)) => job.next_tick_utc(),
+                                _ => None,
+                            };
+                            // Missed when it was due before the previous pass, or when the job came
+                            // due again since
+                            let misfire = match (job.as_ref(), scheduled) {
+                                (Ok(Some(job)), Some(scheduled)) => {
+                                    MisfirePolicy::from_i32(job.misfire).map(|policy| {
+                                        (policy, Scheduler::fires_since(job, scheduled, now))
+                                    })
+                                }
+                                _ => None,
+                            }
+                            .filter(|(_, fires)| {
+                                *fires > 1 || scheduled.is_some_and(|s| s < missed_before)
+                            });
+                            let (misfire, fires) = match misfire {
+                                Some((policy, fires)) => (Some(policy), fires),
+                                None => (None, 1),
+                            };
+                            let runs = match misfire {
+                                Some(MisfirePolicy::Ignore) => 0,
+                                Some(MisfirePolicy::FireAll) => fires,
+                                _ => 1,
+                            };
+                            if let (Some(policy), Some(scheduled)) = (misfire, scheduled) {
+                                info!(
+                                    "{:?} missed its fires since {:?}, running it {} times for {:?}",
+                                    uuid, scheduled, runs, policy
+                                );
+                            }
+
+                            // Paused with `pause`, its schedule goes on until it's resumed
+                            let paused = matches!(job.as_ref(), Ok(Some(job)) if job.paused);
+                            let passed_over = passed_over.contains(&uuid) || runs == 0 || paused;
+                            // Fires on the calendars' exclusions are passed over, or deferred to
+                            // when they're over
+                            let excluded = match (job.as_ref(), scheduled) {
+                                (Ok(Some(job)), Some(scheduled)) => {
+                                    Scheduler::excluded(&context, job, scheduled).await
+                                }
+                                _ => None,
+                            };
+                            if let (Some((action, allowed)), Some(scheduled)) =
+                                (excluded, scheduled)
+                            {
+                                info!(
+                                    "{:?} is excluded at {:?} by its calendar, {:?} to {:?}",
+                                    uuid, scheduled, action, allowed
+                                );
+                                if !passed_over && action == CalendarAction::Skip {
+                                    let mut w = context.stats.write().await;
+                                    w.skipped(uuid, SkipReason::Excluded);
+                                }
+                            }
+                            let passed_over = passed_over || excluded.is_some();
+                            if !passed_over {
+                                lifecycle::event(uuid, JobState::Scheduled);
+                                if let Err(e) = notify_tx.send((uuid, JobState::Scheduled)) {
+                                    error!("Error sending notification activation {:?}", e);
+                                }
+                            }
+
+                            let next_and_last_tick = match job {
+                                Ok(Some(mut job)) => {
+                                    if Scheduler::provide_schedule(&context, uuid, &mut job).await {
+                                        if let Err(e) = w.add_or_update(job.clone()).await {
+                                            error!("Could not store the provided schedule {:?}", e);
+                                        }
+                                    }
+                                    let job_type: JobType =
+                                        JobType::from_i32(job.job_type).unwrap();
+                                    // TODO continue from here
+                                    let fixed_offset =
+                                        FixedOffset::east_opt(job.time_offset_seconds)
+                                            .unwrap_or(FixedOffset::east_opt(0).unwrap());
+                                    let now = now.with_timezone(&fixed_offset);
+                                    let repeated_every = job.repeated_every();
+                                    let next_tick = job
+                                        .next_tick_utc()
+                                        .map(|nt| nt.with_timezone(&fixed_offset));
+                                    let next_tick = match job_type {
+                                        JobType::Cron => job.next_cron_fire(&now),
+                                        JobType::OneShot => None,
+                                        // On the grid of its interval schedule, the first fire
+                                        // after now, none once the schedule is over
+                                        JobType::Repeated if job.interval_bounds.is_some() => job
+                                            .next_interval_fire(now.with_timezone(&Utc))
+                                            .map(|nt| nt.with_timezone(&fixed_offset)),
+                                        JobType::Repeated => repeated_every.and_then(|r| {
+                                            next_tick.and_then(|nt| {
+                                                nt.checked_add_signed(chrono::Duration::seconds(
+                                                    r as i64,
+                                                ))
+                                            })
+                                        }),
+                                    };
+                                    // After missed fires the job goes on with its first fire
+                                    // after now
+                                    let next_tick = match (misfire, repeated_every, next_tick) {
+                                        (Some(_), Some(r), Some(nt)) if r > 0 && nt <= now => {
+                                            let behind = (now - nt).num_seconds() as u64 / r + 1;
                                             nt.checked_add_signed(chrono::Duration::seconds(
-                                                r as i64,
+                                                (behind * r) as i64,
                                             ))
-                                        })
-                                    }),
-                                };
-                                let last_tick = Some(now);
-                                Some((
-                                    next_tick.map(|nt| nt.with_timezone(&Utc)),
-                                    last_tick.map(|nt| nt.with_timezone(&Utc)),
-                                ))
+                                        }
+                                        _ => next_tick,
+                                    };
+                                    let next_tick = match excluded {
+                                        Some((CalendarAction::Defer, Some(allowed))) => {
+                                            Some(allowed.with_timezone(&fixed_offset))
+                                        }
+                                        _ => next_tick,
+                                    };
+                                    let earliest = now + min_interval;
+                                    let next_tick = next_tick.map(|nt| {
+                                        if nt < earliest {
+                                            warn!(
+                                                "Next run of {:?} at {:?} is too soon, moved to {:?}",
+                                                uuid, nt, earliest
+                                            );
+                                            earliest
+                                        } else {
+                                            nt
+                                        }
+                                    });
+                                    let last_tick = Some(now);
+                                    Some((
+                                        next_tick.map(|nt| nt.with_timezone(&Utc)),
+                                        last_tick.map(|nt| nt.with_timezone(&Utc)),
+                                    ))
+                                }
+                                _ => {
+                                    error!("Could not get job metadata");
+                                    None
+                                }
+                            };
+
+                            if let Some((next_tick, last_tick)) = next_and_last_tick {
+                                if let Err(e) =
+                                    w.set_next_and_last_tick(uuid, next_tick, last_tick).await
+                                {
+                                    error!("Could not set next and last tick {:?}", e);
+                                }
+                            }
+                            drop(w);
+
+                            if passed_over {
+                                continue;
                             }
-                            _ => {
-                                error!("Could not get job metadata");
-                                None
+                            if let Some(scheduled) = scheduled {
+                                Scheduler::fired_late(&context, uuid, now - scheduled).await;
+                                let mut w = context.fire_times.write().await;
+                                w.insert(uuid, scheduled);
                             }
-                        };
+                            for _ in 0..runs {
+                                match tx.send(uuid) {
+                                    Ok(_) => sent += 1,
+                                    Err(e) => error!("Error sending job activation tx {:?}", e),
+                                }
+                            }
+                        }
+                        sent
+                    })
+                };
 
-                        if let Some((next_tick, last_tick)) = next_and_last_tick {
-                            if let Err(e) =
-                                w.set_next_and_last_tick(uuid, next_tick, last_tick).await
-                            {
-                                error!("Could not set next and last tick {:?}", e);
+                // One off fires don't change the ticks, the job keeps to its schedule after them
+                let one_offs = {
+                    let mut w = context.one_offs.write().await;
+                    let mut due = vec![];
+                    w.retain(|uuid, whens| {
+                        let before = whens.len();
+                        whens.retain(|when| *when > now);
+                        if !passed_over(uuid) {
+                            for _ in whens.len()..before {
+                                due.push(*uuid);
                             }
                         }
+                        !whens.is_empty()
+                    });
+                    due
+                };
+                // Passed over for the jobs paused with `pause`, like the fires of their schedules
+                let one_offs = if one_offs.is_empty() {
+                    one_offs
+                } else {
+                    let mut w = metadata_storage.write().await;
+                    let mut due = Vec::with_capacity(one_offs.len());
+                    for uuid in one_offs {
+                        if !matches!(w.get(uuid).await, Ok(Some(job)) if job.paused) {
+                            due.push(uuid);
+                        }
+                    }
+                    due
+                };
+                // Jobs that didn't run for longer than they may are fired whatever their schedule
+                let silent = {
+                    let quiet = {
+                        let r = context.max_silence.read().await;
+                        r.iter()
+                            .filter_map(|(uuid, (max_silence, last_run))| {
+                                let max_silence = chrono::Duration::from_std(*max_silence)
+                                    .unwrap_or(chrono::Duration::MAX);
+                                let silence = now - *last_run;
+                                (silence >= max_silence
+                                    && !passed_over(uuid)
+                                    && !must_runs.iter().any(|id| id == uuid && fires_own(id)))
+                                .then_some((*uuid, silence))
+                            })
+                            .collect::<Vec<_>>()
+                    };
+                    // Only the jobs that are still scheduled and not paused with `pause`
+                    let mut scheduled = Vec::with_capacity(quiet.len());
+                    if !quiet.is_empty() {
+                        let mut w = metadata_storage.write().await;
+                        for (uuid, silence) in quiet {
+                            if let Ok(Some(job)) = w.get(uuid).await {
+                                if job.next_tick != 0 && !job.paused {
+                                    scheduled.push((uuid, silence));
+                                }
+                            }
+                        }
+                    }
+                    let mut w = context.max_silence.write().await;
+                    let mut due = vec![];
+                    for (uuid, silence) in scheduled {
+                        if let Some((_, last_run)) = w.get_mut(&uuid) {
+                            warn!("{:?} didn't run for {:?}, firing it", uuid, silence);
+                            // Counted from now until the run starts, so it's fired once
+                            *last_run = now;
+                            due.push(uuid);
+                        }
+                    }
+                    due
+                };
+                // Only the fires that reached the runner are counted
+                let mut fired = 0;
+                for uuid in one_offs.iter().chain(silent.iter()).copied() {
+                    lifecycle::event(uuid, JobState::Scheduled);
+                    if let Err(e) = notify_tx.send((uuid, JobState::Scheduled)) {
+                        error!("Error sending notification activation {:?}", e);
+                    }
+                    match job_activation_tx.send(uuid) {
+                        Ok(_) => fired += 1,
+                        Err(e) => error!("Error sending job activation tx {:?}", e),
+                    }
+                }
+
+                let took = started.elapsed();
+                // The next ticks of the jobs fired in this pass are set by then
+                match dispatching.await {
+                    Ok(sent) => fired += sent,
+                    Err(e) => error!("Error firing the due jobs {:?}", e),
+                }
+                #[cfg(feature = "metrics")]
+                instruments::tick(took, fired, job_activation_tx.len());
+                sleep = Scheduler::next_sleep(&context, clock.now()).await;
+                let on_tick = context
+                    .tick_handler
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner());
+                if let Some(on_tick) = on_tick.as_ref() {
+                    (on_tick)(TickReport {
+                        tick,
+                        now,
+                        evaluated: next_ticks.len(),
+                        fired,
+                        took,
+                        sleep,
                     });
                 }
             }
         });
     }
 
-    pub async fn shutdown(&mut self) {
+    ///
+    /// How long the loop sleeps before its next pass: until the next tick in the metadata store
+    /// or the next one off fire, whichever is sooner, and at most the max sleep
+    async fn next_sleep(context: &Context, now: DateTime<Utc>) -> Duration {
+        let max_sleep = *context.max_sleep.read().await;
+        let next_tick = {
+            let mut w = context.metadata_storage.write().await;
+            w.time_till_next_job_at(now).await.ok().flatten()
+        }
+        // The ticks are whole seconds, counted from the start of the second `now` is in
+        .map(|till| till.saturating_sub(Duration::from_nanos(now.timestamp_subsec_nanos() as u64)));
+        let next_one_off = {
+            let r = context.one_offs.read().await;
+            r.values().flatten().min().copied()
+        }
+        .map(|when| (when - now).to_std().unwrap_or_default());
+        next_tick
+            .into_iter()
+            .chain(next_one_off)
+            .fold(max_sleep, Duration::min)
+    }
+
+    ///
+    /// Stop the loop after the pass it is in, so no more jobs are fired. Fires that come due
+    /// after that are left in the metadata store as they are.
+    pub async fn stop_ticking(&self) {
         let mut w = self.shutdown.write().await;
         *w = true;
     }
 
+    pub async fn shutdown(&mut self) {
+        self.stop_ticking().await;
+        let pending = {
+            let mut w = self.pending_removals.write().await;
+            std::mem::take(&mut *w)
+        };
+        for (uuid, handle) in pending {
+            match self.removals_on_shutdown {
+                RemovalsOnShutdown::Wait => {
+                    if let Err(e) = handle.await {
+                        error!("Error waiting for the removal of {:?} {:?}", uuid, e);
+                    }
+                }
+                RemovalsOnShutdown::Abort => {
+                    if !handle.is_finished() {
+                        warn!("Aborted the removal of {:?}", uuid);
+                    }
+                    handle.abort();
+                }
+                RemovalsOnShutdown::Ignore => {}
+            }
+        }
+    }
+
+    ///
+    /// How many removals of finished one shot jobs are still in flight
+    pub async fn pending_removals(&self) -> usize {
+        let r = self.pending_removals.read().await;
+        r.values().filter(|handle| !handle.is_finished()).count()
+    }
+
     pub async fn start(&mut self) -> Result<(), JobSchedulerError> {
         let is_ticking = {
             let ticking = self.ticking.read().await;
@@ -0,0 +1,9 @@
+///
+/// Whether the scheduler has runs going, as watched with `activity_watch`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchedulerActivity {
+    /// No run is going
+    Idle,
+    /// At least one run is going
+    Busy,
+}
@@ -0,0 +1,64 @@
+use crate::name_conflict::NameConflict;
+use crate::scheduler::RemovalsOnShutdown;
+use chrono::FixedOffset;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+///
+/// Snapshot of the settings in effect for a scheduler
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SchedulerConfig {
+    /// The longest the scheduler loop sleeps between passes
+    pub max_sleep: Duration,
+    /// The shortest time between two runs of the same job
+    pub min_interval: Duration,
+    /// How many runs of all jobs together may go at the same time
+    pub max_concurrency: Option<usize>,
+    /// How many runs of one shot jobs may go at the same time
+    pub max_one_shot_concurrency: Option<usize>,
+    /// How far ahead a cron job must fire for `add` to take it
+    pub schedule_horizon: Duration,
+    /// What `add` does with a job whose name is taken
+    pub name_conflict: NameConflict,
+    /// The offset of the last timezone set with `set_timezone`, in seconds east of UTC when
+    /// serialized
+    #[cfg_attr(feature = "serde", serde(with = "offset_seconds"))]
+    pub timezone: Option<FixedOffset>,
+    /// What `shutdown` does with one shot removals still in flight
+    pub removals_on_shutdown: RemovalsOnShutdown,
+    /// Whether the fires that were due before `start` are passed over
+    pub start_clean: bool,
+    pub has_checkpoint_store: bool,
+    pub has_job_failure_handler: bool,
+    pub has_shutdown_handler: bool,
+    pub has_pre_shutdown_hook: bool,
+    pub has_on_add: bool,
+}
+
+#[cfg(feature = "serde")]
+mod offset_seconds {
+    use chrono::FixedOffset;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        offset: &Option<FixedOffset>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        offset
+            .map(|offset| offset.local_minus_utc())
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<FixedOffset>, D::Error> {
+        match Option::<i32>::deserialize(deserializer)? {
+            Some(seconds) => FixedOffset::east_opt(seconds)
+                .map(Some)
+                .ok_or_else(|| serde::de::Error::custom("offset out of range")),
+            None => Ok(None),
+        }
+    }
+}
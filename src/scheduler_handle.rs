@@ -0,0 +1,92 @@
+use crate::job::JobLocked;
+use crate::job_scheduler::JobsSchedulerLocked;
+use crate::{JobInfo, JobSchedulerError, JobType};
+use chrono::Utc;
+use std::time::Duration;
+use uuid::Uuid;
+
+///
+/// The scheduler together with the id of one of its jobs, for a job to control itself from its
+/// own run. Get one with `JobScheduler::handle`.
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use tokio_cron_scheduler::{Job, JobSchedulerError};
+/// # async fn sync() -> Result<(), ()> {
+/// #     Ok(())
+/// # }
+/// # fn example() -> Result<(), JobSchedulerError> {
+/// let job = Job::new_async("0 */5 * * * *", |uuid, l| {
+///     Box::pin(async move {
+///         let me = l.handle(uuid);
+///         if sync().await.is_err() {
+///             me.self_reschedule_in(Duration::from_secs(30)).await.ok();
+///         }
+///     })
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SchedulerHandle {
+    job_id: Uuid,
+    scheduler: JobsSchedulerLocked,
+}
+
+impl SchedulerHandle {
+    pub fn new(job_id: Uuid, scheduler: JobsSchedulerLocked) -> Self {
+        Self { job_id, scheduler }
+    }
+
+    pub fn job_id(&self) -> Uuid {
+        self.job_id
+    }
+
+    pub fn scheduler(&self) -> &JobsSchedulerLocked {
+        &self.scheduler
+    }
+
+    ///
+    /// Fire the job again `delay` from now. A one shot job is rearmed, other jobs get a one off
+    /// fire on top of their schedule.
+    pub async fn self_reschedule_in(&self, delay: Duration) -> Result<(), JobSchedulerError> {
+        let when = chrono::Duration::from_std(delay)
+            .ok()
+            .and_then(|delay| self.scheduler.context().now().checked_add_signed(delay))
+            .ok_or(JobSchedulerError::NoNextTick)?;
+        let mut scheduler = self.scheduler.clone();
+        match self.self_info().await?.job_type {
+            JobType::OneShot => scheduler.rearm(&self.job_id, when).await,
+            _ => scheduler.schedule_one_off(&self.job_id, when).await,
+        }
+    }
+
+    ///
+    /// Don't fire the job for `duration`, see `JobScheduler::pause_until`
+    pub async fn self_pause(&self, duration: Duration) -> Result<(), JobSchedulerError> {
+        let until = chrono::Duration::from_std(duration)
+            .ok()
+            .and_then(|duration| self.scheduler.context().now().checked_add_signed(duration))
+            .unwrap_or(chrono::DateTime::<Utc>::MAX_UTC);
+        let mut scheduler = self.scheduler.clone();
+        scheduler.pause_until(&self.job_id, until).await
+    }
+
+    ///
+    /// A snapshot of the job as it's in the metadata store
+    pub async fn self_info(&self) -> Result<JobInfo, JobSchedulerError> {
+        let context = self.scheduler.context();
+        let mut r = context.metadata_storage.write().await;
+        r.get(self.job_id)
+            .await?
+            .as_ref()
+            .and_then(JobInfo::from_stored)
+            .ok_or(JobSchedulerError::JobNotFound)
+    }
+
+    ///
+    /// Add another job to the scheduler, as `JobScheduler::add` does
+    pub async fn spawn_follow_up(&self, job: JobLocked) -> Result<Uuid, JobSchedulerError> {
+        self.scheduler.add(job).await
+    }
+}
@@ -0,0 +1,26 @@
+use crate::scheduler_config::SchedulerConfig;
+use crate::stats::SchedulerStats;
+use crate::JobStoredData;
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+///
+/// Snapshot of a whole scheduler, taken with `export_state` and loaded into a scheduler with
+/// `import_state`. With the `serde` feature it can be serialized, for instance to JSON to load it
+/// elsewhere.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SchedulerState {
+    /// The metadata of every job, with their schedules, ticks, flags and tags
+    pub jobs: Vec<JobStoredData>,
+    /// The run counters of the jobs
+    pub stats: SchedulerStats,
+    /// The jobs paused with `pause_until`, with when their pause is over. The jobs paused with
+    /// `pause` are paused in their metadata.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub paused_until: HashMap<Uuid, DateTime<Utc>>,
+    pub config: SchedulerConfig,
+}
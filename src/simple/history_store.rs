@@ -0,0 +1,81 @@
+use crate::store::{HistoryStore, RunRecord};
+use crate::JobSchedulerError;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+const KEEP: usize = 100;
+
+///
+/// Keeps the last `keep` runs of every job in memory, the scheduler's history store unless it's
+/// given another one. The runs of a job go when it's removed.
+pub struct SimpleHistoryStore {
+    pub data: Arc<RwLock<HashMap<Uuid, VecDeque<RunRecord>>>>,
+    pub keep: usize,
+}
+
+impl SimpleHistoryStore {
+    pub fn new(keep: usize) -> Self {
+        Self {
+            data: Arc::new(RwLock::new(HashMap::new())),
+            keep,
+        }
+    }
+}
+
+impl Default for SimpleHistoryStore {
+    fn default() -> Self {
+        Self::new(KEEP)
+    }
+}
+
+impl HistoryStore for SimpleHistoryStore {
+    fn record(
+        &mut self,
+        run: RunRecord,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let data = self.data.clone();
+        let keep = self.keep;
+        Box::pin(async move {
+            if keep == 0 {
+                return Ok(());
+            }
+            let mut w = data.write().await;
+            let runs = w.entry(run.job_id).or_default();
+            if runs.len() >= keep {
+                runs.pop_front();
+            }
+            runs.push_back(run);
+            Ok(())
+        })
+    }
+
+    fn list(
+        &mut self,
+        job_id: Uuid,
+        limit: usize,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<RunRecord>, JobSchedulerError>> + Send>> {
+        let data = self.data.clone();
+        Box::pin(async move {
+            let r = data.read().await;
+            Ok(r.get(&job_id)
+                .map(|runs| runs.iter().rev().take(limit).cloned().collect())
+                .unwrap_or_default())
+        })
+    }
+
+    fn delete(
+        &mut self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let data = self.data.clone();
+        Box::pin(async move {
+            let mut w = data.write().await;
+            w.remove(&job_id);
+            Ok(())
+        })
+    }
+}
@@ -5,16 +5,21 @@ use crate::job::job_data_prost::{JobAndNextTick, JobStoredData};
 use crate::store::{DataStore, InitStore, MetaDataStorage};
 use crate::JobSchedulerError;
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
+///
+/// Keeps the jobs in memory. They're also kept ordered by their next tick, so the scheduler loop
+/// finds the ones that are due without going through all of them. The order is kept up by the
+/// store's own methods, jobs put in `data` by hand aren't in it.
 pub struct SimpleMetadataStore {
     pub data: Arc<RwLock<HashMap<Uuid, JobStoredData>>>,
     pub inited: bool,
+    by_next_tick: Arc<RwLock<BTreeSet<(u64, Uuid)>>>,
 }
 
 impl Default for SimpleMetadataStore {
@@ -22,6 +27,23 @@ impl Default for SimpleMetadataStore {
         Self {
             data: Arc::new(RwLock::new(HashMap::new())),
             inited: false,
+            by_next_tick: Arc::new(RwLock::new(BTreeSet::new())),
+        }
+    }
+}
+
+impl SimpleMetadataStore {
+    fn reorder(
+        by_next_tick: &mut BTreeSet<(u64, Uuid)>,
+        id: Uuid,
+        old: Option<u64>,
+        new: Option<u64>,
+    ) {
+        if let Some(old) = old {
+            by_next_tick.remove(&(old, id));
+        }
+        if let Some(new) = new {
+            by_next_tick.insert((new, id));
         }
     }
 }
@@ -46,9 +68,13 @@ impl DataStore<JobStoredData> for SimpleMetadataStore {
     ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
         let id: Uuid = data.id.as_ref().unwrap().into();
         let job_data = self.data.clone();
+        let by_next_tick = self.by_next_tick.clone();
         Box::pin(async move {
             let mut w = job_data.write().await;
-            w.insert(id, data);
+            let next_tick = data.next_tick;
+            let old = w.insert(id, data).map(|old| old.next_tick);
+            let mut by_next_tick = by_next_tick.write().await;
+            SimpleMetadataStore::reorder(&mut by_next_tick, id, old, Some(next_tick));
             Ok(())
         })
     }
@@ -58,9 +84,12 @@ impl DataStore<JobStoredData> for SimpleMetadataStore {
         guid: Uuid,
     ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
         let job_data = self.data.clone();
+        let by_next_tick = self.by_next_tick.clone();
         Box::pin(async move {
             let mut w = job_data.write().await;
-            w.remove(&guid);
+            let old = w.remove(&guid).map(|old| old.next_tick);
+            let mut by_next_tick = by_next_tick.write().await;
+            SimpleMetadataStore::reorder(&mut by_next_tick, guid, old, None);
             Ok(())
         })
     }
@@ -86,13 +115,40 @@ impl MetaDataStorage for SimpleMetadataStore {
         Box::pin(async move {
             let r = data.read().await;
             let ret = r
-                .iter()
-                .map(|(_, v)| (v.id.clone(), v.next_tick, v.last_tick, v.job_type))
-                .map(|(id, next_tick, last_tick, job_type)| JobAndNextTick {
-                    id,
-                    next_tick,
-                    last_tick,
-                    job_type,
+                .values()
+                .map(|v| JobAndNextTick {
+                    id: v.id.clone(),
+                    next_tick: v.next_tick,
+                    last_tick: v.last_tick,
+                    job_type: v.job_type,
+                    order_key: v.order_key,
+                    priority: v.priority,
+                })
+                .collect::<Vec<_>>();
+            Ok(ret)
+        })
+    }
+
+    fn list_due_ticks(
+        &mut self,
+        now: DateTime<Utc>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>> {
+        let data = self.data.clone();
+        let by_next_tick = self.by_next_tick.clone();
+        let now = now.timestamp().max(0) as u64;
+        Box::pin(async move {
+            let r = data.read().await;
+            let by_next_tick = by_next_tick.read().await;
+            let ret = by_next_tick
+                .range(..(now.saturating_add(1), Uuid::nil()))
+                .filter_map(|(_, id)| r.get(id))
+                .map(|v| JobAndNextTick {
+                    id: v.id.clone(),
+                    next_tick: v.next_tick,
+                    last_tick: v.last_tick,
+                    job_type: v.job_type,
+                    order_key: v.order_key,
+                    priority: v.priority,
                 })
                 .collect::<Vec<_>>();
             Ok(ret)
@@ -106,13 +162,22 @@ impl MetaDataStorage for SimpleMetadataStore {
         last_tick: Option<DateTime<Utc>>,
     ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
         let data = self.data.clone();
+        let by_next_tick = self.by_next_tick.clone();
         Box::pin(async move {
             let mut w = data.write().await;
             let val = w.get_mut(&guid);
             match val {
                 Some(val) => {
+                    let old = val.next_tick;
                     val.set_next_tick(next_tick);
                     val.set_last_tick(last_tick);
+                    let mut by_next_tick = by_next_tick.write().await;
+                    SimpleMetadataStore::reorder(
+                        &mut by_next_tick,
+                        guid,
+                        Some(old),
+                        Some(val.next_tick),
+                    );
                     Ok(())
                 }
                 None => Err(JobSchedulerError::UpdateJobData),
@@ -124,27 +189,34 @@ impl MetaDataStorage for SimpleMetadataStore {
         &mut self,
     ) -> Pin<Box<dyn Future<Output = Result<Option<std::time::Duration>, JobSchedulerError>> + Send>>
     {
-        let data = self.data.clone();
+        self.time_till_next_job_at(Utc::now())
+    }
+
+    fn time_till_next_job_at(
+        &mut self,
+        now: DateTime<Utc>,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<std::time::Duration>, JobSchedulerError>> + Send>>
+    {
+        let by_next_tick = self.by_next_tick.clone();
         Box::pin(async move {
-            let r = data.read().await;
-            let now = Utc::now();
+            let by_next_tick = by_next_tick.read().await;
             let now = now.timestamp() as u64;
-            let val = r
-                .iter()
-                .filter_map(|(_, jd)| match jd.next_tick {
-                    0 => None,
-                    i => {
-                        if i > now {
-                            Some(i)
-                        } else {
-                            None
-                        }
-                    }
-                })
-                .min()
-                .map(|t| t - now)
+            let val = by_next_tick
+                .range((now.saturating_add(1), Uuid::nil())..)
+                .next()
+                .map(|(t, _)| t - now)
                 .map(std::time::Duration::from_secs);
             Ok(val)
         })
     }
+
+    fn list_jobs(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobStoredData>, JobSchedulerError>> + Send>> {
+        let data = self.data.clone();
+        Box::pin(async move {
+            let r = data.read().await;
+            Ok(r.values().cloned().collect())
+        })
+    }
 }
@@ -1,7 +1,9 @@
+mod history_store;
 mod metadata_store;
 mod notification_store;
 mod to_code;
 
+pub use history_store::SimpleHistoryStore;
 pub use metadata_store::SimpleMetadataStore;
 pub use notification_store::SimpleNotificationStore;
 pub use to_code::SimpleJobCode;
@@ -0,0 +1,436 @@
+use crate::job::job_data_prost::{
+    After, CronJob, Exclusions, IntervalBounds, JobType, NonCronJob, Overlap,
+};
+use crate::sqlite::{add_missing_columns, SqliteStore};
+use crate::store::{DataStore, InitStore, MetaDataStorage};
+use crate::{JobAndNextTick, JobSchedulerError, JobStoredData, JobUuid};
+use chrono::{DateTime, Utc};
+use prost::Message;
+use rusqlite::{params, OptionalExtension, Row};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+const TABLE: &str = "job";
+/// Tags can't be kept in an array column, they're joined with the unit separator instead
+const TAG_SEPARATOR: char = '\u{1f}';
+
+/// The columns of the table, in the order they're selected in
+const COLUMNS: &[(&str, &str)] = &[
+    ("id", "TEXT NOT NULL PRIMARY KEY"),
+    ("last_updated", "INTEGER"),
+    ("next_tick", "INTEGER"),
+    ("last_tick", "INTEGER"),
+    ("job_type", "INTEGER NOT NULL DEFAULT 0"),
+    ("count", "INTEGER"),
+    ("ran", "INTEGER"),
+    ("stopped", "INTEGER"),
+    ("schedule", "TEXT"),
+    ("repeating", "INTEGER"),
+    ("repeated_every", "INTEGER"),
+    ("extra", "BLOB"),
+    ("time_offset_seconds", "INTEGER"),
+    ("overlap_kind", "INTEGER"),
+    ("overlap_max_depth", "INTEGER"),
+    ("overlap_drop_oldest", "INTEGER"),
+    ("jitter_seconds", "INTEGER"),
+    ("tags", "TEXT"),
+    ("queue", "TEXT"),
+    ("order_key", "INTEGER"),
+    ("timezone", "TEXT"),
+    ("misfire", "INTEGER"),
+    ("timeout_ms", "INTEGER"),
+    ("name", "TEXT"),
+    ("priority", "INTEGER"),
+    ("random_jitter_seconds", "INTEGER"),
+    ("after_job_id", "TEXT"),
+    ("after_delay_ms", "INTEGER"),
+    ("exclusions", "BLOB"),
+    ("interval_bounds", "BLOB"),
+    ("paused", "INTEGER"),
+];
+
+///
+/// A SQLite backed metadata store, for persisting jobs on a single node without a database
+/// server. The table is made on init, and the columns a table of an earlier version misses are
+/// added to it.
+#[derive(Clone)]
+pub struct SqliteMetadataStore {
+    pub store: Arc<RwLock<SqliteStore>>,
+    pub table: String,
+}
+
+impl Default for SqliteMetadataStore {
+    fn default() -> Self {
+        let table = std::env::var("SQLITE_METADATA_TABLE").unwrap_or_else(|_| TABLE.to_lowercase());
+        let store = Arc::new(RwLock::new(SqliteStore::default()));
+        Self { table, store }
+    }
+}
+
+impl SqliteMetadataStore {
+    fn select(table: &str) -> String {
+        let columns = COLUMNS
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("SELECT {} FROM {}", columns, table)
+    }
+
+    fn job_from_row(row: &Row) -> rusqlite::Result<JobStoredData> {
+        use crate::job::job_data_prost::job_stored_data::Job::CronJob as CronJobType;
+        use crate::job::job_data_prost::job_stored_data::Job::NonCronJob as NonCronJobType;
+
+        let id: String = row.get(0)?;
+        let id = Uuid::parse_str(&id).unwrap_or_default();
+        let job_type = row.get::<_, Option<i32>>(4)?.unwrap_or_default();
+        let job = match JobType::from_i32(job_type) {
+            Some(JobType::Cron) => row
+                .get::<_, Option<String>>(8)?
+                .map(|schedule| CronJobType(CronJob { schedule })),
+            Some(_) => Some(NonCronJobType(NonCronJob {
+                repeating: row.get::<_, Option<bool>>(9)?.unwrap_or_default(),
+                repeated_every: row.get::<_, Option<i64>>(10)?.unwrap_or_default() as u64,
+            })),
+            None => None,
+        };
+        let after = match row.get::<_, Option<String>>(26)? {
+            Some(job_id) => Some(After {
+                job_id: Uuid::parse_str(&job_id).ok().map(Into::into),
+                delay_ms: row.get::<_, Option<i64>>(27)?.unwrap_or_default() as u64,
+            }),
+            None => None,
+        };
+        let exclusions = row
+            .get::<_, Option<Vec<u8>>>(28)?
+            .and_then(|bytes| Exclusions::decode(&*bytes).ok());
+        let interval_bounds = row
+            .get::<_, Option<Vec<u8>>>(29)?
+            .and_then(|bytes| IntervalBounds::decode(&*bytes).ok());
+        let overlap = match row.get::<_, Option<i32>>(13)? {
+            Some(kind) => Some(Overlap {
+                kind,
+                max_depth: row.get::<_, Option<i64>>(14)?.unwrap_or_default() as u32,
+                drop_oldest: row.get::<_, Option<bool>>(15)?.unwrap_or_default(),
+            }),
+            None => None,
+        };
+        let tags = row
+            .get::<_, Option<String>>(17)?
+            .filter(|tags| !tags.is_empty())
+            .map(|tags| tags.split(TAG_SEPARATOR).map(String::from).collect())
+            .unwrap_or_default();
+
+        Ok(JobStoredData {
+            id: Some(id.into()),
+            last_updated: row.get::<_, Option<i64>>(1)?.map(|i| i as u64),
+            next_tick: row.get::<_, Option<i64>>(2)?.unwrap_or_default() as u64,
+            last_tick: row.get::<_, Option<i64>>(3)?.map(|i| i as u64),
+            job_type,
+            count: row.get::<_, Option<i64>>(5)?.unwrap_or_default() as u32,
+            ran: row.get::<_, Option<bool>>(6)?.unwrap_or_default(),
+            stopped: row.get::<_, Option<bool>>(7)?.unwrap_or_default(),
+            job,
+            extra: row.get::<_, Option<Vec<u8>>>(11)?.unwrap_or_default(),
+            time_offset_seconds: row.get::<_, Option<i32>>(12)?.unwrap_or_default(),
+            overlap,
+            jitter_seconds: row.get::<_, Option<i64>>(16)?.unwrap_or_default() as u32,
+            tags,
+            queue: row.get::<_, Option<String>>(18)?.unwrap_or_default(),
+            order_key: row.get::<_, Option<i64>>(19)?.unwrap_or_default(),
+            timezone: row.get::<_, Option<String>>(20)?.unwrap_or_default(),
+            misfire: row.get::<_, Option<i32>>(21)?.unwrap_or_default(),
+            timeout_ms: row.get::<_, Option<i64>>(22)?.unwrap_or_default() as u64,
+            name: row.get::<_, Option<String>>(23)?.unwrap_or_default(),
+            priority: row.get::<_, Option<i32>>(24)?.unwrap_or_default(),
+            random_jitter_seconds: row.get::<_, Option<i64>>(25)?.unwrap_or_default() as u32,
+            after,
+            exclusions,
+            interval_bounds,
+            paused: row.get::<_, Option<bool>>(30)?.unwrap_or_default(),
+        })
+    }
+}
+
+impl DataStore<JobStoredData> for SqliteMetadataStore {
+    fn get(
+        &mut self,
+        id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<JobStoredData>, JobSchedulerError>> + Send>>
+    {
+        let store = self.store.clone();
+        let sql = SqliteMetadataStore::select(&self.table) + " WHERE id = ?1 LIMIT 1";
+        Box::pin(async move {
+            let store = store.read().await;
+            store
+                .call(JobSchedulerError::GetJobData, move |connection| {
+                    connection
+                        .query_row(&sql, params![id.to_string()], |row| {
+                            SqliteMetadataStore::job_from_row(row)
+                        })
+                        .optional()
+                })
+                .await
+        })
+    }
+
+    fn add_or_update(
+        &mut self,
+        data: JobStoredData,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        use crate::job::job_data_prost::job_stored_data::Job::CronJob as CronJobType;
+        use crate::job::job_data_prost::job_stored_data::Job::NonCronJob as NonCronJobType;
+
+        let store = self.store.clone();
+        let columns = COLUMNS.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT (id) DO UPDATE SET {}",
+            self.table,
+            columns.join(", "),
+            (1..=columns.len())
+                .map(|i| format!("?{}", i))
+                .collect::<Vec<_>>()
+                .join(", "),
+            columns
+                .iter()
+                .skip(1)
+                .map(|name| format!("{} = excluded.{}", name, name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        Box::pin(async move {
+            let uuid: Uuid = data.id.as_ref().unwrap().into();
+            let schedule = match data.job.as_ref() {
+                Some(CronJobType(ct)) => Some(ct.schedule.clone()),
+                _ => None,
+            };
+            let (repeating, repeated_every) = match data.job.as_ref() {
+                Some(NonCronJobType(ct)) => (Some(ct.repeating), Some(ct.repeated_every as i64)),
+                _ => (None, None),
+            };
+            let overlap_kind = data.overlap.as_ref().map(|o| o.kind);
+            let overlap_max_depth = data.overlap.as_ref().map(|o| o.max_depth as i64);
+            let overlap_drop_oldest = data.overlap.as_ref().map(|o| o.drop_oldest);
+            let after_job_id = data
+                .after
+                .as_ref()
+                .and_then(|a| a.job_id.as_ref())
+                .map(|id| Uuid::from(id).to_string());
+            let after_delay_ms = data.after.as_ref().map(|a| a.delay_ms as i64);
+            let exclusions = data.exclusions.as_ref().map(|e| e.encode_to_vec());
+            let interval_bounds = data.interval_bounds.as_ref().map(|b| b.encode_to_vec());
+            let tags = data.tags.join(&TAG_SEPARATOR.to_string());
+
+            let store = store.read().await;
+            store
+                .call(JobSchedulerError::CantAdd, move |connection| {
+                    connection.execute(
+                        &sql,
+                        params![
+                            uuid.to_string(),
+                            data.last_updated.map(|i| i as i64),
+                            data.next_tick as i64,
+                            data.last_tick.map(|i| i as i64),
+                            data.job_type,
+                            data.count as i64,
+                            data.ran,
+                            data.stopped,
+                            schedule,
+                            repeating,
+                            repeated_every,
+                            data.extra,
+                            data.time_offset_seconds,
+                            overlap_kind,
+                            overlap_max_depth,
+                            overlap_drop_oldest,
+                            data.jitter_seconds as i64,
+                            tags,
+                            data.queue,
+                            data.order_key,
+                            data.timezone,
+                            data.misfire,
+                            data.timeout_ms as i64,
+                            data.name,
+                            data.priority,
+                            data.random_jitter_seconds as i64,
+                            after_job_id,
+                            after_delay_ms,
+                            exclusions,
+                            interval_bounds,
+                            data.paused,
+                        ],
+                    )
+                })
+                .await
+                .map(|_| ())
+        })
+    }
+
+    fn delete(
+        &mut self,
+        guid: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let sql = format!("DELETE FROM {} WHERE id = ?1", self.table);
+        Box::pin(async move {
+            let store = store.read().await;
+            store
+                .call(JobSchedulerError::CantRemove, move |connection| {
+                    connection.execute(&sql, params![guid.to_string()])
+                })
+                .await
+                .map(|_| ())
+        })
+    }
+}
+
+impl InitStore for SqliteMetadataStore {
+    fn init(&mut self) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+        Box::pin(async move {
+            // The store can be shared with the other store, its tables are made either way
+            let mut w = store.write().await;
+            let inited = w.clone().init().await?;
+            inited
+                .call(JobSchedulerError::CantInit, move |connection| {
+                    let columns = COLUMNS
+                        .iter()
+                        .map(|(name, kind)| format!("{} {}", name, kind))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    connection.execute(
+                        &format!("CREATE TABLE IF NOT EXISTS {} ({})", table, columns),
+                        [],
+                    )?;
+                    add_missing_columns(connection, &table, COLUMNS)
+                })
+                .await?;
+            *w = inited;
+            Ok(())
+        })
+    }
+
+    fn inited(&mut self) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            Ok(store.inited())
+        })
+    }
+}
+
+impl MetaDataStorage for SqliteMetadataStore {
+    fn list_next_ticks(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        // The jobs that are due, and the ones with no next tick that are to be removed
+        let sql = format!(
+            "SELECT id, job_type, next_tick, last_tick, order_key, priority FROM {} \
+            WHERE next_tick <= ?1",
+            self.table
+        );
+        Box::pin(async move {
+            let now = Utc::now().timestamp();
+            let store = store.read().await;
+            store
+                .call(JobSchedulerError::CantListNextTicks, move |connection| {
+                    let mut statement = connection.prepare(&sql)?;
+                    let rows = statement.query_map(params![now], |row| {
+                        let id: String = row.get(0)?;
+                        let id: JobUuid = Uuid::parse_str(&id).unwrap_or_default().into();
+                        Ok(JobAndNextTick {
+                            id: Some(id),
+                            job_type: row.get(1)?,
+                            next_tick: row.get::<_, Option<i64>>(2)?.unwrap_or_default() as u64,
+                            last_tick: row.get::<_, Option<i64>>(3)?.map(|i| i as u64),
+                            order_key: row.get::<_, Option<i64>>(4)?.unwrap_or_default(),
+                            priority: row.get::<_, Option<i32>>(5)?.unwrap_or_default(),
+                        })
+                    })?;
+                    rows.collect::<rusqlite::Result<Vec<_>>>()
+                })
+                .await
+        })
+    }
+
+    fn set_next_and_last_tick(
+        &mut self,
+        guid: Uuid,
+        next_tick: Option<DateTime<Utc>>,
+        last_tick: Option<DateTime<Utc>>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let sql = format!(
+            "UPDATE {} SET next_tick = ?1, last_tick = ?2 WHERE id = ?3",
+            self.table
+        );
+        Box::pin(async move {
+            let next_tick = next_tick.map(|b| b.timestamp()).unwrap_or(0);
+            let last_tick = last_tick.map(|b| b.timestamp());
+            let store = store.read().await;
+            store
+                .call(JobSchedulerError::UpdateJobData, move |connection| {
+                    connection.execute(&sql, params![next_tick, last_tick, guid.to_string()])
+                })
+                .await
+                .map(|_| ())
+        })
+    }
+
+    fn time_till_next_job(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<std::time::Duration>, JobSchedulerError>> + Send>>
+    {
+        self.time_till_next_job_at(Utc::now())
+    }
+
+    fn time_till_next_job_at(
+        &mut self,
+        now: DateTime<Utc>,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Duration>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let sql = format!(
+            "SELECT MIN(next_tick) FROM {} WHERE next_tick > ?1",
+            self.table
+        );
+        Box::pin(async move {
+            let now = now.timestamp();
+            let store = store.read().await;
+            let next_tick = store
+                .call(
+                    JobSchedulerError::CouldNotGetTimeUntilNextTick,
+                    move |connection| {
+                        connection.query_row(&sql, params![now], |row| row.get::<_, Option<i64>>(0))
+                    },
+                )
+                .await?;
+            Ok(next_tick
+                .map(|ts| ts - now)
+                .filter(|ts| *ts > 0)
+                .map(|ts| Duration::from_secs(ts as u64)))
+        })
+    }
+
+    fn list_jobs(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobStoredData>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let sql = SqliteMetadataStore::select(&self.table);
+        Box::pin(async move {
+            let store = store.read().await;
+            store
+                .call(JobSchedulerError::ErrorLoadingJob, move |connection| {
+                    let mut statement = connection.prepare(&sql)?;
+                    let rows = statement.query_map([], SqliteMetadataStore::job_from_row)?;
+                    rows.collect::<rusqlite::Result<Vec<_>>>()
+                })
+                .await
+        })
+    }
+}
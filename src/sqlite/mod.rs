@@ -0,0 +1,129 @@
+mod metadata_store;
+mod notification_store;
+
+use crate::JobSchedulerError;
+use rusqlite::Connection;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use tracing::error;
+
+pub use metadata_store::SqliteMetadataStore;
+pub use notification_store::SqliteNotificationStore;
+
+///
+/// A SQLite database file, opened when the store is inited. The calls to the database block, so
+/// they're run on tokio's blocking threads one at a time.
+#[derive(Clone)]
+pub enum SqliteStore {
+    Created(String),
+    Inited(Arc<Mutex<Connection>>),
+}
+
+impl SqliteStore {
+    pub fn inited(&self) -> bool {
+        matches!(self, SqliteStore::Inited(_))
+    }
+}
+
+impl Default for SqliteStore {
+    fn default() -> Self {
+        let path =
+            std::env::var("SQLITE_PATH").unwrap_or_else(|_| "tokio-cron-scheduler.db".to_string());
+        Self::Created(path)
+    }
+}
+
+impl SqliteStore {
+    pub fn init(
+        self,
+    ) -> Pin<Box<dyn Future<Output = Result<SqliteStore, JobSchedulerError>> + Send>> {
+        Box::pin(async move {
+            match self {
+                SqliteStore::Created(path) => {
+                    let connection = tokio::task::spawn_blocking(move || {
+                        let connection = Connection::open(&*path)?;
+                        // The states of a notification are removed with it
+                        connection.execute_batch("PRAGMA foreign_keys = ON;")?;
+                        Ok::<_, rusqlite::Error>(connection)
+                    })
+                    .await;
+                    match connection {
+                        Ok(Ok(connection)) => {
+                            Ok(SqliteStore::Inited(Arc::new(Mutex::new(connection))))
+                        }
+                        Ok(Err(e)) => {
+                            error!("Error opening the SQLite database {:?}", e);
+                            Err(JobSchedulerError::CantInit)
+                        }
+                        Err(e) => {
+                            error!("Error opening the SQLite database {:?}", e);
+                            Err(JobSchedulerError::CantInit)
+                        }
+                    }
+                }
+                SqliteStore::Inited(connection) => Ok(SqliteStore::Inited(connection)),
+            }
+        })
+    }
+
+    ///
+    /// Run `f` with the connection on a blocking thread, `error` when the store isn't inited or
+    /// the call failed
+    pub(crate) async fn call<T, F>(
+        &self,
+        error: JobSchedulerError,
+        f: F,
+    ) -> Result<T, JobSchedulerError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> rusqlite::Result<T> + Send + 'static,
+    {
+        let connection = match self {
+            SqliteStore::Created(_) => return Err(error),
+            SqliteStore::Inited(connection) => connection.clone(),
+        };
+        let done = tokio::task::spawn_blocking(move || {
+            let mut connection = connection.lock().unwrap_or_else(|e| e.into_inner());
+            f(&mut connection)
+        })
+        .await;
+        match done {
+            Ok(Ok(done)) => Ok(done),
+            Ok(Err(e)) => {
+                error!("Error with the SQLite database {:?}", e);
+                Err(error)
+            }
+            Err(e) => {
+                error!("Error running the SQLite call {:?}", e);
+                Err(error)
+            }
+        }
+    }
+}
+
+///
+/// Add the columns a table made by an earlier version doesn't have yet
+pub(crate) fn add_missing_columns(
+    connection: &Connection,
+    table: &str,
+    columns: &[(&str, &str)],
+) -> rusqlite::Result<()> {
+    let existing = {
+        let mut statement = connection.prepare(&format!("PRAGMA table_info({})", table))?;
+        let names = statement
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        names
+    };
+    for (name, kind) in columns {
+        if existing.iter().any(|existing| existing == name) {
+            continue;
+        }
+        connection.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, name, kind),
+            [],
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,280 @@
+use crate::job::job_data_prost::{JobIdAndNotification, JobState, NotificationData};
+use crate::job::{JobId, NotificationId};
+use crate::sqlite::SqliteStore;
+use crate::store::{DataStore, InitStore, NotificationStore};
+use crate::JobSchedulerError;
+use rusqlite::{params, OptionalExtension};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+const MAIN_TABLE: &str = "notification";
+const STATES_TABLE: &str = "notification_state";
+
+///
+/// A SQLite backed notification store, with the states of each notification in a table of their
+/// own. The tables are made on init.
+#[derive(Clone)]
+pub struct SqliteNotificationStore {
+    pub store: Arc<RwLock<SqliteStore>>,
+    pub table: String,
+    pub states_table: String,
+}
+
+impl Default for SqliteNotificationStore {
+    fn default() -> Self {
+        let table = std::env::var("SQLITE_NOTIFICATION_TABLE")
+            .unwrap_or_else(|_| MAIN_TABLE.to_lowercase());
+        let states_table = std::env::var("SQLITE_NOTIFICATION_STATES_TABLE")
+            .unwrap_or_else(|_| STATES_TABLE.to_lowercase());
+        let store = Arc::new(RwLock::new(SqliteStore::default()));
+        Self {
+            table,
+            states_table,
+            store,
+        }
+    }
+}
+
+fn parse_uuid(id: String) -> Uuid {
+    Uuid::parse_str(&id).unwrap_or_default()
+}
+
+impl DataStore<NotificationData> for SqliteNotificationStore {
+    fn get(
+        &mut self,
+        id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<NotificationData>, JobSchedulerError>> + Send>>
+    {
+        let store = self.store.clone();
+        let sql = format!("SELECT job_id, extra FROM {} WHERE id = ?1", self.table);
+        let states_sql = format!("SELECT state FROM {} WHERE id = ?1", self.states_table);
+        Box::pin(async move {
+            let store = store.read().await;
+            store
+                .call(JobSchedulerError::GetJobData, move |connection| {
+                    let row = connection
+                        .query_row(&sql, params![id.to_string()], |row| {
+                            Ok((
+                                parse_uuid(row.get(0)?),
+                                row.get::<_, Option<Vec<u8>>>(1)?.unwrap_or_default(),
+                            ))
+                        })
+                        .optional()?;
+                    let (job_id, extra) = match row {
+                        Some(row) => row,
+                        None => return Ok(None),
+                    };
+                    let job_states = {
+                        let mut statement = connection.prepare(&states_sql)?;
+                        let states = statement
+                            .query_map(params![id.to_string()], |row| row.get::<_, i32>(0))?
+                            .collect::<rusqlite::Result<Vec<_>>>()?;
+                        states
+                    };
+                    Ok(Some(NotificationData {
+                        job_id: Some(JobIdAndNotification {
+                            job_id: Some(job_id.into()),
+                            notification_id: Some(id.into()),
+                        }),
+                        job_states,
+                        extra,
+                    }))
+                })
+                .await
+        })
+    }
+
+    fn add_or_update(
+        &mut self,
+        data: NotificationData,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let sql = format!(
+            "INSERT INTO {} (id, job_id, extra) VALUES (?1, ?2, ?3) \
+             ON CONFLICT (id) DO UPDATE SET job_id = excluded.job_id, extra = excluded.extra",
+            self.table
+        );
+        let delete_states_sql = format!("DELETE FROM {} WHERE id = ?1", self.states_table);
+        let state_sql = format!(
+            "INSERT INTO {} (id, state) VALUES (?1, ?2)",
+            self.states_table
+        );
+        Box::pin(async move {
+            let (job_id, notification_id) = match data.job_id_and_notification_id_from_data() {
+                Some((job_id, notification_id)) => (job_id, notification_id),
+                None => return Err(JobSchedulerError::UpdateJobData),
+            };
+            let store = store.read().await;
+            store
+                .call(JobSchedulerError::CantAdd, move |connection| {
+                    let transaction = connection.transaction()?;
+                    transaction.execute(
+                        &sql,
+                        params![notification_id.to_string(), job_id.to_string(), data.extra],
+                    )?;
+                    transaction
+                        .execute(&delete_states_sql, params![notification_id.to_string()])?;
+                    for state in data.job_states {
+                        transaction
+                            .execute(&state_sql, params![notification_id.to_string(), state])?;
+                    }
+                    transaction.commit()
+                })
+                .await
+        })
+    }
+
+    fn delete(
+        &mut self,
+        guid: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let sql = format!("DELETE FROM {} WHERE id = ?1", self.table);
+        Box::pin(async move {
+            let store = store.read().await;
+            store
+                .call(JobSchedulerError::CantRemove, move |connection| {
+                    connection.execute(&sql, params![guid.to_string()])
+                })
+                .await
+                .map(|_| ())
+        })
+    }
+}
+
+impl InitStore for SqliteNotificationStore {
+    fn init(&mut self) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+        let states_table = self.states_table.clone();
+        Box::pin(async move {
+            // The store can be shared with the other store, its tables are made either way
+            let mut w = store.write().await;
+            let inited = w.clone().init().await?;
+            inited
+                .call(JobSchedulerError::CantInit, move |connection| {
+                    connection.execute_batch(&format!(
+                        "CREATE TABLE IF NOT EXISTS {table} (\
+                            id TEXT NOT NULL PRIMARY KEY, \
+                            job_id TEXT, \
+                            extra BLOB\
+                        ); \
+                        CREATE TABLE IF NOT EXISTS {states_table} (\
+                            id TEXT NOT NULL, \
+                            state INTEGER NOT NULL, \
+                            PRIMARY KEY (id, state), \
+                            FOREIGN KEY (id) REFERENCES {table} (id) ON DELETE CASCADE\
+                        ); \
+                        CREATE INDEX IF NOT EXISTS {table}_job_id ON {table} (job_id);",
+                        table = table,
+                        states_table = states_table
+                    ))
+                })
+                .await?;
+            *w = inited;
+            Ok(())
+        })
+    }
+
+    fn inited(&mut self) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            Ok(store.inited())
+        })
+    }
+}
+
+impl NotificationStore for SqliteNotificationStore {
+    fn list_notification_guids_for_job_and_state(
+        &mut self,
+        job: JobId,
+        state: JobState,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<NotificationId>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let sql = format!(
+            "SELECT DISTINCT n.id FROM {} AS n JOIN {} AS st ON st.id = n.id \
+             WHERE n.job_id = ?1 AND st.state = ?2",
+            self.table, self.states_table
+        );
+        Box::pin(async move {
+            let state = state as i32;
+            let store = store.read().await;
+            store
+                .call(JobSchedulerError::CantListGuids, move |connection| {
+                    let mut statement = connection.prepare(&sql)?;
+                    let ids = statement
+                        .query_map(params![job.to_string(), state], |row| {
+                            row.get(0).map(parse_uuid)
+                        })?
+                        .collect::<rusqlite::Result<Vec<_>>>();
+                    ids
+                })
+                .await
+        })
+    }
+
+    fn list_notification_guids_for_job_id(
+        &mut self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Uuid>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let sql = format!("SELECT DISTINCT id FROM {} WHERE job_id = ?1", self.table);
+        Box::pin(async move {
+            let store = store.read().await;
+            store
+                .call(JobSchedulerError::CantListGuids, move |connection| {
+                    let mut statement = connection.prepare(&sql)?;
+                    let ids = statement
+                        .query_map(params![job_id.to_string()], |row| {
+                            row.get(0).map(parse_uuid)
+                        })?
+                        .collect::<rusqlite::Result<Vec<_>>>();
+                    ids
+                })
+                .await
+        })
+    }
+
+    fn delete_notification_for_state(
+        &mut self,
+        notification_id: Uuid,
+        state: JobState,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let sql = format!(
+            "DELETE FROM {} WHERE id = ?1 AND state = ?2",
+            self.states_table
+        );
+        Box::pin(async move {
+            let state = state as i32;
+            let store = store.read().await;
+            store
+                .call(JobSchedulerError::CantRemove, move |connection| {
+                    connection.execute(&sql, params![notification_id.to_string(), state])
+                })
+                .await
+                .map(|deleted| deleted > 0)
+        })
+    }
+
+    fn delete_for_job(
+        &mut self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let sql = format!("DELETE FROM {} WHERE job_id = ?1", self.table);
+        Box::pin(async move {
+            let store = store.read().await;
+            store
+                .call(JobSchedulerError::CantRemove, move |connection| {
+                    connection.execute(&sql, params![job_id.to_string()])
+                })
+                .await
+                .map(|_| ())
+        })
+    }
+}
@@ -0,0 +1,275 @@
+#[cfg(feature = "metrics")]
+use crate::instruments;
+#[cfg(feature = "metrics")]
+use crate::latency_histogram::LatencyHistogram;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+///
+/// Why a fire of a job didn't lead to a run
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SkipReason {
+    /// The previous run was still going, see `OverlapPolicy`
+    Overlap,
+    /// The last run failed, see `set_skip_after_failure`
+    AfterFailure,
+    /// The job's queue was full, see `add_queue`
+    QueueFull,
+    /// The fire fell on the job's calendar or the global one, see `JobBuilder::with_calendar`
+    Excluded,
+    /// A rate limit of one of the job's tags had no room, see `set_tag_rate_limit`
+    RateLimited,
+}
+
+///
+/// Run counters for a single job
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct JobRunStats {
+    pub runs: u64,
+    pub failures: u64,
+    pub skipped: u64,
+    /// The skipped fires by why they were skipped, adding up to `skipped`
+    pub skipped_by: HashMap<SkipReason, u64>,
+    /// The runs a rate limit delayed or skipped, see `set_tag_rate_limit`
+    pub throttled: u64,
+    pub running: u32,
+    /// Whether the last run that finished failed
+    pub last_failed: bool,
+    /// The error of the last run that failed
+    pub last_error: Option<String>,
+    pub last_duration: Option<Duration>,
+    pub total_duration: Duration,
+    /// How long after its scheduled time the job fired the last time
+    pub last_lateness: Option<Duration>,
+    /// How long the runs took, for percentiles
+    #[cfg(feature = "metrics")]
+    pub latency: LatencyHistogram,
+}
+
+///
+/// Snapshot of the counters kept by the job runner
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SchedulerStats {
+    pub jobs: HashMap<Uuid, JobRunStats>,
+    pub running: usize,
+}
+
+impl JobRunStats {
+    pub fn skipped_for(&self, reason: SkipReason) -> u64 {
+        self.skipped_by.get(&reason).copied().unwrap_or_default()
+    }
+
+    ///
+    /// How long the runs that finished took on average, None before the first one finished
+    pub fn average_duration(&self) -> Option<Duration> {
+        let finished = self.runs.saturating_sub(self.running as u64);
+        (finished > 0).then(|| self.total_duration.div_f64(finished as f64))
+    }
+}
+
+impl SchedulerStats {
+    pub fn jobs_total(&self) -> usize {
+        self.jobs.len()
+    }
+
+    pub fn runs_total(&self) -> u64 {
+        self.jobs.values().map(|j| j.runs).sum()
+    }
+
+    pub fn failures_total(&self) -> u64 {
+        self.jobs.values().map(|j| j.failures).sum()
+    }
+
+    pub fn skipped_total(&self) -> u64 {
+        self.jobs.values().map(|j| j.skipped).sum()
+    }
+
+    pub fn job(&self, job_id: &Uuid) -> Option<&JobRunStats> {
+        self.jobs.get(job_id)
+    }
+
+    pub(crate) fn added(&mut self, job_id: Uuid) {
+        self.jobs.entry(job_id).or_default();
+        #[cfg(feature = "metrics")]
+        instruments::jobs(self.jobs.len());
+    }
+
+    pub(crate) fn removed(&mut self, job_id: &Uuid) {
+        self.jobs.remove(job_id);
+        #[cfg(feature = "metrics")]
+        instruments::jobs(self.jobs.len());
+    }
+
+    pub(crate) fn started(&mut self, job_id: Uuid) {
+        let job = self.jobs.entry(job_id).or_default();
+        job.runs += 1;
+        job.running += 1;
+        self.running += 1;
+        #[cfg(feature = "metrics")]
+        instruments::run_started(job_id, self.running);
+    }
+
+    pub(crate) fn fired(&mut self, job_id: Uuid, lateness: Duration) {
+        let job = self.jobs.entry(job_id).or_default();
+        job.last_lateness = Some(lateness);
+        #[cfg(feature = "metrics")]
+        instruments::fired_late(job_id, lateness);
+    }
+
+    pub(crate) fn reset_failure(&mut self, job_id: &Uuid) {
+        if let Some(job) = self.jobs.get_mut(job_id) {
+            job.last_failed = false;
+        }
+    }
+
+    pub(crate) fn skipped(&mut self, job_id: Uuid, reason: SkipReason) {
+        let job = self.jobs.entry(job_id).or_default();
+        job.skipped += 1;
+        *job.skipped_by.entry(reason).or_default() += 1;
+        #[cfg(feature = "metrics")]
+        instruments::run_skipped(job_id, reason);
+    }
+
+    pub(crate) fn throttled(&mut self, job_id: Uuid, tag: &str, skipped: bool) {
+        let job = self.jobs.entry(job_id).or_default();
+        job.throttled += 1;
+        #[cfg(feature = "metrics")]
+        instruments::run_throttled(job_id, tag, skipped);
+        #[cfg(not(feature = "metrics"))]
+        let _ = (tag, skipped);
+    }
+
+    pub(crate) fn reset(&mut self, job_id: &Uuid) {
+        if let Some(job) = self.jobs.get_mut(job_id) {
+            *job = JobRunStats {
+                running: job.running,
+                last_failed: job.last_failed,
+                last_error: job.last_error.take(),
+                ..Default::default()
+            };
+        }
+    }
+
+    pub(crate) fn finished(&mut self, job_id: Uuid, failed: bool, duration: Duration) {
+        let job = self.jobs.entry(job_id).or_default();
+        if failed {
+            job.failures += 1;
+        }
+        job.last_failed = failed;
+        job.last_duration = Some(duration);
+        job.total_duration += duration;
+        #[cfg(feature = "metrics")]
+        job.latency.record(duration);
+        job.running = job.running.saturating_sub(1);
+        self.running = self.running.saturating_sub(1);
+        #[cfg(feature = "metrics")]
+        instruments::run_finished(job_id, failed, duration, self.running);
+    }
+
+    pub(crate) fn failed_with(&mut self, job_id: Uuid, error: String) {
+        let job = self.jobs.entry(job_id).or_default();
+        job.last_error = Some(error);
+    }
+
+    ///
+    /// Render the counters in the Prometheus text exposition format
+    #[cfg(feature = "metrics")]
+    pub fn to_prometheus(&self) -> String {
+        use std::fmt::Write;
+
+        let mut ids = self.jobs.keys().collect::<Vec<_>>();
+        ids.sort();
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "# HELP tokio_cron_jobs_total Number of jobs in the scheduler"
+        );
+        let _ = writeln!(out, "# TYPE tokio_cron_jobs_total gauge");
+        let _ = writeln!(out, "tokio_cron_jobs_total {}", self.jobs_total());
+        let _ = writeln!(
+            out,
+            "# HELP tokio_cron_job_runs_total Number of runs per job"
+        );
+        let _ = writeln!(out, "# TYPE tokio_cron_job_runs_total counter");
+        for id in ids.iter() {
+            let _ = writeln!(
+                out,
+                "tokio_cron_job_runs_total{{id=\"{}\"}} {}",
+                id, self.jobs[id].runs
+            );
+        }
+        let _ = writeln!(
+            out,
+            "# HELP tokio_cron_job_failures_total Number of failed job runs"
+        );
+        let _ = writeln!(out, "# TYPE tokio_cron_job_failures_total counter");
+        let _ = writeln!(
+            out,
+            "tokio_cron_job_failures_total {}",
+            self.failures_total()
+        );
+        let _ = writeln!(
+            out,
+            "# HELP tokio_cron_job_skipped_total Number of job runs skipped or dropped because of overlap or a failed run"
+        );
+        let _ = writeln!(out, "# TYPE tokio_cron_job_skipped_total counter");
+        let _ = writeln!(out, "tokio_cron_job_skipped_total {}", self.skipped_total());
+        let _ = writeln!(
+            out,
+            "# HELP tokio_cron_job_throttled_total Number of job runs delayed or skipped by a rate limit"
+        );
+        let _ = writeln!(out, "# TYPE tokio_cron_job_throttled_total counter");
+        for id in ids.iter() {
+            let _ = writeln!(
+                out,
+                "tokio_cron_job_throttled_total{{id=\"{}\"}} {}",
+                id, self.jobs[id].throttled
+            );
+        }
+        let _ = writeln!(
+            out,
+            "# HELP tokio_cron_job_duration_seconds How long the runs of a job took"
+        );
+        let _ = writeln!(out, "# TYPE tokio_cron_job_duration_seconds summary");
+        for id in ids {
+            let job = &self.jobs[id];
+            for quantile in [0.5, 0.95, 0.99] {
+                if let Some(duration) = job.latency.percentile(quantile * 100.0) {
+                    let _ = writeln!(
+                        out,
+                        "tokio_cron_job_duration_seconds{{id=\"{}\",quantile=\"{}\"}} {}",
+                        id,
+                        quantile,
+                        duration.as_secs_f64()
+                    );
+                }
+            }
+            let _ = writeln!(
+                out,
+                "tokio_cron_job_duration_seconds_sum{{id=\"{}\"}} {}",
+                id,
+                job.total_duration.as_secs_f64()
+            );
+            let _ = writeln!(
+                out,
+                "tokio_cron_job_duration_seconds_count{{id=\"{}\"}} {}",
+                id,
+                job.latency.count()
+            );
+        }
+        let _ = writeln!(
+            out,
+            "# HELP tokio_cron_running_jobs Number of jobs currently running"
+        );
+        let _ = writeln!(out, "# TYPE tokio_cron_running_jobs gauge");
+        let _ = writeln!(out, "tokio_cron_running_jobs {}", self.running);
+        out
+    }
+}
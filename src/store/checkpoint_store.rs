@@ -0,0 +1,21 @@
+use crate::JobSchedulerError;
+use chrono::{DateTime, Utc};
+use std::future::Future;
+use std::pin::Pin;
+use uuid::Uuid;
+
+pub type PinnedCheckpointFuture =
+    Pin<Box<dyn Future<Output = Result<Option<DateTime<Utc>>, JobSchedulerError>> + Send>>;
+
+///
+/// Keeps when the last successful run of an at least once job started, so fires that were missed
+/// while the scheduler was down can be run when the job is added again
+pub trait CheckpointStore {
+    fn get(&mut self, job_id: Uuid) -> PinnedCheckpointFuture;
+
+    fn set(
+        &mut self,
+        job_id: Uuid,
+        ran_at: DateTime<Utc>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>>;
+}
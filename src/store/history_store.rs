@@ -0,0 +1,50 @@
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::JobState;
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::JobState;
+use crate::JobSchedulerError;
+use chrono::{DateTime, Utc};
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+use uuid::Uuid;
+
+///
+/// A run of a job that finished, as kept by a `HistoryStore`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunRecord {
+    pub job_id: Uuid,
+    /// When the run started, by the scheduler's clock
+    pub started: DateTime<Utc>,
+    pub duration: Duration,
+    /// `JobState::Done` when the run went well, `JobState::Failed` or `JobState::TimedOut` when
+    /// it didn't, `JobState::Cancelled` when the next fire cancelled it
+    pub outcome: JobState,
+    /// What went wrong when it failed
+    pub error: Option<String>,
+}
+
+///
+/// Keeps the runs of jobs that finished, every attempt of a job that's retried among them, for
+/// `JobScheduler::job_history`
+pub trait HistoryStore {
+    fn record(
+        &mut self,
+        run: RunRecord,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>>;
+
+    ///
+    /// The last `limit` runs of a job, the last one first
+    fn list(
+        &mut self,
+        job_id: Uuid,
+        limit: usize,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<RunRecord>, JobSchedulerError>> + Send>>;
+
+    ///
+    /// Called when a job is removed. Stores that keep the runs of removed jobs can leave them.
+    fn delete(
+        &mut self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>>;
+}
@@ -0,0 +1,21 @@
+use crate::JobSchedulerError;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+///
+/// A lease shared by the scheduler instances that use the same metadata store, held by one of
+/// them at a time. Kept up by a `LeaderLease`.
+pub trait LeaseStore {
+    ///
+    /// Take the lease, or keep it when this instance has it already, for at least `ttl`. Whether
+    /// it's held by this instance afterwards.
+    fn acquire(
+        &mut self,
+        ttl: Duration,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>>;
+
+    ///
+    /// Give the lease up if this instance has it, so another one can take it right away
+    fn release(&mut self) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>>;
+}
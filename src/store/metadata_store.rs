@@ -2,7 +2,7 @@
 use crate::job::job_data::{JobAndNextTick, JobStoredData};
 #[cfg(feature = "has_bytes")]
 use crate::job::job_data_prost::{JobAndNextTick, JobStoredData};
-use crate::job::JobToRunAsync;
+use crate::job::{JobDescription, JobToRunAsync};
 use crate::store::{CodeGet, DataStore, InitStore};
 use crate::JobSchedulerError;
 use chrono::{DateTime, Utc};
@@ -14,6 +14,23 @@ pub trait MetaDataStorage: DataStore<JobStoredData> + InitStore {
     fn list_next_ticks(
         &mut self,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>>;
+
+    ///
+    /// The next ticks of the jobs that are due by `now`, and of the ones with no next tick left
+    /// that are to be removed, what the scheduler loop looks at every pass. Goes through
+    /// `list_next_ticks` unless the store has a better way.
+    fn list_due_ticks(
+        &mut self,
+        now: DateTime<Utc>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>> {
+        let next_ticks = self.list_next_ticks();
+        let now = now.timestamp().max(0) as u64;
+        Box::pin(async move {
+            let mut next_ticks = next_ticks.await?;
+            next_ticks.retain(|n| n.next_tick <= now);
+            Ok(next_ticks)
+        })
+    }
     fn set_next_and_last_tick(
         &mut self,
         guid: Uuid,
@@ -23,6 +40,57 @@ pub trait MetaDataStorage: DataStore<JobStoredData> + InitStore {
     fn time_till_next_job(
         &mut self,
     ) -> Pin<Box<dyn Future<Output = Result<Option<std::time::Duration>, JobSchedulerError>> + Send>>;
+
+    ///
+    /// How long from `now` until the soonest next tick after it, with `now` the time of the
+    /// scheduler's clock, what the scheduler loop sleeps towards. Goes through `list_next_ticks`
+    /// unless the store has a better way.
+    fn time_till_next_job_at(
+        &mut self,
+        now: DateTime<Utc>,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<std::time::Duration>, JobSchedulerError>> + Send>>
+    {
+        let next_ticks = self.list_next_ticks();
+        let now = now.timestamp().max(0) as u64;
+        Box::pin(async move {
+            let next_ticks = next_ticks.await?;
+            Ok(next_ticks
+                .iter()
+                .map(|n| n.next_tick)
+                .filter(|next_tick| *next_tick > now)
+                .min()
+                .map(|next_tick| std::time::Duration::from_secs(next_tick - now)))
+        })
+    }
+
+    ///
+    /// Every job in the store. A store that can't list its jobs can leave it out, the scheduler's
+    /// methods that go through all jobs, like `export_state` and the ones by tag, then fail with
+    /// `JobSchedulerError::CantListJobs`.
+    fn list_jobs(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobStoredData>, JobSchedulerError>> + Send>> {
+        Box::pin(async { Err(JobSchedulerError::CantListJobs) })
+    }
+
+    ///
+    /// Describe every job in the store, with the next `upcoming` fires of its schedule. Goes
+    /// through `list_jobs` unless the store has a better way. Jobs whose metadata can't be read
+    /// are left out.
+    fn describe(
+        &mut self,
+        upcoming: usize,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobDescription>, JobSchedulerError>> + Send>> {
+        let jobs = self.list_jobs();
+        Box::pin(async move {
+            let jobs = jobs.await?;
+            Ok(jobs
+                .iter()
+                .filter_map(|data| JobDescription::from_stored(data, upcoming))
+                .collect())
+        })
+    }
 }
 
+#[allow(dead_code)]
 pub trait JobCodeGet: CodeGet<Box<JobToRunAsync>> {}
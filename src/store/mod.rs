@@ -2,9 +2,15 @@ use crate::JobSchedulerError;
 use std::future::Future;
 use std::pin::Pin;
 use uuid::Uuid;
+mod checkpoint_store;
+mod history_store;
+mod lease_store;
 mod metadata_store;
 mod notification_store;
 
+pub use checkpoint_store::{CheckpointStore, PinnedCheckpointFuture};
+pub use history_store::{HistoryStore, RunRecord};
+pub use lease_store::LeaseStore;
 pub use metadata_store::MetaDataStorage;
 pub use notification_store::NotificationStore;
 
@@ -33,6 +39,7 @@ where
     ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>>;
 }
 
+#[allow(dead_code)]
 pub trait CodeGet<CODE>
 where
     CODE: Sized,
@@ -0,0 +1,201 @@
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::JobType;
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::JobType;
+use crate::job::{CatchUnwind, JobContext, JobLocked, JobRunner, JobToRunAsync};
+use crate::job_scheduler::JobsSchedulerLocked;
+use crate::scheduler::Scheduler;
+use crate::{JobSchedulerError, JobStoredData};
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use uuid::Uuid;
+
+///
+/// A run of a job by `TestScheduler::tick`
+#[derive(Debug)]
+pub struct TestRun {
+    pub job_id: Uuid,
+    /// When the run was due
+    pub scheduled: DateTime<Utc>,
+    /// Err with `JobSchedulerError::JobPanicked` when the job panicked, and with
+    /// `JobSchedulerError::RunFailed` and the error when the run of a fallible job failed
+    pub result: Result<(), JobSchedulerError>,
+}
+
+struct TestJob {
+    data: JobStoredData,
+    run: Box<JobToRunAsync>,
+    run_count: u64,
+}
+
+///
+/// Runs jobs on a clock of its own for tests, without a scheduler loop or runner: `tick` runs
+/// the jobs that are due at the time it's given one after another on the calling task and
+/// returns how they went, so a test can move the clock on, tick and check what the jobs did.
+/// The next fires are worked out as the scheduler does, a job that's due more than once by then
+/// runs once, like a scheduler that was held up. Queues, overlap policies, limits and the other
+/// settings of a scheduler, retry policies among them, don't apply. The jobs are given a
+/// scheduler of their own that isn't started, since a job's run takes one.
+///
+/// ```rust
+/// # use chrono::{TimeZone, Utc};
+/// # use std::sync::atomic::{AtomicUsize, Ordering};
+/// # use std::sync::Arc;
+/// # use std::time::Duration;
+/// # use tokio_cron_scheduler::{Job, JobSchedulerError, TestScheduler};
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), JobSchedulerError> {
+/// # let hits = Arc::new(AtomicUsize::new(0));
+/// let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+/// let mut sched = TestScheduler::new(start).await?;
+/// sched.add(Job::new("0 */5 * * * *", move |_uuid, _l| { hits.fetch_add(1, Ordering::SeqCst); })?)?;
+/// assert!(sched.advance(Duration::from_secs(60)).await.is_empty());
+/// assert_eq!(sched.advance(Duration::from_secs(240)).await.len(), 1);
+/// # Ok(())
+/// # }
+/// ```
+pub struct TestScheduler {
+    now: DateTime<Utc>,
+    jobs: Vec<TestJob>,
+    scheduler: JobsSchedulerLocked,
+}
+
+impl TestScheduler {
+    pub async fn new(now: DateTime<Utc>) -> Result<Self, JobSchedulerError> {
+        Ok(Self {
+            now,
+            jobs: vec![],
+            scheduler: JobsSchedulerLocked::new().await?,
+        })
+    }
+
+    ///
+    /// The time of the clock, as given to `new` or the last `tick`
+    pub fn now(&self) -> DateTime<Utc> {
+        self.now
+    }
+
+    ///
+    /// Add a job with its first fire worked out from the clock, as a scheduler does when a job is
+    /// added. A one shot job made for a time keeps that time, one made to fire after a wait fires
+    /// that long after the clock's time. Use `add_at` to give a job another first fire.
+    pub fn add(&mut self, mut job: JobLocked) -> Result<Uuid, JobSchedulerError> {
+        job.anchor(self.now)?;
+        let data = job.job_data()?;
+        Ok(self.push(job, data))
+    }
+
+    ///
+    /// Add a job that first fires at `at`, then keeps to its schedule
+    pub fn add_at(
+        &mut self,
+        mut job: JobLocked,
+        at: DateTime<Utc>,
+    ) -> Result<Uuid, JobSchedulerError> {
+        let mut data = job.job_data()?;
+        data.set_next_tick(Some(at));
+        Ok(self.push(job, data))
+    }
+
+    fn push(&mut self, job: JobLocked, data: JobStoredData) -> Uuid {
+        let job_id = job.guid();
+        self.jobs
+            .retain(|job| job.data.id.as_ref().map(Uuid::from) != Some(job_id));
+        self.jobs.push(TestJob {
+            data,
+            run: job.into_run(),
+            run_count: 0,
+        });
+        job_id
+    }
+
+    ///
+    /// Remove a job, returns whether it was there
+    pub fn remove(&mut self, job_id: &Uuid) -> bool {
+        let before = self.jobs.len();
+        self.jobs
+            .retain(|job| job.data.id.as_ref().map(Uuid::from) != Some(*job_id));
+        self.jobs.len() != before
+    }
+
+    ///
+    /// When a job fires next, None when it's not there or won't fire again
+    pub fn next_tick(&self, job_id: &Uuid) -> Option<DateTime<Utc>> {
+        self.jobs
+            .iter()
+            .find(|job| job.data.id.as_ref().map(Uuid::from) == Some(*job_id))
+            .and_then(|job| job.data.next_tick_utc())
+    }
+
+    ///
+    /// Set the clock to `now` and run the jobs due by then, soonest first and in the order the
+    /// scheduler fires them when they're at the same time. Each run is done before the next one
+    /// starts. One shot jobs are removed once they ran.
+    pub async fn tick(&mut self, now: DateTime<Utc>) -> Vec<TestRun> {
+        self.now = now;
+        let mut due = self
+            .jobs
+            .iter()
+            .enumerate()
+            .filter(|(_, job)| !job.data.stopped)
+            .filter_map(|(i, job)| {
+                let at = job.data.next_tick_utc().filter(|at| *at <= now)?;
+                let job_id = job.data.id.as_ref().map(Uuid::from).unwrap_or_default();
+                let data = &job.data;
+                let order = Scheduler::fire_order(at, data.priority, data.order_key, job_id);
+                Some((order, i))
+            })
+            .collect::<Vec<_>>();
+        due.sort();
+
+        let mut runs = vec![];
+        for ((scheduled, ..), i) in due {
+            let scheduler = self.scheduler.clone();
+            let job = &mut self.jobs[i];
+            let job_id = job.data.id.as_ref().map(Uuid::from).unwrap_or_default();
+            // As the scheduler loop does, a cron job goes on from now, a repeated job from the
+            // fire that was due
+            let next = match JobType::from_i32(job.data.job_type) {
+                Some(JobType::Cron) => JobsSchedulerLocked::fire_after(&job.data, now),
+                Some(JobType::Repeated) => JobsSchedulerLocked::fire_after(&job.data, scheduled),
+                _ => None,
+            };
+            job.data.set_next_tick(next);
+            job.data.set_last_tick(Some(now));
+            job.run_count += 1;
+            let context = JobContext {
+                job_id,
+                name: job.data.name.clone(),
+                scheduled,
+                attempt: 1,
+                next_run: next,
+                run_count: job.run_count,
+                extra: job.data.extra.clone(),
+            };
+            let run = (job.run)(job_id, scheduler);
+            let result = CatchUnwind(Box::pin(context.scope(run)))
+                .await
+                .map_err(JobRunner::panicked)
+                .and_then(|ran| {
+                    ran.map_err(|failure| JobSchedulerError::RunFailed(failure.error.to_string()))
+                });
+            runs.push(TestRun {
+                job_id,
+                scheduled,
+                result,
+            });
+        }
+        self.jobs.retain(|job| job.data.next_tick != 0);
+        runs
+    }
+
+    ///
+    /// Move the clock on by `duration` and `tick`
+    pub async fn advance(&mut self, duration: Duration) -> Vec<TestRun> {
+        let now = chrono::Duration::from_std(duration)
+            .ok()
+            .and_then(|duration| self.now.checked_add_signed(duration))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        self.tick(now).await
+    }
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_cron_scheduler::{AfterJob, Job, JobSchedulerError};
+
+fn every(secs: u64) -> Job {
+    common::counted(Duration::from_secs(secs)).0
+}
+
+#[tokio::test(start_paused = true)]
+async fn fires_the_delay_after_the_run_of_the_other_job() {
+    let sched = common::scheduler().await;
+    let started = tokio::time::Instant::now();
+    let fired = Arc::new(Mutex::new(vec![]));
+    let seen = fired.clone();
+    let parent = sched.add(every(10)).await.unwrap();
+    let child = sched
+        .add(
+            Job::new_repeated(Duration::from_secs(3), move |_, _| {
+                seen.lock().unwrap().push(started.elapsed());
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+    let after = AfterJob {
+        job_id: parent,
+        delay: Duration::from_secs(5),
+    };
+    sched.set_after_job(&child, Some(after)).await.unwrap();
+    sched.start().await.unwrap();
+
+    // The parent runs at 10 and 20 seconds, its own schedule doesn't fire the child
+    tokio::time::sleep(Duration::from_secs(27)).await;
+    let fired = fired.lock().unwrap().clone();
+    assert_eq!(fired.len(), 2, "{:?}", fired);
+    for (fire, due) in fired.iter().zip([15, 25]) {
+        assert!(*fire >= Duration::from_secs(due), "{:?}", fired);
+        assert!(*fire < Duration::from_secs(due + 1), "{:?}", fired);
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_job_fired_after_itself_is_rejected() {
+    let sched = common::scheduler().await;
+    let first = sched.add(every(10)).await.unwrap();
+    let second = sched.add(every(10)).await.unwrap();
+    let third = sched.add(every(10)).await.unwrap();
+    let after = |job_id| {
+        Some(AfterJob {
+            job_id,
+            delay: Duration::ZERO,
+        })
+    };
+    sched.set_after_job(&second, after(first)).await.unwrap();
+    sched.set_after_job(&third, after(second)).await.unwrap();
+
+    let err = sched.set_after_job(&first, after(third)).await.unwrap_err();
+    assert!(matches!(err, JobSchedulerError::InvalidTrigger(_)));
+    let err = sched.set_after_job(&first, after(first)).await.unwrap_err();
+    assert!(matches!(err, JobSchedulerError::InvalidTrigger(_)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_one_shot_is_rejected() {
+    let sched = common::scheduler().await;
+    let parent = sched.add(every(10)).await.unwrap();
+    let one_shot = sched
+        .add(Job::new_one_shot(Duration::from_secs(30), |_, _| {}).unwrap())
+        .await
+        .unwrap();
+    let after = AfterJob {
+        job_id: parent,
+        delay: Duration::ZERO,
+    };
+    let err = sched
+        .set_after_job(&one_shot, Some(after))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, JobSchedulerError::InvalidTrigger(_)));
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tokio::sync::watch;
+
+#[tokio::test(start_paused = true)]
+async fn fires_are_passed_over_while_backpressured() {
+    let mut sched = common::scheduler().await;
+    let (job, runs) = common::counted(Duration::from_secs(10));
+    let job_id = sched.add(job).await.unwrap();
+    let (overloaded_tx, overloaded_rx) = watch::channel(true);
+    sched.set_backpressure(overloaded_rx);
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(35)).await;
+    assert!(sched.is_backpressured());
+    assert_eq!(runs.load(Ordering::SeqCst), 0);
+    // The schedule went on meanwhile
+    assert_eq!(
+        sched.next_tick_for_job(job_id).await.unwrap(),
+        Some(common::start() + chrono::Duration::seconds(40))
+    );
+
+    overloaded_tx.send(false).unwrap();
+    assert!(!sched.is_backpressured());
+    // The fires at 10, 20 and 30 seconds aren't caught up on
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn removing_the_backpressure_fires_jobs_again() {
+    let mut sched = common::scheduler().await;
+    let (job, runs) = common::counted(Duration::from_secs(10));
+    sched.add(job).await.unwrap();
+    let (_overloaded_tx, overloaded_rx) = watch::channel(true);
+    sched.set_backpressure(overloaded_rx);
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(15)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 0);
+    sched.remove_backpressure();
+    assert!(!sched.is_backpressured());
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
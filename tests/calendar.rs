@@ -0,0 +1,75 @@
+mod common;
+
+use chrono::NaiveTime;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_cron_scheduler::{Calendar, CalendarAction, JobChange, JobScheduler, SkipReason};
+
+// Nothing fires from 15 to 35 seconds past midnight
+fn window(action: CalendarAction) -> Calendar {
+    Calendar::new()
+        .exclude_window(
+            NaiveTime::from_hms_opt(0, 0, 15).unwrap(),
+            NaiveTime::from_hms_opt(0, 0, 35).unwrap(),
+        )
+        .with_action(action)
+}
+
+// A job that fires every 10 seconds, keeping when its runs were due
+async fn every_ten_seconds(sched: &JobScheduler) -> (uuid::Uuid, common::Fires) {
+    let (job, fires) = common::recorded(Duration::from_secs(10));
+    (sched.add(job).await.unwrap(), fires)
+}
+
+#[tokio::test(start_paused = true)]
+async fn excluded_fires_are_passed_over() {
+    let sched = common::scheduler().await;
+    let (job_id, fires) = every_ten_seconds(&sched).await;
+    sched
+        .set_calendar(&job_id, Some(window(CalendarAction::Skip)))
+        .await
+        .unwrap();
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(45)).await;
+    assert_eq!(*fires.lock().unwrap(), vec![10, 40]);
+    let stats = sched.stats().await.job(&job_id).unwrap().clone();
+    assert_eq!(stats.skipped_for(SkipReason::Excluded), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn deferred_fires_run_when_the_exclusion_is_over() {
+    let sched = common::scheduler().await;
+    let (job_id, fires) = every_ten_seconds(&sched).await;
+    sched
+        .set_calendar(&job_id, Some(window(CalendarAction::Defer)))
+        .await
+        .unwrap();
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(50)).await;
+    assert_eq!(*fires.lock().unwrap(), vec![10, 35, 45]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn the_global_calendar_applies_to_every_job() {
+    let mut sched = common::scheduler().await;
+    let changes = Arc::new(Mutex::new(vec![]));
+    let changed = changes.clone();
+    sched.set_on_change(Box::new(move |job_id, change| {
+        changed.lock().unwrap().push((job_id, change));
+    }));
+    let (first, first_fires) = every_ten_seconds(&sched).await;
+    let (second, second_fires) = every_ten_seconds(&sched).await;
+    sched
+        .set_global_calendar(Some(window(CalendarAction::Skip)))
+        .await;
+    let changes = changes.lock().unwrap().clone();
+    assert!(changes.contains(&(first, JobChange::Schedule)));
+    assert!(changes.contains(&(second, JobChange::Schedule)));
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(45)).await;
+    assert_eq!(*first_fires.lock().unwrap(), vec![10, 40]);
+    assert_eq!(*second_fires.lock().unwrap(), vec![10, 40]);
+}
@@ -0,0 +1,54 @@
+// Not every test uses every helper
+#![allow(dead_code)]
+
+use chrono::{DateTime, TimeZone, Utc};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_cron_scheduler::{Job, JobContext, JobScheduler, MockClock};
+use uuid::Uuid;
+
+/// The seconds past `start` the runs of a job were due at
+pub type Fires = Arc<Mutex<Vec<i64>>>;
+
+/// When the mock clock of `scheduler` starts
+pub fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+}
+
+/// A scheduler going by a mock clock that starts at `start`, so it goes by tokio's paused time
+pub async fn scheduler() -> JobScheduler {
+    let mut sched = JobScheduler::new().await.unwrap();
+    sched.set_clock(Arc::new(MockClock::starting_at(start())));
+    sched
+}
+
+/// A job repeated `every`, counting its runs
+pub fn counted(every: Duration) -> (Job, Arc<AtomicUsize>) {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let counted = runs.clone();
+    let job = Job::new_repeated(every, move |_, _| {
+        counted.fetch_add(1, Ordering::SeqCst);
+    })
+    .unwrap();
+    (job, runs)
+}
+
+/// A job repeated `every`, keeping when its runs were due
+pub fn recorded(every: Duration) -> (Job, Fires) {
+    let fires = Fires::default();
+    let job = Job::new_repeated(every, recording(&fires)).unwrap();
+    (job, fires)
+}
+
+/// The run of a job keeping when it was due in `fires`
+pub fn recording(fires: &Fires) -> impl FnMut(Uuid, JobScheduler) + Send + Sync + 'static {
+    let seen = fires.clone();
+    move |_, _| {
+        if let Some(ctx) = JobContext::current() {
+            seen.lock()
+                .unwrap()
+                .push((ctx.scheduled - start()).num_seconds());
+        }
+    }
+}
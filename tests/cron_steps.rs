@@ -0,0 +1,104 @@
+use tokio_cron_scheduler::{parse_cron, validate_cron, Job, JobSchedulerError};
+
+// The fields of a schedule with how many values they have
+const FIELDS: [(&str, u32); 7] = [
+    ("seconds", 60),
+    ("minutes", 60),
+    ("hours", 24),
+    ("day of month", 31),
+    ("month", 12),
+    ("day of week", 7),
+    ("year", 130),
+];
+
+fn with_step(field: usize, value: &str) -> String {
+    let mut fields = ["*"; 7];
+    fields[field] = value;
+    fields.join(" ")
+}
+
+fn invalid_field(expr: &str) -> Option<String> {
+    match validate_cron(expr) {
+        Err(JobSchedulerError::InvalidCron(why)) => Some(why),
+        _ => None,
+    }
+}
+
+#[test]
+fn rejects_zero_steps_in_every_field() {
+    for (i, (name, _)) in FIELDS.iter().enumerate() {
+        let expr = with_step(i, "*/0");
+        let why = invalid_field(&expr).unwrap_or_else(|| panic!("{:?} is accepted", expr));
+        assert!(
+            why.contains(name),
+            "{:?} doesn't name the {} field",
+            why,
+            name
+        );
+        assert!(parse_cron(&expr).is_err());
+        assert!(Job::new(expr.as_str(), |_, _| {}).is_err());
+    }
+}
+
+#[test]
+fn rejects_steps_larger_than_their_field() {
+    for (i, (name, size)) in FIELDS.iter().enumerate() {
+        let expr = with_step(i, &format!("*/{}", size + 1));
+        let why = invalid_field(&expr).unwrap_or_else(|| panic!("{:?} is accepted", expr));
+        assert!(
+            why.contains(name),
+            "{:?} doesn't name the {} field",
+            why,
+            name
+        );
+        assert!(
+            why.contains(&size.to_string()),
+            "{:?} doesn't give the size",
+            why
+        );
+        match Job::new(expr.as_str(), |_, _| {}) {
+            Err(JobSchedulerError::InvalidCron(why)) => assert!(why.contains(name), "{:?}", why),
+            other => panic!("{:?} gives {:?}", expr, other.map(|_| ())),
+        }
+    }
+}
+
+#[test]
+fn rejects_steps_that_arent_numbers() {
+    for (i, (name, _)) in FIELDS.iter().enumerate() {
+        let expr = with_step(i, "*/x");
+        if let Some(why) = invalid_field(&expr) {
+            assert!(why.contains(name) || why.contains(&expr), "{:?}", why);
+        } else {
+            panic!("{:?} is accepted", expr);
+        }
+    }
+}
+
+#[test]
+fn takes_steps_within_their_field() {
+    let cases = [
+        ("*/1 * * * * *", true),
+        ("*/15 * * * * *", true),
+        ("*/60 * * * * *", true),
+        ("0 */30 * * * *", true),
+        ("0 0 */24 * * *", true),
+        ("0 0 0 */31 * *", true),
+        ("0 0 0 1 */12 *", true),
+        ("0 0 0 * * */7", true),
+        ("0 0 0 1 1 * */1", true),
+        ("0,30/10 * * * * *", true),
+        ("0 0 */25 * * *", false),
+        ("0 0 0 1 */13 *", false),
+        ("0 0 0 * * */8", false),
+    ];
+    for (expr, valid) in cases.iter() {
+        assert_eq!(
+            validate_cron(expr).is_ok(),
+            *valid,
+            "{:?}: {:?}",
+            expr,
+            validate_cron(expr)
+        );
+    }
+}
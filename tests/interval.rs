@@ -0,0 +1,42 @@
+mod common;
+
+use std::time::Duration;
+use tokio_cron_scheduler::{IntervalSchedule, Job};
+
+// The fires of a job on `schedule` in the first `secs` seconds
+async fn fires_of(schedule: IntervalSchedule, secs: u64) -> Vec<i64> {
+    let sched = common::scheduler().await;
+    let fires = common::Fires::default();
+    let job = Job::new_interval(schedule, common::recording(&fires)).unwrap();
+    sched.add(job).await.unwrap();
+    sched.start().await.unwrap();
+    tokio::time::sleep(Duration::from_secs(secs)).await;
+    let fires = fires.lock().unwrap().clone();
+    fires
+}
+
+fn after(secs: i64) -> chrono::DateTime<chrono::Utc> {
+    common::start() + chrono::Duration::seconds(secs)
+}
+
+#[tokio::test(start_paused = true)]
+async fn fires_from_the_start_until_the_end() {
+    let schedule = IntervalSchedule::every(Duration::from_secs(10))
+        .starting_at(after(15))
+        .ending_at(after(50));
+    assert_eq!(fires_of(schedule, 80).await, vec![15, 25, 35, 45]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn stops_after_the_max_runs() {
+    let schedule = IntervalSchedule::every(Duration::from_secs(10)).with_max_runs(3);
+    assert_eq!(fires_of(schedule, 60).await, vec![10, 20, 30]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn aligned_fires_on_the_multiples_of_the_interval() {
+    let schedule = IntervalSchedule::every(Duration::from_secs(15))
+        .aligned()
+        .starting_at(after(7));
+    assert_eq!(fires_of(schedule, 50).await, vec![15, 30, 45]);
+}
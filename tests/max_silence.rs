@@ -0,0 +1,66 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_cron_scheduler::Job;
+
+// A job that fires at noon, counting its runs
+fn at_noon() -> (Job, Arc<AtomicUsize>) {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let counted = runs.clone();
+    let job = Job::new("0 0 12 * * *", move |_, _| {
+        counted.fetch_add(1, Ordering::SeqCst);
+    })
+    .unwrap();
+    (job, runs)
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_silent_job_is_fired_anyway() {
+    let sched = common::scheduler().await;
+    let (job, runs) = at_noon();
+    let job_id = sched.add(job).await.unwrap();
+    sched
+        .set_max_silence(&job_id, Some(Duration::from_secs(30)))
+        .await
+        .unwrap();
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(29)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 0);
+    // At 30 seconds, and 30 seconds after that run
+    tokio::time::sleep(Duration::from_secs(36)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_job_on_schedule_is_not_fired_extra() {
+    let sched = common::scheduler().await;
+    let (job, runs) = common::counted(Duration::from_secs(10));
+    let job_id = sched.add(job).await.unwrap();
+    sched
+        .set_max_silence(&job_id, Some(Duration::from_secs(15)))
+        .await
+        .unwrap();
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(65)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 6);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_paused_job_is_not_fired() {
+    let mut sched = common::scheduler().await;
+    let (job, runs) = at_noon();
+    let job_id = sched.add(job).await.unwrap();
+    sched
+        .set_max_silence(&job_id, Some(Duration::from_secs(30)))
+        .await
+        .unwrap();
+    sched.pause(&job_id).await.unwrap();
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(65)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tokio_cron_scheduler::MisfirePolicy;
+
+// The runs of a job that fires every 10 seconds, 45 seconds in, when the scheduler only started
+// after 35 seconds so the fires at 10, 20 and 30 were missed
+async fn runs_after_missing_three(policy: MisfirePolicy) -> usize {
+    let sched = common::scheduler().await;
+    let (mut job, runs) = common::counted(Duration::from_secs(10));
+    job.set_misfire_policy(Some(policy)).unwrap();
+    sched.add(job).await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(35)).await;
+    sched.start().await.unwrap();
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    runs.load(Ordering::SeqCst)
+}
+
+#[tokio::test(start_paused = true)]
+async fn fire_once_runs_once_for_the_missed_fires() {
+    // Once for the missed fires, then at 40 seconds
+    assert_eq!(runs_after_missing_three(MisfirePolicy::FireOnce).await, 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn fire_all_runs_for_every_missed_fire() {
+    assert_eq!(runs_after_missing_three(MisfirePolicy::FireAll).await, 4);
+}
+
+#[tokio::test(start_paused = true)]
+async fn ignore_passes_the_missed_fires_over() {
+    assert_eq!(runs_after_missing_three(MisfirePolicy::Ignore).await, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn on_schedule_fires_are_not_missed() {
+    let sched = common::scheduler().await;
+    let (mut job, runs) = common::counted(Duration::from_secs(10));
+    job.set_misfire_policy(Some(MisfirePolicy::Ignore)).unwrap();
+    sched.add(job).await.unwrap();
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(35)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use chrono::NaiveTime;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_cron_scheduler::Calendar;
+
+#[tokio::test(start_paused = true)]
+async fn fires_passed_over_by_a_calendar_are_not_counted() {
+    let mut sched = common::scheduler().await;
+    let fired = Arc::new(AtomicUsize::new(0));
+    let counted = fired.clone();
+    sched.set_on_tick(Box::new(move |report| {
+        counted.fetch_add(report.fired, Ordering::SeqCst);
+    }));
+    let (job, runs) = common::counted(Duration::from_secs(10));
+    sched.add(job).await.unwrap();
+    let (excluded, _) = common::counted(Duration::from_secs(10));
+    let excluded = sched.add(excluded).await.unwrap();
+    let night = Calendar::new().exclude_window(
+        NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+        NaiveTime::from_hms_opt(1, 0, 0).unwrap(),
+    );
+    sched.set_calendar(&excluded, Some(night)).await.unwrap();
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(35)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+    // Both jobs were due three times, only the fires of the first one reached the runner
+    assert_eq!(fired.load(Ordering::SeqCst), 3);
+}
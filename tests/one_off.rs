@@ -0,0 +1,26 @@
+mod common;
+
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+#[tokio::test(start_paused = true)]
+async fn one_off_fires_on_top_of_the_schedule() {
+    let mut sched = common::scheduler().await;
+    let (job, runs) = common::counted(Duration::from_secs(10));
+    let job_id = sched.add(job).await.unwrap();
+    sched
+        .schedule_one_off(&job_id, common::start() + chrono::Duration::seconds(15))
+        .await
+        .unwrap();
+    sched.start().await.unwrap();
+
+    // The fire at 10 seconds and the one off at 15
+    tokio::time::sleep(Duration::from_secs(17)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+    assert_eq!(
+        sched.next_tick_for_job(job_id).await.unwrap(),
+        Some(common::start() + chrono::Duration::seconds(20))
+    );
+    tokio::time::sleep(Duration::from_secs(20)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 4);
+}
@@ -0,0 +1,160 @@
+use chrono::{TimeZone, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_cron_scheduler::{
+    EventStream, JobBuilder, JobNotification, JobScheduler, MockClock, TestScheduler,
+};
+use uuid::Uuid;
+
+async fn scheduler() -> JobScheduler {
+    let clock = MockClock::starting_at(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+    let mut sched = JobScheduler::new().await.unwrap();
+    sched.set_clock(Arc::new(clock));
+    sched
+}
+
+fn cron(schedule: &str) -> JobBuilder<Utc> {
+    JobBuilder::new()
+        .with_cron_job_type()
+        .with_schedule(schedule)
+        .unwrap()
+        .with_run_async(Box::new(|_, _| Box::pin(async move {})))
+}
+
+// The jobs fired, in the order they're fired
+async fn fired(events: &mut EventStream, count: usize) -> Vec<Uuid> {
+    let mut fired = vec![];
+    while fired.len() < count {
+        let event = tokio::time::timeout(Duration::from_secs(10), events.next())
+            .await
+            .expect("The jobs weren't all fired")
+            .unwrap();
+        if event.state == JobNotification::Scheduled {
+            fired.push(event.job_id);
+        }
+    }
+    fired
+}
+
+#[tokio::test(start_paused = true)]
+async fn most_overdue_jobs_are_fired_first() {
+    let sched = scheduler().await;
+    let at_ten = sched
+        .add(cron("0 10 0 * * *").build().unwrap())
+        .await
+        .unwrap();
+    let at_fifty = sched
+        .add(cron("0 50 0 * * *").build().unwrap())
+        .await
+        .unwrap();
+    let at_thirty = sched
+        .add(cron("0 30 0 * * *").build().unwrap())
+        .await
+        .unwrap();
+    let at_twenty = sched
+        .add(cron("0 20 0 * * *").build().unwrap())
+        .await
+        .unwrap();
+    let at_twenty_first = sched
+        .add(cron("0 20 0 * * *").with_priority(5).build().unwrap())
+        .await
+        .unwrap();
+
+    // Held up for an hour before the loop gets to the jobs
+    tokio::time::sleep(Duration::from_secs(3600)).await;
+    let mut events = sched.event_stream();
+    sched.start().await.unwrap();
+
+    assert_eq!(
+        fired(&mut events, 5).await,
+        vec![at_ten, at_twenty_first, at_twenty, at_thirty, at_fifty]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn jobs_due_at_the_same_second_are_fired_by_order_key() {
+    let sched = scheduler().await;
+    let last = sched
+        .add(cron("0 10 0 * * *").with_order_key(3).build().unwrap())
+        .await
+        .unwrap();
+    let first = sched
+        .add(cron("0 10 0 * * *").with_order_key(-1).build().unwrap())
+        .await
+        .unwrap();
+    let second = sched
+        .add(cron("0 10 0 * * *").with_order_key(2).build().unwrap())
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_secs(600)).await;
+    let mut events = sched.event_stream();
+    sched.start().await.unwrap();
+
+    assert_eq!(fired(&mut events, 3).await, vec![first, second, last]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn priority_goes_before_the_order_key() {
+    let sched = scheduler().await;
+    let low_key = sched
+        .add(cron("0 10 0 * * *").with_order_key(-5).build().unwrap())
+        .await
+        .unwrap();
+    let high_priority = sched
+        .add(
+            cron("0 10 0 * * *")
+                .with_order_key(5)
+                .with_priority(1)
+                .build()
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_secs(600)).await;
+    let mut events = sched.event_stream();
+    sched.start().await.unwrap();
+
+    assert_eq!(fired(&mut events, 2).await, vec![high_priority, low_key]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn the_same_order_key_is_fired_by_job_id() {
+    let sched = scheduler().await;
+    let mut ids = vec![];
+    for _ in 0..3 {
+        let id = sched
+            .add(cron("0 10 0 * * *").with_order_key(1).build().unwrap())
+            .await
+            .unwrap();
+        ids.push(id);
+    }
+    tokio::time::sleep(Duration::from_secs(600)).await;
+    let mut events = sched.event_stream();
+    sched.start().await.unwrap();
+
+    let fired = fired(&mut events, 3).await;
+    ids.sort();
+    assert_eq!(fired, ids);
+}
+
+#[tokio::test]
+async fn the_test_scheduler_runs_by_priority_as_the_scheduler_fires() {
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let mut sched = TestScheduler::new(start).await.unwrap();
+    let low_key = sched
+        .add(cron("0 10 0 * * *").with_order_key(-5).build().unwrap())
+        .unwrap();
+    let high_priority = sched
+        .add(
+            cron("0 10 0 * * *")
+                .with_order_key(5)
+                .with_priority(1)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+
+    let runs = sched.advance(Duration::from_secs(600)).await;
+    let ran = runs.iter().map(|run| run.job_id).collect::<Vec<_>>();
+    assert_eq!(ran, vec![high_priority, low_key]);
+}
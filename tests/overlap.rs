@@ -0,0 +1,88 @@
+use chrono::{TimeZone, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_cron_scheduler::{
+    JobBuilder, JobNotification, JobRunStats, JobScheduler, MockClock, OverlapPolicy, QueueDrop,
+    SkipReason,
+};
+
+// A job that fires every 10 seconds and runs for 24, with the stats of its runs 65 seconds in
+// so the runs went at 10, 20, 30, 40, 50 and 60 seconds
+async fn overlapping(policy: OverlapPolicy) -> (JobRunStats, Vec<JobNotification>) {
+    let clock = MockClock::starting_at(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+    let mut sched = JobScheduler::new().await.unwrap();
+    sched.set_clock(Arc::new(clock));
+    let job = JobBuilder::new()
+        .with_repeated_job_type()
+        .every_seconds(10)
+        .with_overlap_policy(policy)
+        .with_run_async(Box::new(|_, _| {
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_secs(24)).await;
+            })
+        }))
+        .build()
+        .unwrap();
+    let job_id = sched.add(job).await.unwrap();
+    let mut events = sched.event_stream();
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(65)).await;
+    let stats = sched.stats().await.job(&job_id).unwrap().clone();
+    let mut states = vec![];
+    while let Ok(Some(event)) = tokio::time::timeout(Duration::ZERO, events.next()).await {
+        states.push(event.state);
+    }
+    (stats, states)
+}
+
+fn count(states: &[JobNotification], state: JobNotification) -> usize {
+    states.iter().filter(|s| **s == state).count()
+}
+
+#[tokio::test(start_paused = true)]
+async fn allow_runs_every_fire() {
+    let (stats, _) = overlapping(OverlapPolicy::Allow).await;
+    assert_eq!(stats.runs, 6);
+    assert_eq!(stats.running, 2);
+    assert_eq!(stats.skipped, 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn skip_leaves_out_fires_while_running() {
+    let (stats, _) = overlapping(OverlapPolicy::Skip).await;
+    // Runs at 10 and 40, the fires at 20, 30, 50 and 60 are left out
+    assert_eq!(stats.runs, 2);
+    assert_eq!(stats.skipped_for(SkipReason::Overlap), 4);
+}
+
+#[tokio::test(start_paused = true)]
+async fn queue_keeps_up_to_its_depth() {
+    for drop in [QueueDrop::Oldest, QueueDrop::Newest] {
+        // Runs at 10, 34 and 58, one of the fires at 20 and 30 and one of 40 and 50 is dropped,
+        // the one at 60 waits
+        let (stats, _) = overlapping(OverlapPolicy::Queue { max_depth: 1, drop }).await;
+        assert_eq!(stats.runs, 3, "{:?}", drop);
+        assert_eq!(stats.running, 1, "{:?}", drop);
+        assert_eq!(stats.skipped_for(SkipReason::Overlap), 2, "{:?}", drop);
+    }
+
+    // Runs at 10, 34 and 58, only the fire at 50 finds the queue full
+    let (stats, _) = overlapping(OverlapPolicy::Queue {
+        max_depth: 2,
+        drop: QueueDrop::Oldest,
+    })
+    .await;
+    assert_eq!(stats.runs, 3);
+    assert_eq!(stats.skipped_for(SkipReason::Overlap), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancel_previous_cancels_the_run_still_going() {
+    let (stats, states) = overlapping(OverlapPolicy::CancelPrevious).await;
+    assert_eq!(stats.runs, 6);
+    assert_eq!(stats.running, 1);
+    assert_eq!(stats.failures, 0);
+    assert_eq!(count(&states, JobNotification::Cancelled), 5);
+    assert_eq!(count(&states, JobNotification::Failed), 0);
+}
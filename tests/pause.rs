@@ -0,0 +1,143 @@
+use chrono::{TimeZone, Utc};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_cron_scheduler::{Job, JobBuilder, JobContext, JobScheduler, MockClock};
+
+async fn scheduler(clock: &MockClock) -> JobScheduler {
+    let mut sched = JobScheduler::new().await.unwrap();
+    sched.set_clock(Arc::new(clock.clone()));
+    sched
+}
+
+#[tokio::test(start_paused = true)]
+async fn paused_job_stays_paused_after_a_restart() {
+    let clock = MockClock::starting_at(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+    let mut old = scheduler(&clock).await;
+    let job_id = old
+        .add(Job::new_repeated(Duration::from_secs(10), |_, _| {}).unwrap())
+        .await
+        .unwrap();
+    old.pause(&job_id).await.unwrap();
+    let state = old.export_state().await.unwrap();
+    assert!(state.jobs.iter().all(|data| data.paused && !data.stopped));
+
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counted = hits.clone();
+    let handler = Arc::new(move |_ctx: JobContext, _l: JobScheduler| {
+        counted.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async move {}) as _
+    });
+    let mut new = scheduler(&clock).await;
+    let ids = new
+        .import_state(state, |_data| Some(JobContext::handler(handler.clone())))
+        .await
+        .unwrap();
+    assert_eq!(ids, vec![job_id]);
+    new.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(60)).await;
+    assert_eq!(hits.load(Ordering::SeqCst), 0);
+
+    new.resume(&job_id).await.unwrap();
+    tokio::time::sleep(Duration::from_secs(25)).await;
+    assert!(hits.load(Ordering::SeqCst) > 0);
+}
+
+// A job that fires every 10 seconds, counting its runs
+fn counted(tag: &str) -> (Job, Arc<AtomicUsize>) {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counted = hits.clone();
+    let job = JobBuilder::new()
+        .with_repeated_job_type()
+        .every_seconds(10)
+        .with_tags([tag])
+        .with_run_async(Box::new(move |_, _| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move {})
+        }))
+        .build()
+        .unwrap();
+    (job, hits)
+}
+
+#[tokio::test(start_paused = true)]
+async fn paused_by_tag_until_resumed_by_tag() {
+    let clock = MockClock::starting_at(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+    let mut sched = scheduler(&clock).await;
+    let (job, hits) = counted("billing");
+    let job_id = sched.add(job).await.unwrap();
+    let (other_job, other) = counted("reports");
+    sched.add(other_job).await.unwrap();
+    assert_eq!(sched.pause_by_tag("billing").await.unwrap(), vec![job_id]);
+    let listed = sched.list_jobs(0).await.unwrap();
+    let status = listed.iter().find(|j| j.description.info.id == job_id);
+    assert!(status.is_some_and(|j| j.paused && j.description.paused));
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(35)).await;
+    assert_eq!(hits.load(Ordering::SeqCst), 0);
+    assert!(other.load(Ordering::SeqCst) > 0);
+    let next_fires = sched.jobs_by_next_fire(false).await.unwrap();
+    assert!(next_fires.iter().all(|(id, _)| *id != job_id));
+
+    assert_eq!(sched.resume_by_tag("billing").await.unwrap(), vec![job_id]);
+    tokio::time::sleep(Duration::from_secs(25)).await;
+    assert!(hits.load(Ordering::SeqCst) > 0);
+    assert!(sched.resume_by_tag("billing").await.unwrap().is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn resume_leaves_a_stopped_job_stopped() {
+    let clock = MockClock::starting_at(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+    let mut sched = scheduler(&clock).await;
+    let (mut job, hits) = counted("batch");
+    job.set_stop(true).unwrap();
+    let job_id = sched.add(job).await.unwrap();
+    sched.pause(&job_id).await.unwrap();
+    sched.start().await.unwrap();
+
+    sched.resume(&job_id).await.unwrap();
+    tokio::time::sleep(Duration::from_secs(40)).await;
+    assert_eq!(hits.load(Ordering::SeqCst), 0);
+    let state = sched.export_state().await.unwrap();
+    assert!(state.jobs.iter().all(|data| data.stopped && !data.paused));
+}
+
+#[tokio::test(start_paused = true)]
+async fn paused_until_fires_again_without_resume() {
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let clock = MockClock::starting_at(start);
+    let mut sched = scheduler(&clock).await;
+    let (job, hits) = counted("alerts");
+    let job_id = sched.add(job).await.unwrap();
+    sched
+        .pause_until(&job_id, start + chrono::Duration::seconds(35))
+        .await
+        .unwrap();
+    sched.start().await.unwrap();
+
+    // The fires at 10, 20 and 30 seconds are passed over
+    tokio::time::sleep(Duration::from_secs(34)).await;
+    assert_eq!(hits.load(Ordering::SeqCst), 0);
+    tokio::time::sleep(Duration::from_secs(11)).await;
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn paused_job_passes_its_one_offs_over() {
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let clock = MockClock::starting_at(start);
+    let mut sched = scheduler(&clock).await;
+    let (job, hits) = counted("exports");
+    let job_id = sched.add(job).await.unwrap();
+    sched.pause(&job_id).await.unwrap();
+    sched
+        .schedule_one_off(&job_id, start + chrono::Duration::seconds(5))
+        .await
+        .unwrap();
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(25)).await;
+    assert_eq!(hits.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,74 @@
+use chrono::{TimeZone, Utc};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_cron_scheduler::{JobBuilder, JobScheduler, MockClock};
+
+type Log = Arc<Mutex<Vec<&'static str>>>;
+
+async fn scheduler() -> JobScheduler {
+    let clock = MockClock::starting_at(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+    let mut sched = JobScheduler::new().await.unwrap();
+    sched.set_clock(Arc::new(clock));
+    sched
+}
+
+// A job that fires once a day at `second` past midnight and runs for `takes`, logging its name
+// when it starts
+async fn add(
+    sched: &JobScheduler,
+    log: &Log,
+    name: &'static str,
+    second: u32,
+    priority: i32,
+    takes: u64,
+) {
+    let logged = log.clone();
+    let job = JobBuilder::new()
+        .with_cron_job_type()
+        .with_schedule(format!("{} 0 0 * * *", second).as_str())
+        .unwrap()
+        .with_priority(priority)
+        .with_run_async(Box::new(move |_, _| {
+            let logged = logged.clone();
+            Box::pin(async move {
+                logged.lock().unwrap().push(name);
+                tokio::time::sleep(Duration::from_secs(takes)).await;
+            })
+        }))
+        .build()
+        .unwrap();
+    sched.add(job).await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn runs_waiting_on_the_limit_start_by_priority() {
+    let mut sched = scheduler().await;
+    sched.set_max_concurrency(Some(1));
+    let log = Log::default();
+    add(&sched, &log, "blocker", 1, 0, 10).await;
+    add(&sched, &log, "low", 2, -1, 1).await;
+    add(&sched, &log, "default", 3, 0, 1).await;
+    add(&sched, &log, "high", 4, 10, 1).await;
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    assert_eq!(*log.lock().unwrap(), vec!["blocker"]);
+    tokio::time::sleep(Duration::from_secs(20)).await;
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["blocker", "high", "default", "low"]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn runs_start_as_they_fire_without_a_limit() {
+    let sched = scheduler().await;
+    let log = Log::default();
+    add(&sched, &log, "blocker", 1, 0, 10).await;
+    add(&sched, &log, "low", 2, -1, 1).await;
+    add(&sched, &log, "high", 3, 10, 1).await;
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    assert_eq!(*log.lock().unwrap(), vec!["blocker", "low", "high"]);
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio_cron_scheduler::{JobBuilder, JobScheduler, OnRateLimited, RateLimiter, SkipReason};
+use uuid::Uuid;
+
+type Starts = Arc<Mutex<Vec<Duration>>>;
+
+// A job with `tag` that fires every second, keeping how long after `started` its runs started
+async fn tagged(sched: &JobScheduler, tag: &str, started: Instant, starts: &Starts) -> Uuid {
+    let seen = starts.clone();
+    let job = JobBuilder::new()
+        .with_repeated_job_type()
+        .every_seconds(1)
+        .with_tags([tag])
+        .with_run_async(Box::new(move |_, _| {
+            seen.lock().unwrap().push(started.elapsed());
+            Box::pin(async move {})
+        }))
+        .build()
+        .unwrap();
+    sched.add(job).await.unwrap()
+}
+
+fn gaps(starts: &Starts) -> Vec<Duration> {
+    let starts = starts.lock().unwrap();
+    starts.windows(2).map(|w| w[1] - w[0]).collect()
+}
+
+#[tokio::test(start_paused = true)]
+async fn skipped_runs_keep_the_tag_to_its_rate() {
+    let mut sched = common::scheduler().await;
+    let started = Instant::now();
+    let starts = Starts::default();
+    let first = tagged(&sched, "api", started, &starts).await;
+    let second = tagged(&sched, "api", started, &starts).await;
+    let limiter = RateLimiter::new(1, Duration::from_secs(5)).with_on_limited(OnRateLimited::Skip);
+    sched.set_tag_rate_limit("api", Some(limiter)).await;
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(20500)).await;
+    // Both jobs together ran at 1, 6, 11 and 16 seconds
+    assert_eq!(starts.lock().unwrap().len(), 4);
+    assert!(gaps(&starts)
+        .iter()
+        .all(|gap| *gap >= Duration::from_secs(5)));
+    let stats = sched.stats().await;
+    let skipped = [first, second]
+        .iter()
+        .map(|id| stats.job(id).unwrap().skipped_for(SkipReason::RateLimited))
+        .sum::<u64>();
+    assert_eq!(skipped, 40 - 4);
+}
+
+#[tokio::test(start_paused = true)]
+async fn delayed_runs_start_one_after_another_at_the_rate() {
+    let mut sched = common::scheduler().await;
+    let started = Instant::now();
+    let starts = Starts::default();
+    let job_id = tagged(&sched, "api", started, &starts).await;
+    let unlimited = Starts::default();
+    tagged(&sched, "reports", started, &unlimited).await;
+    sched
+        .set_tag_rate_limit("api", Some(RateLimiter::new(1, Duration::from_secs(5))))
+        .await;
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(20500)).await;
+    assert_eq!(starts.lock().unwrap().len(), 4);
+    assert!(gaps(&starts)
+        .iter()
+        .all(|gap| *gap >= Duration::from_secs(5) && *gap < Duration::from_secs(6)));
+    assert!(sched.stats().await.job(&job_id).unwrap().throttled > 0);
+    // The jobs without the tag don't wait
+    assert_eq!(unlimited.lock().unwrap().len(), 20);
+}
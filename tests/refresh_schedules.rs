@@ -0,0 +1,122 @@
+use chrono::{DateTime, TimeZone, Utc};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_cron_scheduler::{Clock, Job, JobContext, JobScheduler};
+
+// Goes by tokio's paused time, and can be set back or forward
+#[derive(Clone)]
+struct JumpingClock {
+    start: DateTime<Utc>,
+    started: tokio::time::Instant,
+    jumped: Arc<AtomicI64>,
+}
+
+impl JumpingClock {
+    fn starting_at(start: DateTime<Utc>) -> Self {
+        Self {
+            start,
+            started: tokio::time::Instant::now(),
+            jumped: Arc::new(AtomicI64::new(0)),
+        }
+    }
+
+    fn jump(&self, by: chrono::Duration) {
+        self.jumped.fetch_add(by.num_seconds(), Ordering::SeqCst);
+    }
+}
+
+impl Clock for JumpingClock {
+    fn now(&self) -> DateTime<Utc> {
+        let elapsed = chrono::Duration::from_std(self.started.elapsed()).unwrap();
+        let jumped = chrono::Duration::seconds(self.jumped.load(Ordering::SeqCst));
+        self.start + elapsed + jumped
+    }
+
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn refresh_fixes_the_next_fires_after_the_clock_jumped_back() {
+    let clock = JumpingClock::starting_at(Utc.with_ymd_and_hms(2024, 1, 2, 0, 30, 0).unwrap());
+    let mut sched = JobScheduler::new().await.unwrap();
+    sched.set_clock(Arc::new(clock.clone()));
+    let fired = Arc::new(Mutex::new(vec![]));
+    let seen = fired.clone();
+    let hourly = sched
+        .add(
+            Job::new_async("0 0 * * * *", move |_, _| {
+                if let Some(ctx) = JobContext::current() {
+                    seen.lock().unwrap().push(ctx.scheduled);
+                }
+                Box::pin(async move {})
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+    let every_minute = sched
+        .add(Job::new_repeated(Duration::from_secs(60), |_, _| {}).unwrap())
+        .await
+        .unwrap();
+    sched.start().await.unwrap();
+
+    clock.jump(-chrono::Duration::days(1));
+    // The next fires were worked out before the jump, they're a day off now
+    assert_eq!(
+        sched.next_tick_for_job(hourly).await.unwrap(),
+        Some(Utc.with_ymd_and_hms(2024, 1, 2, 1, 0, 0).unwrap())
+    );
+    assert_eq!(
+        sched.next_tick_for_job(every_minute).await.unwrap(),
+        Some(Utc.with_ymd_and_hms(2024, 1, 2, 0, 31, 0).unwrap())
+    );
+
+    assert_eq!(sched.refresh_schedules().await.unwrap(), 2);
+    let one = Utc.with_ymd_and_hms(2024, 1, 1, 1, 0, 0).unwrap();
+    assert_eq!(sched.next_tick_for_job(hourly).await.unwrap(), Some(one));
+    assert_eq!(
+        sched.next_tick_for_job(every_minute).await.unwrap(),
+        Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 31, 0).unwrap())
+    );
+    // Nothing more to do the second time
+    assert_eq!(sched.refresh_schedules().await.unwrap(), 0);
+
+    tokio::time::sleep(Duration::from_secs(31 * 60)).await;
+    assert_eq!(*fired.lock().unwrap(), vec![one]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn refresh_wakes_the_sleeping_loop() {
+    let clock = JumpingClock::starting_at(Utc.with_ymd_and_hms(2024, 1, 2, 0, 30, 0).unwrap());
+    let mut sched = JobScheduler::new().await.unwrap();
+    sched.set_clock(Arc::new(clock.clone()));
+    sched.set_max_sleep(Duration::from_secs(48 * 3600)).await;
+    let fired = Arc::new(Mutex::new(vec![]));
+    let seen = fired.clone();
+    sched
+        .add(
+            Job::new_async("0 0 * * * *", move |_, _| {
+                if let Some(ctx) = JobContext::current() {
+                    seen.lock().unwrap().push(ctx.scheduled);
+                }
+                Box::pin(async move {})
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+    clock.jump(-chrono::Duration::days(1));
+    // Asleep towards the next fire worked out before the jump, a day away now
+    sched.start().await.unwrap();
+    tokio::time::sleep(Duration::from_secs(60)).await;
+
+    assert_eq!(sched.refresh_schedules().await.unwrap(), 1);
+    tokio::time::sleep(Duration::from_secs(30 * 60)).await;
+    let one = Utc.with_ymd_and_hms(2024, 1, 1, 1, 0, 0).unwrap();
+    assert_eq!(*fired.lock().unwrap(), vec![one]);
+}
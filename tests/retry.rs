@@ -0,0 +1,123 @@
+use chrono::{DateTime, TimeZone, Utc};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_cron_scheduler::{
+    Backoff, Job, JobContext, JobError, JobNotification, JobScheduler, MockClock, RetryPolicy,
+};
+use uuid::Uuid;
+
+type Attempts = Arc<Mutex<Vec<(u32, DateTime<Utc>)>>>;
+
+// An hourly job that fails its first `failing` attempts, with the attempts it made
+async fn failing(failing: u32, policy: RetryPolicy) -> (JobScheduler, Uuid, Attempts) {
+    let clock = MockClock::starting_at(Utc.with_ymd_and_hms(2024, 1, 1, 0, 59, 0).unwrap());
+    let mut sched = JobScheduler::new().await.unwrap();
+    sched.set_clock(Arc::new(clock));
+    let attempts = Attempts::default();
+    let made = attempts.clone();
+    let job = Job::new_async_fallible("0 0 * * * *", move |job_id, _| {
+        let made = made.clone();
+        Box::pin(async move {
+            let context = JobContext::current().unwrap();
+            made.lock()
+                .unwrap()
+                .push((context.attempt, context.scheduled));
+            match context.attempt <= failing {
+                true => Err(JobError::new(job_id, context.attempt, "failed")),
+                false => Ok(()),
+            }
+        })
+    })
+    .unwrap();
+    let job_id = sched.add(job).await.unwrap();
+    sched.set_retry_policy(&job_id, Some(policy)).await.unwrap();
+    (sched, job_id, attempts)
+}
+
+fn at(minute: u32, second: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 1, 1, minute, second).unwrap()
+}
+
+#[tokio::test(start_paused = true)]
+async fn retries_with_exponential_backoff_until_it_goes_well() {
+    let policy = RetryPolicy {
+        max_attempts: 5,
+        backoff: Backoff::Exponential {
+            initial: Duration::from_secs(10),
+            max: Duration::from_secs(30),
+        },
+        jitter: Duration::ZERO,
+    };
+    let (sched, _, attempts) = failing(3, policy).await;
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(300)).await;
+    // 10, 20 and then 30 seconds after the failed attempts, as 40 is over the max
+    assert_eq!(
+        *attempts.lock().unwrap(),
+        vec![(1, at(0, 0)), (2, at(0, 10)), (3, at(0, 30)), (4, at(1, 0))]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn gives_up_after_max_attempts() {
+    let policy = RetryPolicy {
+        max_attempts: 3,
+        backoff: Backoff::Fixed(Duration::from_secs(10)),
+        jitter: Duration::ZERO,
+    };
+    let (sched, job_id, attempts) = failing(u32::MAX, policy).await;
+    let failures = Arc::new(Mutex::new(vec![]));
+    let seen = failures.clone();
+    sched
+        .set_job_failure_handler(Box::new(move |failure: JobError| {
+            seen.lock().unwrap().push(failure.attempt);
+            Box::pin(async move {})
+        }))
+        .await;
+    let mut events = sched.event_stream();
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(300)).await;
+    assert_eq!(
+        *attempts.lock().unwrap(),
+        vec![(1, at(0, 0)), (2, at(0, 10)), (3, at(0, 20))]
+    );
+    assert_eq!(*failures.lock().unwrap(), vec![1, 2, 3]);
+    let mut states = vec![];
+    while let Ok(Some(event)) = tokio::time::timeout(Duration::ZERO, events.next()).await {
+        if event.job_id == job_id {
+            states.push(event.state);
+        }
+    }
+    let count = |state| states.iter().filter(|s| **s == state).count();
+    assert_eq!(count(JobNotification::Retrying), 2);
+    assert_eq!(count(JobNotification::Failed), 1);
+}
+
+#[test]
+fn backoff_doubles_up_to_the_max_with_jitter_on_top() {
+    let policy = RetryPolicy {
+        max_attempts: 10,
+        backoff: Backoff::Exponential {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(5),
+        },
+        jitter: Duration::ZERO,
+    };
+    let delays = (1..=5)
+        .map(|attempt| policy.delay(attempt))
+        .collect::<Vec<_>>();
+    assert_eq!(delays, [1, 2, 4, 5, 5].map(Duration::from_secs).to_vec());
+    assert_eq!(policy.delay(u32::MAX), Duration::from_secs(5));
+
+    let jittered = RetryPolicy {
+        jitter: Duration::from_millis(500),
+        ..policy
+    };
+    for attempt in 1..=5 {
+        let delay = jittered.delay(attempt);
+        assert!(delay >= policy.delay(attempt));
+        assert!(delay <= policy.delay(attempt) + Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,52 @@
+use std::time::Duration;
+use tokio_cron_scheduler::{Job, JobScheduler};
+use uuid::Uuid;
+
+///
+/// Start a scheduler on a runtime of its own, shut the runtime down while a run is going and hand
+/// the scheduler back
+fn shut_down_mid_run() -> (JobScheduler, Uuid) {
+    std::thread::spawn(|| {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .enable_all()
+            .build()
+            .unwrap();
+        let (sched, job_id) = runtime.block_on(async {
+            let sched = JobScheduler::new().await.unwrap();
+            let job = Job::new_repeated_async(Duration::from_secs(1), |_, _| {
+                Box::pin(async move {
+                    tokio::time::sleep(Duration::from_secs(3600)).await;
+                })
+            })
+            .unwrap();
+            let job_id = sched.add(job).await.unwrap();
+            sched.start().await.unwrap();
+            tokio::time::timeout(Duration::from_secs(10), async {
+                while sched.stats().await.running == 0 {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .expect("the job never started");
+            (sched, job_id)
+        });
+        runtime.shutdown_timeout(Duration::from_millis(100));
+        (sched, job_id)
+    })
+    .join()
+    .expect("shutting the runtime down panicked")
+}
+
+#[tokio::test]
+async fn runs_dropped_by_runtime_shutdown_are_not_left_running() {
+    let (sched, job_id) = shut_down_mid_run();
+
+    let stats = sched.stats().await;
+    assert_eq!(stats.running, 0);
+    let job = stats.job(&job_id).unwrap();
+    assert_eq!(job.running, 0);
+    assert_eq!(job.runs, 1);
+    assert_eq!(job.failures, 1);
+    assert!(job.last_failed);
+}
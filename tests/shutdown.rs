@@ -0,0 +1,172 @@
+use chrono::{TimeZone, Utc};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_cron_scheduler::{Job, JobScheduler, MockClock};
+use uuid::Uuid;
+
+async fn counting_scheduler() -> (JobScheduler, Arc<AtomicUsize>) {
+    let clock = MockClock::starting_at(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+    let mut sched = JobScheduler::new().await.unwrap();
+    sched.set_clock(Arc::new(clock));
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counted = hits.clone();
+    sched
+        .add(
+            Job::new_repeated(Duration::from_secs(1), move |_, _| {
+                counted.fetch_add(1, Ordering::SeqCst);
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+    (sched, hits)
+}
+
+async fn shuts_down_with<F>(handler: F)
+where
+    F: FnMut() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync + 'static,
+{
+    let (mut sched, hits) = counting_scheduler().await;
+    sched.set_shutdown_handler(Box::new(handler));
+    sched.start().await.unwrap();
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    assert!(hits.load(Ordering::SeqCst) > 0);
+
+    sched.shutdown().await.unwrap();
+    assert!(sched.is_shutting_down());
+    let after_shutdown = hits.load(Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    assert_eq!(hits.load(Ordering::SeqCst), after_shutdown);
+
+    // The handler's lock isn't left poisoned
+    sched.set_shutdown_handler(Box::new(|| Box::pin(async move {})));
+    sched.remove_shutdown_handler();
+}
+
+#[tokio::test(start_paused = true)]
+async fn shutdown_completes_when_the_handler_panics() {
+    shuts_down_with(|| panic!("shutdown handler")).await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn shutdown_completes_when_the_handler_future_panics() {
+    shuts_down_with(|| {
+        Box::pin(async move {
+            panic!("shutdown handler future");
+        })
+    })
+    .await;
+}
+
+type Log = Arc<Mutex<Vec<&'static str>>>;
+
+// A job that fires every second and runs for `takes`, stopping early when it's told the
+// scheduler shuts down and `listens`
+async fn slow_scheduler(
+    takes: Duration,
+    listens: bool,
+) -> (JobScheduler, Uuid, Arc<AtomicUsize>, Log) {
+    let clock = MockClock::starting_at(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+    let mut sched = JobScheduler::new().await.unwrap();
+    sched.set_clock(Arc::new(clock));
+    let started = Arc::new(AtomicUsize::new(0));
+    let log = Log::default();
+    let (counted, logged) = (started.clone(), log.clone());
+    let job = Job::new_repeated_async(Duration::from_secs(1), move |_, sched| {
+        let (counted, logged) = (counted.clone(), logged.clone());
+        Box::pin(async move {
+            counted.fetch_add(1, Ordering::SeqCst);
+            let mut shutting_down = sched.shutdown_watch();
+            let stop = shutting_down.wait_for(|shutting_down| *shutting_down && listens);
+            let _ = tokio::time::timeout(takes, stop).await;
+            logged.lock().unwrap().push("run done");
+        })
+    })
+    .unwrap();
+    let job_id = sched.add(job).await.unwrap();
+    let logged = log.clone();
+    sched.set_shutdown_handler(Box::new(move || {
+        logged.lock().unwrap().push("handler");
+        Box::pin(async move {})
+    }));
+    (sched, job_id, started, log)
+}
+
+#[tokio::test(start_paused = true)]
+async fn graceful_shutdown_waits_for_the_runs_going() {
+    let (mut sched, _, started, log) = slow_scheduler(Duration::from_secs(3), false).await;
+    sched.start().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert_eq!(started.load(Ordering::SeqCst), 1);
+
+    assert_eq!(
+        sched
+            .shutdown_graceful(Duration::from_secs(10))
+            .await
+            .unwrap(),
+        0
+    );
+    // No more fires while waiting, the handler goes last
+    assert_eq!(started.load(Ordering::SeqCst), 1);
+    assert_eq!(*log.lock().unwrap(), vec!["run done", "handler"]);
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    assert_eq!(started.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn graceful_shutdown_gives_up_after_the_timeout() {
+    let (mut sched, _, started, log) = slow_scheduler(Duration::from_secs(60), false).await;
+    sched.start().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+
+    let waited = tokio::time::Instant::now();
+    assert_eq!(
+        sched
+            .shutdown_graceful(Duration::from_secs(5))
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(waited.elapsed() >= Duration::from_secs(5));
+    assert!(waited.elapsed() < Duration::from_secs(6));
+    assert_eq!(started.load(Ordering::SeqCst), 1);
+    assert_eq!(*log.lock().unwrap(), vec!["handler"]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn graceful_shutdown_lets_runs_stop_early() {
+    let (mut sched, _, _, log) = slow_scheduler(Duration::from_secs(60), true).await;
+    sched.start().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+
+    let waited = tokio::time::Instant::now();
+    assert_eq!(
+        sched
+            .shutdown_graceful(Duration::from_secs(30))
+            .await
+            .unwrap(),
+        0
+    );
+    assert!(waited.elapsed() < Duration::from_secs(1));
+    assert_eq!(*log.lock().unwrap(), vec!["run done", "handler"]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn stop_graceful_waits_for_the_run_going() {
+    let (mut sched, job_id, started, log) = slow_scheduler(Duration::from_secs(3), false).await;
+    sched.start().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert_eq!(started.load(Ordering::SeqCst), 1);
+
+    let waited = tokio::time::Instant::now();
+    assert!(sched.stop_graceful(&job_id).await.unwrap());
+    // The run that fired at 1 second is done at 4, the fires while waiting didn't start a run
+    assert!(waited.elapsed() >= Duration::from_millis(2500));
+    assert_eq!(started.load(Ordering::SeqCst), 1);
+    assert_eq!(*log.lock().unwrap(), vec!["run done"]);
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    assert_eq!(started.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,66 @@
+use chrono::{DateTime, TimeZone, Utc};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_cron_scheduler::{Job, JobBuilder, JobScheduler, MockClock};
+use uuid::Uuid;
+
+fn every_minute(job_id: Uuid) -> Job {
+    JobBuilder::new()
+        .with_id(job_id)
+        .with_cron_job_type()
+        .with_schedule("0 * * * * *")
+        .unwrap()
+        .with_stable_jitter(Duration::from_secs(30))
+        .with_run_async(Box::new(|_, _| Box::pin(async move {})))
+        .build()
+        .unwrap()
+}
+
+async fn first_fire(start: DateTime<Utc>, job_id: Uuid) -> DateTime<Utc> {
+    let mut sched = JobScheduler::new().await.unwrap();
+    sched.set_clock(Arc::new(MockClock::starting_at(start)));
+    sched.add(every_minute(job_id)).await.unwrap();
+    let next = sched.next_tick_for_job(job_id).await.unwrap().unwrap();
+    sched.shutdown().await.unwrap();
+    next
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_job_keeps_its_offset_in_a_new_scheduler() {
+    // Past the window, so the first fire is in the next minute whatever the offset
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 40).unwrap();
+    let minute = Utc.with_ymd_and_hms(2024, 1, 1, 0, 1, 0).unwrap();
+    let job_id = Uuid::new_v4();
+
+    let first = first_fire(start, job_id).await;
+    assert!(first >= minute && first < minute + chrono::Duration::seconds(30));
+    for _ in 0..3 {
+        assert_eq!(first_fire(start, job_id).await, first);
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_job_keeps_its_offset_from_fire_to_fire() {
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 40).unwrap();
+    let job_id = Uuid::new_v4();
+    let mut sched = JobScheduler::new().await.unwrap();
+    sched.set_clock(Arc::new(MockClock::starting_at(start)));
+    sched.add(every_minute(job_id)).await.unwrap();
+    let first = sched.next_tick_for_job(job_id).await.unwrap().unwrap();
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(60)).await;
+    let second = sched.next_tick_for_job(job_id).await.unwrap().unwrap();
+    assert_eq!(second, first + chrono::Duration::seconds(60));
+}
+
+#[tokio::test(start_paused = true)]
+async fn jobs_are_spread_over_the_window() {
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 40).unwrap();
+    let mut offsets = HashSet::new();
+    for _ in 0..10 {
+        offsets.insert(first_fire(start, Uuid::new_v4()).await);
+    }
+    assert!(offsets.len() > 1, "{:?}", offsets);
+}
@@ -0,0 +1,55 @@
+use chrono::{Duration as ChronoDuration, TimeZone, Utc};
+use std::time::Duration;
+use tokio_cron_scheduler::{Job, JobBuilder, TestScheduler};
+
+fn every_minute(start_immediately: Option<bool>) -> Job {
+    let builder = JobBuilder::new().with_repeated_job_type().every_seconds(60);
+    let builder = match start_immediately {
+        Some(start_immediately) => builder.with_start_immediately(start_immediately),
+        None => builder,
+    };
+    builder
+        .with_run_async(Box::new(|_, _| Box::pin(async move {})))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn starts_immediately_then_every_interval() {
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let mut sched = TestScheduler::new(start).await.unwrap();
+    let job_id = sched.add(every_minute(Some(true))).unwrap();
+    assert_eq!(sched.next_tick(&job_id), Some(start));
+
+    let runs = sched.advance(Duration::ZERO).await;
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].scheduled, start);
+
+    assert!(sched.advance(Duration::from_secs(59)).await.is_empty());
+    let runs = sched.advance(Duration::from_secs(1)).await;
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].scheduled, start + ChronoDuration::seconds(60));
+}
+
+#[tokio::test]
+async fn waits_one_interval_by_default() {
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    for start_immediately in [None, Some(false)] {
+        let mut sched = TestScheduler::new(start).await.unwrap();
+        let job_id = sched.add(every_minute(start_immediately)).unwrap();
+        assert_eq!(
+            sched.next_tick(&job_id),
+            Some(start + ChronoDuration::seconds(60))
+        );
+
+        assert!(sched.advance(Duration::ZERO).await.is_empty());
+        assert!(sched.advance(Duration::from_secs(59)).await.is_empty());
+        let runs = sched.advance(Duration::from_secs(1)).await;
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].scheduled, start + ChronoDuration::seconds(60));
+        assert_eq!(
+            sched.next_tick(&job_id),
+            Some(start + ChronoDuration::seconds(120))
+        );
+    }
+}
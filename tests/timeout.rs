@@ -0,0 +1,77 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_cron_scheduler::{Job, JobNotification, JobScheduler, JobSchedulerError};
+use uuid::Uuid;
+
+// A job that fires every 10 seconds, runs for `takes` and may run for `timeout`, counting the
+// runs that got to the end
+async fn sleeping(
+    sched: &JobScheduler,
+    takes: Duration,
+    timeout: Duration,
+) -> (Uuid, Arc<AtomicUsize>) {
+    let finished = Arc::new(AtomicUsize::new(0));
+    let counted = finished.clone();
+    let mut job = Job::new_repeated_async(Duration::from_secs(10), move |_, _| {
+        let counted = counted.clone();
+        Box::pin(async move {
+            tokio::time::sleep(takes).await;
+            counted.fetch_add(1, Ordering::SeqCst);
+        })
+    })
+    .unwrap();
+    job.set_timeout(Some(timeout)).unwrap();
+    (sched.add(job).await.unwrap(), finished)
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_run_over_its_timeout_is_cancelled_and_fails() {
+    let sched = common::scheduler().await;
+    let errors = Arc::new(Mutex::new(vec![]));
+    let seen = errors.clone();
+    sched
+        .set_job_failure_handler(Box::new(move |failure| {
+            let timed_out = matches!(
+                failure.error.downcast_ref(),
+                Some(JobSchedulerError::RunTimedOut(timeout)) if *timeout == Duration::from_secs(5)
+            );
+            seen.lock().unwrap().push(timed_out);
+            Box::pin(async move {})
+        }))
+        .await;
+    let (job_id, finished) =
+        sleeping(&sched, Duration::from_secs(30), Duration::from_secs(5)).await;
+    let mut events = sched.event_stream();
+    sched.start().await.unwrap();
+
+    // The run that fired at 10 seconds is cancelled at 15
+    tokio::time::sleep(Duration::from_secs(17)).await;
+    assert_eq!(*errors.lock().unwrap(), vec![true]);
+    let stats = sched.stats().await.job(&job_id).unwrap().clone();
+    assert_eq!((stats.runs, stats.failures, stats.running), (1, 1, 0));
+    let mut timed_out = 0;
+    while let Ok(Some(event)) = tokio::time::timeout(Duration::ZERO, events.next()).await {
+        if event.state == JobNotification::TimedOut {
+            assert!(event.error.is_some());
+            timed_out += 1;
+        }
+    }
+    assert_eq!(timed_out, 1);
+    tokio::time::sleep(Duration::from_secs(30)).await;
+    assert_eq!(finished.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_run_within_its_timeout_is_done() {
+    let sched = common::scheduler().await;
+    let (job_id, finished) = sleeping(&sched, Duration::from_secs(2), Duration::from_secs(5)).await;
+    sched.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(25)).await;
+    assert_eq!(finished.load(Ordering::SeqCst), 2);
+    let stats = sched.stats().await.job(&job_id).unwrap().clone();
+    assert_eq!((stats.runs, stats.failures), (2, 0));
+}
@@ -0,0 +1,80 @@
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_cron_scheduler::{Job, JobContext, JobScheduler, MockClock};
+
+async fn noon_job(clock: &MockClock) -> (JobScheduler, uuid::Uuid, Arc<Mutex<Vec<DateTime<Utc>>>>) {
+    let mut sched = JobScheduler::new().await.unwrap();
+    sched.set_clock(Arc::new(clock.clone()));
+    let fired = Arc::new(Mutex::new(vec![]));
+    let seen = fired.clone();
+    let job_id = sched
+        .add(
+            Job::new_async("0 0 12 * * *", move |_, _| {
+                if let Some(ctx) = JobContext::current() {
+                    seen.lock().unwrap().push(ctx.scheduled);
+                }
+                Box::pin(async move {})
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+    (sched, job_id, fired)
+}
+
+#[tokio::test(start_paused = true)]
+async fn noon_job_fires_at_noon_in_the_new_timezone() {
+    let clock = MockClock::starting_at(Utc.with_ymd_and_hms(2024, 1, 1, 8, 0, 0).unwrap());
+    let (mut sched, job_id, fired) = noon_job(&clock).await;
+    assert_eq!(
+        sched.next_tick_for_job(job_id).await.unwrap(),
+        Some(Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap())
+    );
+
+    sched
+        .set_timezone(FixedOffset::east_opt(2 * 3600).unwrap())
+        .await
+        .unwrap();
+    let noon = Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap();
+    assert_eq!(sched.next_tick_for_job(job_id).await.unwrap(), Some(noon));
+
+    sched.start().await.unwrap();
+    tokio::time::sleep(Duration::from_secs(3 * 3600)).await;
+    assert_eq!(*fired.lock().unwrap(), vec![noon]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn noon_job_follows_a_named_timezone() {
+    let clock = MockClock::starting_at(Utc.with_ymd_and_hms(2024, 1, 1, 8, 0, 0).unwrap());
+    let (mut sched, job_id, fired) = noon_job(&clock).await;
+
+    sched
+        .set_timezone(chrono_tz::America::New_York)
+        .await
+        .unwrap();
+    let noon = Utc.with_ymd_and_hms(2024, 1, 1, 17, 0, 0).unwrap();
+    assert_eq!(sched.next_tick_for_job(job_id).await.unwrap(), Some(noon));
+
+    sched.start().await.unwrap();
+    tokio::time::sleep(Duration::from_secs(10 * 3600)).await;
+    assert_eq!(*fired.lock().unwrap(), vec![noon]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_new_timezone_wakes_the_sleeping_loop() {
+    let clock = MockClock::starting_at(Utc.with_ymd_and_hms(2024, 1, 1, 8, 0, 0).unwrap());
+    let (mut sched, _, fired) = noon_job(&clock).await;
+    sched.set_max_sleep(Duration::from_secs(24 * 3600)).await;
+    sched.start().await.unwrap();
+    // Asleep towards noon UTC
+    tokio::time::sleep(Duration::from_secs(60)).await;
+
+    sched
+        .set_timezone(FixedOffset::east_opt(2 * 3600).unwrap())
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_secs(2 * 3600)).await;
+    let noon = Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap();
+    assert_eq!(*fired.lock().unwrap(), vec![noon]);
+}
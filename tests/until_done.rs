@@ -0,0 +1,53 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_cron_scheduler::{Job, JobError, JobScheduler};
+
+// A job that fires every 10 seconds and fails until its `succeeds_on`th run, counting its runs
+async fn failing_until(succeeds_on: usize, window: Duration) -> (JobScheduler, Arc<AtomicUsize>) {
+    let sched = common::scheduler().await;
+    let runs = Arc::new(AtomicUsize::new(0));
+    let counted = runs.clone();
+    let job = Job::new_async_fallible("*/10 * * * * *", move |job_id, _| {
+        let run = counted.fetch_add(1, Ordering::SeqCst) + 1;
+        Box::pin(async move {
+            if run < succeeds_on {
+                return Err(JobError::new(job_id, 1, "not yet"));
+            }
+            Ok(())
+        })
+    })
+    .unwrap();
+    sched.add_until_done(job, window).await.unwrap();
+    sched.start().await.unwrap();
+    (sched, runs)
+}
+
+#[tokio::test(start_paused = true)]
+async fn removed_after_the_first_run_that_is_done() {
+    let (sched, runs) = failing_until(3, Duration::from_secs(3600)).await;
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    assert_eq!(sched.list_jobs(0).await.unwrap().len(), 1);
+
+    // Ran right away, then at 10 and 20 seconds
+    tokio::time::sleep(Duration::from_secs(20)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+    assert!(sched.list_jobs(0).await.unwrap().is_empty());
+    tokio::time::sleep(Duration::from_secs(30)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn removed_when_the_window_is_over() {
+    let (sched, runs) = failing_until(usize::MAX, Duration::from_secs(25)).await;
+
+    // The fire at 30 seconds would be past the window, the run at 20 is the last one
+    tokio::time::sleep(Duration::from_secs(25)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+    assert!(sched.list_jobs(0).await.unwrap().is_empty());
+    tokio::time::sleep(Duration::from_secs(30)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+}
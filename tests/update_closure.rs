@@ -0,0 +1,73 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_cron_scheduler::{Job, JobSchedulerError};
+
+#[tokio::test(start_paused = true)]
+async fn sync_code_is_replaced_with_sync_code() {
+    let sched = common::scheduler().await;
+    let job_id = sched
+        .add(Job::new_repeated(Duration::from_secs(10), |_, _| {}).unwrap())
+        .await
+        .unwrap();
+
+    let err = sched
+        .update_closure_async(&job_id, |_, _| Box::pin(async move {}))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, JobSchedulerError::ClosureKindMismatch(_)));
+
+    let runs = Arc::new(AtomicUsize::new(0));
+    let counted = runs.clone();
+    sched
+        .update_closure(&job_id, move |_, _| {
+            counted.fetch_add(1, Ordering::SeqCst);
+        })
+        .await
+        .unwrap();
+    sched.start().await.unwrap();
+    tokio::time::sleep(Duration::from_secs(25)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn async_code_is_replaced_with_async_code() {
+    let sched = common::scheduler().await;
+    let job_id = sched
+        .add(
+            Job::new_repeated_async(Duration::from_secs(10), |_, _| Box::pin(async move {}))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let err = sched.update_closure(&job_id, |_, _| {}).await.unwrap_err();
+    assert!(matches!(err, JobSchedulerError::ClosureKindMismatch(_)));
+
+    let runs = Arc::new(AtomicUsize::new(0));
+    let counted = runs.clone();
+    sched
+        .update_closure_async(&job_id, move |_, _| {
+            let counted = counted.clone();
+            Box::pin(async move {
+                counted.fetch_add(1, Ordering::SeqCst);
+            })
+        })
+        .await
+        .unwrap();
+    sched.start().await.unwrap();
+    tokio::time::sleep(Duration::from_secs(25)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn an_unknown_job_is_not_found() {
+    let sched = common::scheduler().await;
+    let err = sched
+        .update_closure(&uuid::Uuid::new_v4(), |_, _| {})
+        .await
+        .unwrap_err();
+    assert!(matches!(err, JobSchedulerError::JobNotFound));
+}